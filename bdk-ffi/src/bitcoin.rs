@@ -2,7 +2,7 @@ use crate::error::{
     AddressParseError, Bip32Error, ExtractTxError, FeeRateError, FromScriptError, HashParseError,
     PsbtError, PsbtParseError, TransactionError,
 };
-use crate::error::{ParseAmountError, PsbtFinalizeError};
+use crate::error::{ParseAmountError, PaymentUriError, PsbtFinalizeError};
 use crate::keys::DerivationPath;
 
 use crate::{impl_from_core_type, impl_hash_like, impl_into_core_type};
@@ -24,6 +24,7 @@ use bdk_wallet::bitcoin::taproot::NodeInfo as BdkNodeInfo;
 use bdk_wallet::bitcoin::taproot::TapTree as BdkTapTree;
 use bdk_wallet::bitcoin::Amount as BdkAmount;
 use bdk_wallet::bitcoin::BlockHash as BitcoinBlockHash;
+use bdk_wallet::bitcoin::Denomination;
 use bdk_wallet::bitcoin::FeeRate as BdkFeeRate;
 use bdk_wallet::bitcoin::OutPoint as BdkOutPoint;
 use bdk_wallet::bitcoin::Psbt as BdkPsbt;
@@ -430,6 +431,118 @@ impl Display for Address {
 impl_from_core_type!(BdkAddress, Address);
 impl_into_core_type!(Address, BdkAddress);
 
+/// A BIP-21 payment request such as `bitcoin:<address>?amount=0.001&label=Shop`.
+#[derive(Debug, uniffi::Object)]
+pub struct PaymentUri {
+    address: BdkAddress<NetworkChecked>,
+    amount: Option<BdkAmount>,
+    label: Option<String>,
+    message: Option<String>,
+}
+
+#[uniffi::export]
+impl PaymentUri {
+    /// Parse a BIP-21 URI whose address is valid for the given network.
+    ///
+    /// Unknown parameters are ignored, unless they start with `req-` which marks them as required.
+    #[uniffi::constructor]
+    pub fn new(uri: String, network: Network) -> Result<Self, PaymentUriError> {
+        let (scheme, rest) = uri.split_once(':').ok_or(PaymentUriError::InvalidScheme)?;
+        if !scheme.eq_ignore_ascii_case("bitcoin") {
+            return Err(PaymentUriError::InvalidScheme);
+        }
+        let (address, query) = rest.split_once('?').unwrap_or((rest, ""));
+        let address = address
+            .parse::<BdkAddress<NetworkUnchecked>>()
+            .and_then(|address| address.require_network(network))
+            .map_err(|error| PaymentUriError::InvalidAddress {
+                error_message: error.to_string(),
+            })?;
+
+        let mut payment_uri = PaymentUri {
+            address,
+            amount: None,
+            label: None,
+            message: None,
+        };
+        for parameter in query.split('&').filter(|parameter| !parameter.is_empty()) {
+            let (key, value) = parameter.split_once('=').unwrap_or((parameter, ""));
+            let value = percent_decode(value).ok_or_else(|| PaymentUriError::InvalidEncoding {
+                parameter: key.to_string(),
+            })?;
+            let duplicate = PaymentUriError::DuplicateParameter {
+                parameter: key.to_string(),
+            };
+            match key.to_ascii_lowercase().as_str() {
+                "amount" => {
+                    let amount =
+                        BdkAmount::from_str_in(&value, Denomination::Bitcoin).map_err(|error| {
+                            PaymentUriError::InvalidAmount {
+                                error_message: error.to_string(),
+                            }
+                        })?;
+                    if payment_uri.amount.replace(amount).is_some() {
+                        return Err(duplicate);
+                    }
+                }
+                "label" => {
+                    if payment_uri.label.replace(value).is_some() {
+                        return Err(duplicate);
+                    }
+                }
+                "message" => {
+                    if payment_uri.message.replace(value).is_some() {
+                        return Err(duplicate);
+                    }
+                }
+                key if key.starts_with("req-") => {
+                    return Err(PaymentUriError::UnsupportedRequiredParameter {
+                        parameter: key.to_string(),
+                    });
+                }
+                _ => {}
+            }
+        }
+        Ok(payment_uri)
+    }
+
+    /// The address to pay to.
+    pub fn address(&self) -> Arc<Address> {
+        Arc::new(Address(self.address.clone()))
+    }
+
+    /// The requested amount, if any.
+    pub fn amount(&self) -> Option<Arc<Amount>> {
+        self.amount.map(|amount| Arc::new(Amount(amount)))
+    }
+
+    /// The label for the recipient, if any.
+    pub fn label(&self) -> Option<String> {
+        self.label.clone()
+    }
+
+    /// A message describing the payment, if any.
+    pub fn message(&self) -> Option<String> {
+        self.message.clone()
+    }
+}
+
+/// Decode a percent-encoded URI component, returning `None` if it is malformed or not UTF-8.
+fn percent_decode(value: &str) -> Option<String> {
+    let mut bytes = Vec::with_capacity(value.len());
+    let mut encoded = value.bytes();
+    while let Some(byte) = encoded.next() {
+        if byte == b'%' {
+            let high = (encoded.next()? as char).to_digit(16)?;
+            let low = (encoded.next()? as char).to_digit(16)?;
+            bytes.push((high * 16 + low) as u8);
+        } else {
+            bytes.push(byte);
+        }
+    }
+    String::from_utf8(bytes).ok()
+}
+
 /// Bitcoin transaction.
 /// An authenticated movement of coins.
 #[derive(Debug, Clone, PartialEq, Eq, uniffi::Object)]
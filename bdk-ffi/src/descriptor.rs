@@ -2,11 +2,18 @@ use crate::bitcoin::DescriptorId;
 use crate::bitcoin::DescriptorType;
 use crate::bitcoin::{Address, NetworkKind};
 use crate::error::DescriptorError;
+use crate::error::DescriptorKeyError;
 use crate::error::MiniscriptError;
-use crate::keys::DescriptorPublicKey;
-use crate::keys::DescriptorSecretKey;
-use crate::types::KeychainKind;
+use crate::keys::{is_bip48_account_path, DerivationPath, DescriptorPublicKey};
+use crate::keys::{DescriptorSecretKey, TemplateKey};
+use crate::secret::Secret;
+use crate::spending_path::{threshold_alternatives, MAX_SPENDING_PATHS};
+use crate::types::{
+    Bip48ScriptType, Cosigner, DescriptorAnalysis, DescriptorKeyInfo, HashFunction, Hashlock,
+    KeychainKind, LockTime, PolicyItem, SatisfactionPath, TaprootLeaf, ThresholdNode, WildcardType,
+};
 
+use bdk_wallet::bitcoin::absolute::LockTime as BdkLockTime;
 use bdk_wallet::bitcoin::bip32::Fingerprint;
 use bdk_wallet::bitcoin::key::Secp256k1;
 use bdk_wallet::bitcoin::Network;
@@ -14,13 +21,20 @@ use bdk_wallet::chain::DescriptorExt;
 use bdk_wallet::descriptor::{ExtendedDescriptor, IntoWalletDescriptor};
 use bdk_wallet::keys::DescriptorPublicKey as BdkDescriptorPublicKey;
 use bdk_wallet::keys::{DescriptorSecretKey as BdkDescriptorSecretKey, KeyMap};
-use bdk_wallet::miniscript::descriptor::{ConversionError, TapTree};
+use bdk_wallet::miniscript::descriptor::{ConversionError, TapTree, Wildcard};
+use bdk_wallet::miniscript::iter::TreeLike;
+use bdk_wallet::miniscript::plan::Assets;
+use bdk_wallet::miniscript::policy::concrete::{DescriptorCtx, Policy as ConcretePolicy};
+use bdk_wallet::miniscript::policy::semantic::Policy as SemanticPolicy;
+use bdk_wallet::miniscript::policy::Liftable;
 use bdk_wallet::miniscript::Descriptor as BdkDescriptor;
 use bdk_wallet::miniscript::Miniscript as BdkMiniscript;
+use bdk_wallet::miniscript::{ForEachKey, RelLockTime, Segwitv0, Tap};
 use bdk_wallet::template::{
     Bip44, Bip44Public, Bip49, Bip49Public, Bip84, Bip84Public, Bip86, Bip86Public,
     DescriptorTemplate,
 };
+use zeroize::Zeroizing;
 
 use std::fmt::Display;
 use std::str::FromStr;
@@ -31,7 +45,7 @@ use std::sync::Arc;
 #[uniffi::export(Debug, Display)]
 pub struct Descriptor {
     pub extended_descriptor: ExtendedDescriptor,
-    pub key_map: KeyMap,
+    pub(crate) key_map: Secret<KeyMap>,
 }
 
 #[uniffi::export]
@@ -39,13 +53,7 @@ impl Descriptor {
     /// Parse a string as a descriptor for the given network.
     #[uniffi::constructor]
     pub fn new(descriptor: String, network_kind: NetworkKind) -> Result<Self, DescriptorError> {
-        let secp = Secp256k1::new();
-        let (extended_descriptor, key_map) =
-            descriptor.into_wallet_descriptor(&secp, network_kind)?;
-        Ok(Self {
-            extended_descriptor,
-            key_map,
-        })
+        Self::parse(&descriptor, network_kind)
     }
 
     /// Multi-account hierarchy descriptor: https://github.com/bitcoin/bips/blob/master/bip-0044.mediawiki
@@ -55,26 +63,19 @@ impl Descriptor {
         keychain_kind: KeychainKind,
         network_kind: NetworkKind,
     ) -> Self {
-        let derivable_key = &secret_key.0;
-
-        match derivable_key {
-            BdkDescriptorSecretKey::Single(_) => {
-                unreachable!()
-            }
-            BdkDescriptorSecretKey::XPrv(descriptor_x_key) => {
-                let derivable_key = descriptor_x_key.xkey;
-                let (extended_descriptor, key_map, _) = Bip44(derivable_key, keychain_kind)
-                    .build(network_kind)
-                    .unwrap();
-                Self {
-                    extended_descriptor,
-                    key_map,
-                }
+        let (extended_descriptor, key_map, _) = match secret_key.template_key(44, network_kind) {
+            TemplateKey::Secret(derivable_key) => {
+                Bip44(derivable_key, keychain_kind).build(network_kind)
             }
-            BdkDescriptorSecretKey::MultiXPrv(_) => {
-                unreachable!()
+            TemplateKey::Public(derivable_key, fingerprint) => {
+                Bip44Public(derivable_key, fingerprint, keychain_kind).build(network_kind)
             }
         }
+        .unwrap();
+        Self {
+            extended_descriptor,
+            key_map: key_map.into(),
+        }
     }
 
     /// Multi-account hierarchy descriptor: https://github.com/bitcoin/bips/blob/master/bip-0044.mediawiki
@@ -105,7 +106,7 @@ impl Descriptor {
 
                 Ok(Self {
                     extended_descriptor,
-                    key_map,
+                    key_map: key_map.into(),
                 })
             }
             BdkDescriptorPublicKey::MultiXPub(_) => {
@@ -121,26 +122,19 @@ impl Descriptor {
         keychain_kind: KeychainKind,
         network_kind: NetworkKind,
     ) -> Self {
-        let derivable_key = &secret_key.0;
-
-        match derivable_key {
-            BdkDescriptorSecretKey::Single(_) => {
-                unreachable!()
+        let (extended_descriptor, key_map, _) = match secret_key.template_key(49, network_kind) {
+            TemplateKey::Secret(derivable_key) => {
+                Bip49(derivable_key, keychain_kind).build(network_kind)
             }
-            BdkDescriptorSecretKey::XPrv(descriptor_x_key) => {
-                let derivable_key = descriptor_x_key.xkey;
-                let (extended_descriptor, key_map, _) = Bip49(derivable_key, keychain_kind)
-                    .build(network_kind)
-                    .unwrap();
-                Self {
-                    extended_descriptor,
-                    key_map,
-                }
-            }
-            BdkDescriptorSecretKey::MultiXPrv(_) => {
-                unreachable!()
+            TemplateKey::Public(derivable_key, fingerprint) => {
+                Bip49Public(derivable_key, fingerprint, keychain_kind).build(network_kind)
             }
         }
+        .unwrap();
+        Self {
+            extended_descriptor,
+            key_map: key_map.into(),
+        }
     }
 
     /// P2SH nested P2WSH descriptor: https://github.com/bitcoin/bips/blob/master/bip-0049.mediawiki
@@ -171,7 +165,7 @@ impl Descriptor {
 
                 Ok(Self {
                     extended_descriptor,
-                    key_map,
+                    key_map: key_map.into(),
                 })
             }
             BdkDescriptorPublicKey::MultiXPub(_) => {
@@ -187,26 +181,19 @@ impl Descriptor {
         keychain_kind: KeychainKind,
         network_kind: NetworkKind,
     ) -> Self {
-        let derivable_key = &secret_key.0;
-
-        match derivable_key {
-            BdkDescriptorSecretKey::Single(_) => {
-                unreachable!()
+        let (extended_descriptor, key_map, _) = match secret_key.template_key(84, network_kind) {
+            TemplateKey::Secret(derivable_key) => {
+                Bip84(derivable_key, keychain_kind).build(network_kind)
             }
-            BdkDescriptorSecretKey::XPrv(descriptor_x_key) => {
-                let derivable_key = descriptor_x_key.xkey;
-                let (extended_descriptor, key_map, _) = Bip84(derivable_key, keychain_kind)
-                    .build(network_kind)
-                    .unwrap();
-                Self {
-                    extended_descriptor,
-                    key_map,
-                }
-            }
-            BdkDescriptorSecretKey::MultiXPrv(_) => {
-                unreachable!()
+            TemplateKey::Public(derivable_key, fingerprint) => {
+                Bip84Public(derivable_key, fingerprint, keychain_kind).build(network_kind)
             }
         }
+        .unwrap();
+        Self {
+            extended_descriptor,
+            key_map: key_map.into(),
+        }
     }
 
     /// Pay to witness PKH descriptor: https://github.com/bitcoin/bips/blob/master/bip-0084.mediawiki
@@ -237,7 +224,7 @@ impl Descriptor {
 
                 Ok(Self {
                     extended_descriptor,
-                    key_map,
+                    key_map: key_map.into(),
                 })
             }
             BdkDescriptorPublicKey::MultiXPub(_) => {
@@ -253,26 +240,19 @@ impl Descriptor {
         keychain_kind: KeychainKind,
         network_kind: NetworkKind,
     ) -> Self {
-        let derivable_key = &secret_key.0;
-
-        match derivable_key {
-            BdkDescriptorSecretKey::Single(_) => {
-                unreachable!()
+        let (extended_descriptor, key_map, _) = match secret_key.template_key(86, network_kind) {
+            TemplateKey::Secret(derivable_key) => {
+                Bip86(derivable_key, keychain_kind).build(network_kind)
             }
-            BdkDescriptorSecretKey::XPrv(descriptor_x_key) => {
-                let derivable_key = descriptor_x_key.xkey;
-                let (extended_descriptor, key_map, _) = Bip86(derivable_key, keychain_kind)
-                    .build(network_kind)
-                    .unwrap();
-                Self {
-                    extended_descriptor,
-                    key_map,
-                }
-            }
-            BdkDescriptorSecretKey::MultiXPrv(_) => {
-                unreachable!()
+            TemplateKey::Public(derivable_key, fingerprint) => {
+                Bip86Public(derivable_key, fingerprint, keychain_kind).build(network_kind)
             }
         }
+        .unwrap();
+        Self {
+            extended_descriptor,
+            key_map: key_map.into(),
+        }
     }
 
     /// Single key P2TR descriptor: https://github.com/bitcoin/bips/blob/master/bip-0086.mediawiki
@@ -303,7 +283,7 @@ impl Descriptor {
 
                 Ok(Self {
                     extended_descriptor,
-                    key_map,
+                    key_map: key_map.into(),
                 })
             }
             BdkDescriptorPublicKey::MultiXPub(_) => {
@@ -337,7 +317,7 @@ impl Descriptor {
 
         Ok(Self {
             extended_descriptor,
-            key_map: KeyMap::new(),
+            key_map: KeyMap::new().into(),
         })
     }
 
@@ -366,7 +346,7 @@ impl Descriptor {
 
         Ok(Self {
             extended_descriptor,
-            key_map: KeyMap::new(),
+            key_map: KeyMap::new().into(),
         })
     }
 
@@ -395,7 +375,7 @@ impl Descriptor {
 
         Ok(Self {
             extended_descriptor,
-            key_map: KeyMap::new(),
+            key_map: KeyMap::new().into(),
         })
     }
 
@@ -411,7 +391,7 @@ impl Descriptor {
 
         Ok(Self {
             extended_descriptor,
-            key_map: KeyMap::new(),
+            key_map: KeyMap::new().into(),
         })
     }
 
@@ -434,7 +414,7 @@ impl Descriptor {
 
         Ok(Self {
             extended_descriptor,
-            key_map: KeyMap::new(),
+            key_map: KeyMap::new().into(),
         })
     }
 
@@ -457,7 +437,7 @@ impl Descriptor {
 
         Ok(Self {
             extended_descriptor,
-            key_map: KeyMap::new(),
+            key_map: KeyMap::new().into(),
         })
     }
 
@@ -480,7 +460,7 @@ impl Descriptor {
 
         Ok(Self {
             extended_descriptor,
-            key_map: KeyMap::new(),
+            key_map: KeyMap::new().into(),
         })
     }
 
@@ -510,7 +490,7 @@ impl Descriptor {
 
         Ok(Self {
             extended_descriptor,
-            key_map: KeyMap::new(),
+            key_map: KeyMap::new().into(),
         })
     }
 
@@ -540,7 +520,7 @@ impl Descriptor {
 
         Ok(Self {
             extended_descriptor,
-            key_map: KeyMap::new(),
+            key_map: KeyMap::new().into(),
         })
     }
 
@@ -569,7 +549,7 @@ impl Descriptor {
 
         Ok(Self {
             extended_descriptor,
-            key_map: KeyMap::new(),
+            key_map: KeyMap::new().into(),
         })
     }
 
@@ -599,7 +579,7 @@ impl Descriptor {
 
         Ok(Self {
             extended_descriptor,
-            key_map: KeyMap::new(),
+            key_map: KeyMap::new().into(),
         })
     }
 
@@ -616,7 +596,7 @@ impl Descriptor {
             let sh_with_wpkh = bdk_wallet::miniscript::Descriptor::new_sh_with_wpkh(wpkh_inner);
             Ok(Self {
                 extended_descriptor: ExtendedDescriptor::from(sh_with_wpkh),
-                key_map: KeyMap::new(),
+                key_map: KeyMap::new().into(),
             })
         } else {
             Err(DescriptorError::Miniscript {
@@ -638,7 +618,7 @@ impl Descriptor {
             let sh_with_wsh = bdk_wallet::miniscript::Descriptor::new_sh_with_wsh(wsh_inner);
             Ok(Self {
                 extended_descriptor: ExtendedDescriptor::from(sh_with_wsh),
-                key_map: KeyMap::new(),
+                key_map: KeyMap::new().into(),
             })
         } else {
             Err(DescriptorError::Miniscript {
@@ -674,23 +654,152 @@ impl Descriptor {
             })?;
         Ok(Self {
             extended_descriptor: ExtendedDescriptor::from(descriptor),
-            key_map: KeyMap::new(),
+            key_map: KeyMap::new().into(),
         })
     }
 
+    /// Create a wsh inheritance descriptor, spendable by `primary_key` at any time or by
+    /// `recovery_key` once the coins are `recovery_delay` blocks old:
+    /// `wsh(or_d(pk(primary),and_v(v:pk(recovery),older(recovery_delay))))`.
+    ///
+    /// Keys can be public or secret descriptor key expressions, secret keys are kept in the key map
+    /// so the descriptor can be used for signing. `recovery_delay` must be between 1 and 65535.
+    #[uniffi::constructor]
+    pub fn new_wsh_inheritance(
+        primary_key: String,
+        recovery_key: String,
+        recovery_delay: u32,
+        network_kind: NetworkKind,
+    ) -> Result<Self, DescriptorError> {
+        let (primary_key, recovery_key) =
+            (Zeroizing::new(primary_key), Zeroizing::new(recovery_key));
+        check_template_key("primary key", &primary_key)?;
+        check_template_key("recovery key", &recovery_key)?;
+        check_relative_timelock(recovery_delay)?;
+        Self::from_template(
+            Zeroizing::new(format!(
+                "wsh(or_d(pk({}),and_v(v:pk({}),older({recovery_delay}))))",
+                *primary_key, *recovery_key
+            )),
+            network_kind,
+        )
+    }
+
+    /// Create a taproot inheritance descriptor, spendable by `primary_key` through the key path or
+    /// by `recovery_key` once the coins are `recovery_delay` blocks old through the script path:
+    /// `tr(primary,and_v(v:pk(recovery),older(recovery_delay)))`.
+    #[uniffi::constructor]
+    pub fn new_tr_inheritance(
+        primary_key: String,
+        recovery_key: String,
+        recovery_delay: u32,
+        network_kind: NetworkKind,
+    ) -> Result<Self, DescriptorError> {
+        let (primary_key, recovery_key) =
+            (Zeroizing::new(primary_key), Zeroizing::new(recovery_key));
+        check_template_key("primary key", &primary_key)?;
+        check_template_key("recovery key", &recovery_key)?;
+        check_relative_timelock(recovery_delay)?;
+        Self::from_template(
+            Zeroizing::new(format!(
+                "tr({},and_v(v:pk({}),older({recovery_delay})))",
+                *primary_key, *recovery_key
+            )),
+            network_kind,
+        )
+    }
+
+    /// Create a wsh decaying multisig descriptor: `threshold` of `keys` need to sign, and the
+    /// threshold drops by one each time the coins get as old as one of the `decay_delays` (in
+    /// blocks). At least one signature is always required. Delays must be between 1 and 65535.
+    #[uniffi::constructor]
+    pub fn new_wsh_decaying_multisig(
+        threshold: u32,
+        keys: Vec<String>,
+        decay_delays: Vec<u32>,
+        network_kind: NetworkKind,
+    ) -> Result<Self, DescriptorError> {
+        let keys = keys.into_iter().map(Zeroizing::new).collect::<Vec<_>>();
+        let thresh = decaying_thresh(threshold, &keys, &decay_delays)?;
+        Self::from_template(Zeroizing::new(format!("wsh({})", *thresh)), network_kind)
+    }
+
+    /// Create a taproot decaying multisig descriptor, with the same spending conditions as
+    /// `Descriptor::new_wsh_decaying_multisig` in a single script leaf. The internal key is the
+    /// provably unspendable point from BIP-341 so the key path cannot be used.
+    #[uniffi::constructor]
+    pub fn new_tr_decaying_multisig(
+        threshold: u32,
+        keys: Vec<String>,
+        decay_delays: Vec<u32>,
+        network_kind: NetworkKind,
+    ) -> Result<Self, DescriptorError> {
+        let keys = keys.into_iter().map(Zeroizing::new).collect::<Vec<_>>();
+        let thresh = decaying_thresh(threshold, &keys, &decay_delays)?;
+        Self::from_template(
+            Zeroizing::new(format!("tr({UNSPENDABLE_KEY},{})", *thresh)),
+            network_kind,
+        )
+    }
+
+    /// Compile a spending policy such as `or(99@pk(A),and(pk(B),older(1000)))` into the wsh
+    /// descriptor with the cheapest expected spending cost. Probabilities (`N@`) on `or` branches
+    /// tell the compiler which paths are expected to be used most.
+    ///
+    /// Keys can be public or secret descriptor key expressions.
+    #[uniffi::constructor]
+    pub fn compile_wsh_policy(
+        policy: String,
+        network_kind: NetworkKind,
+    ) -> Result<Self, DescriptorError> {
+        let policy = Zeroizing::new(policy);
+        let descriptor = parse_policy(&policy)?
+            .compile_to_descriptor::<Segwitv0>(DescriptorCtx::Wsh)
+            .map_err(compiler_error)?;
+        Self::parse(&Zeroizing::new(descriptor.to_string()), network_kind)
+    }
+
+    /// Compile a spending policy into a taproot descriptor. The compiler uses the most likely key
+    /// of the policy as the internal key, and falls back to `unspendable_key` (by default the
+    /// provably unspendable point from BIP-341) when every path needs more than one key.
+    #[uniffi::constructor(default(unspendable_key = None))]
+    pub fn compile_tr_policy(
+        policy: String,
+        network_kind: NetworkKind,
+        unspendable_key: Option<String>,
+    ) -> Result<Self, DescriptorError> {
+        let unspendable_key = unspendable_key.unwrap_or_else(|| UNSPENDABLE_KEY.to_string());
+        let policy = Zeroizing::new(policy);
+        let descriptor = parse_policy(&policy)?
+            .compile_to_descriptor::<Tap>(DescriptorCtx::Tr(Some(unspendable_key)))
+            .map_err(compiler_error)?;
+        Self::parse(&Zeroizing::new(descriptor.to_string()), network_kind)
+    }
+
     /// Return a public version of this descriptor without secret key material.
     pub fn as_public(&self) -> Arc<Self> {
         Arc::new(Self {
             extended_descriptor: self.extended_descriptor.clone(),
-            key_map: KeyMap::new(),
+            key_map: KeyMap::new().into(),
         })
     }
 
     /// Dangerously convert the descriptor to a string.
     pub fn to_string_with_secret(&self) -> String {
         let descriptor = &self.extended_descriptor;
-        let key_map = &self.key_map;
-        descriptor.to_string_with_secret(key_map)
+        match self.key_map.read() {
+            Ok(key_map) => descriptor.to_string_with_secret(&key_map),
+            Err(_) => descriptor.to_string(),
+        }
+    }
+
+    /// Erase the secret keys of the descriptor from memory without waiting for the object to be
+    /// dropped. The descriptor remains usable as a public descriptor.
+    ///
+    /// Signers and wallets already built from the descriptor hold their own copies of the keys,
+    /// which are not erased.
+    pub fn wipe(&self) {
+        self.key_map.clear()
     }
 
     /// Does this descriptor contain paths: https://github.com/bitcoin/bips/blob/master/bip-0389.mediawiki
@@ -771,6 +880,76 @@ impl Descriptor {
         self.extended_descriptor.has_wildcard()
     }
 
+    /// Describe the contents of the descriptor for review: its keys with their origin, the
+    /// timelocks and hashlocks, the threshold structure of the spending policy, the taproot
+    /// leaves, and the satisfaction weight of each way of spending it.
+    ///
+    /// For multipath descriptors the spending paths are computed from the first path. They are
+    /// left out for descriptors with hardened wildcards (`/*h`), which cannot be derived from the
+    /// public keys alone. Fails with `DescriptorError::TooManySpendingPaths` for policies with too
+    /// many spending paths to list, such as large thresholds of keys.
+    pub fn analyze(&self) -> Result<DescriptorAnalysis, DescriptorError> {
+        let descriptor = &self.extended_descriptor;
+        let policy = descriptor.lift().map_err(|e| DescriptorError::Miniscript {
+            error_message: e.to_string(),
+        })?;
+
+        let mut keys = Vec::new();
+        descriptor.for_each_key(|key| {
+            keys.push(key_info(key));
+            true
+        });
+
+        let mut absolute_timelocks = Vec::new();
+        let mut relative_timelocks = Vec::new();
+        let mut hashlocks = Vec::new();
+        for node in policy.pre_order_iter() {
+            match node {
+                SemanticPolicy::After(lock_time) => {
+                    absolute_timelocks.push(BdkLockTime::from(*lock_time).into())
+                }
+                SemanticPolicy::Older(lock_time) => {
+                    relative_timelocks.push(lock_time.to_consensus_u32())
+                }
+                node => hashlocks.extend(hashlock(node)),
+            }
+        }
+
+        let mut nodes = Vec::new();
+        match flatten_policy(&policy, &mut nodes) {
+            PolicyItem::Threshold { .. } => {}
+            item => nodes.push(ThresholdNode {
+                threshold: 1,
+                items: vec![item],
+            }),
+        }
+
+        let (taproot_internal_key, taproot_leaves) = match descriptor {
+            BdkDescriptor::Tr(tr) => (
+                Some(tr.internal_key().to_string()),
+                tr.iter_scripts()
+                    .map(|(depth, miniscript)| TaprootLeaf {
+                        depth,
+                        miniscript: miniscript.to_string(),
+                    })
+                    .collect(),
+            ),
+            _ => (None, Vec::new()),
+        };
+
+        Ok(DescriptorAnalysis {
+            desc_type: descriptor.desc_type(),
+            keys,
+            absolute_timelocks,
+            relative_timelocks,
+            hashlocks,
+            policy: nodes,
+            taproot_internal_key,
+            taproot_leaves,
+            spending_paths: satisfaction_paths(descriptor)?,
+        })
+    }
+
     /// Checks whether the descriptor is safe.
     ///
     /// Checks whether all the spend paths in the descriptor are possible on the
@@ -789,6 +968,422 @@ impl Descriptor {
     }
 }
 
+impl Descriptor {
+    /// Parse a descriptor without taking ownership of the string, so that callers can erase it.
+    pub(crate) fn parse(
+        descriptor: &str,
+        network_kind: NetworkKind,
+    ) -> Result<Self, DescriptorError> {
+        let secp = Secp256k1::new();
+        let (extended_descriptor, key_map) =
+            descriptor.into_wallet_descriptor(&secp, network_kind)?;
+        Ok(Self {
+            extended_descriptor,
+            key_map: key_map.into(),
+        })
+    }
+
+    /// Parse a descriptor assembled from key expressions, which are erased along with the
+    /// descriptor text since they may be secret.
+    fn from_template(
+        descriptor: Zeroizing<String>,
+        network_kind: NetworkKind,
+    ) -> Result<Self, DescriptorError> {
+        let descriptor = Self::parse(&descriptor, network_kind)?;
+        descriptor.sanity_check()?;
+        Ok(descriptor)
+    }
+}
+
+/// Combine the BIP-48 account keys of the participants of a multisig wallet into sortedmulti
+/// descriptors.
+#[derive(Clone, uniffi::Object)]
+pub struct MultisigDescriptorBuilder {
+    threshold: u32,
+    script_type: Bip48ScriptType,
+    cosigners: Vec<Cosigner>,
+    signers: Vec<(String, Secret<BdkDescriptorSecretKey>)>,
+}
+
+#[uniffi::export]
+impl MultisigDescriptorBuilder {
+    /// Start a `threshold` of n multisig of the given script type.
+    #[uniffi::constructor]
+    pub fn new(threshold: u32, script_type: Bip48ScriptType) -> Self {
+        MultisigDescriptorBuilder {
+            threshold,
+            script_type,
+            cosigners: Vec::new(),
+            signers: Vec::new(),
+        }
+    }
+
+    /// Add a participant by its exported account key.
+    pub fn add_cosigner(&self, cosigner: Cosigner) -> Arc<Self> {
+        let mut builder = self.clone();
+        builder.cosigners.push(cosigner);
+        Arc::new(builder)
+    }
+
+    /// Add a participant this wallet signs for, from its account key as returned by
+    /// `DescriptorSecretKey::derive_bip48_account`. The secret key ends up in the key map of the
+    /// built descriptors.
+    pub fn add_signer(
+        &self,
+        account_key: &DescriptorSecretKey,
+    ) -> Result<Arc<Self>, DescriptorKeyError> {
+        let secret_key = account_key.0.clone();
+        secret_key.read()?;
+        let cosigner = account_key.as_public().to_cosigner()?;
+        let mut builder = self.clone();
+        builder.signers.push((cosigner.xpub.clone(), secret_key));
+        builder.cosigners.push(cosigner);
+        Ok(Arc::new(builder))
+    }
+
+    /// Build the descriptor of one keychain, deriving `0/*` from the account keys for the external
+    /// keychain and `1/*` for the internal one. Errors with `DescriptorError::InvalidHdKeyPath` if
+    /// a cosigner is not a BIP-48 account of this script type and network.
+    pub fn build(
+        &self,
+        keychain: KeychainKind,
+        network_kind: NetworkKind,
+    ) -> Result<Arc<Descriptor>, DescriptorError> {
+        let branch = match keychain {
+            KeychainKind::External => 0,
+            KeychainKind::Internal => 1,
+        };
+        let keys = self
+            .cosigners
+            .iter()
+            .map(|cosigner| {
+                if !is_bip48_account_path(
+                    &cosigner.derivation_path.0,
+                    self.script_type,
+                    network_kind,
+                ) {
+                    return Err(DescriptorError::InvalidHdKeyPath);
+                }
+                let key = match self.signers.iter().find(|(xpub, _)| *xpub == cosigner.xpub) {
+                    Some((_, secret_key)) => Zeroizing::new(secret_key.read()?.to_string()),
+                    None => Zeroizing::new(format!(
+                        "[{}/{}]{}",
+                        cosigner.master_fingerprint, cosigner.derivation_path, cosigner.xpub
+                    )),
+                };
+                Ok(Zeroizing::new(format!("{}/{branch}/*", *key)))
+            })
+            .collect::<Result<Vec<_>, DescriptorError>>()?;
+
+        let keys = Zeroizing::new(
+            keys.iter()
+                .map(|key| key.as_str())
+                .collect::<Vec<_>>()
+                .join(","),
+        );
+        let multi = Zeroizing::new(format!("sortedmulti({},{})", self.threshold, *keys));
+        let descriptor = match self.script_type {
+            Bip48ScriptType::P2wsh => format!("wsh({})", *multi),
+            Bip48ScriptType::P2shP2wsh => format!("sh(wsh({}))", *multi),
+        };
+        Descriptor::from_template(Zeroizing::new(descriptor), network_kind).map(Arc::new)
+    }
+}
+
+fn key_info(key: &BdkDescriptorPublicKey) -> DescriptorKeyInfo {
+    let (origin, derivation_paths, wildcard) = match key {
+        BdkDescriptorPublicKey::Single(single) => (&single.origin, Vec::new(), Wildcard::None),
+        BdkDescriptorPublicKey::XPub(xkey) => (
+            &xkey.origin,
+            vec![xkey.derivation_path.clone()],
+            xkey.wildcard,
+        ),
+        BdkDescriptorPublicKey::MultiXPub(xkey) => (
+            &xkey.origin,
+            xkey.derivation_paths.paths().clone(),
+            xkey.wildcard,
+        ),
+    };
+    DescriptorKeyInfo {
+        key: Arc::new(DescriptorPublicKey(key.clone())),
+        master_fingerprint: key.master_fingerprint().to_string(),
+        origin_path: origin
+            .as_ref()
+            .map(|(_, path)| Arc::new(DerivationPath(path.clone()))),
+        derivation_paths: derivation_paths
+            .into_iter()
+            .map(|path| Arc::new(DerivationPath(path)))
+            .collect(),
+        wildcard: match wildcard {
+            Wildcard::None => None,
+            Wildcard::Unhardened => Some(WildcardType::Unhardened),
+            Wildcard::Hardened => Some(WildcardType::Hardened),
+        },
+    }
+}
+
+fn hashlock(policy: &SemanticPolicy<BdkDescriptorPublicKey>) -> Option<Hashlock> {
+    let (function, hash) = match policy {
+        SemanticPolicy::Sha256(hash) => (HashFunction::Sha256, hash.to_string()),
+        SemanticPolicy::Hash256(hash) => (HashFunction::Hash256, hash.to_string()),
+        SemanticPolicy::Ripemd160(hash) => (HashFunction::Ripemd160, hash.to_string()),
+        SemanticPolicy::Hash160(hash) => (HashFunction::Hash160, hash.to_string()),
+        _ => return None,
+    };
+    Some(Hashlock { function, hash })
+}
+
+fn hashlock_item(function: HashFunction, hash: impl Display) -> PolicyItem {
+    PolicyItem::Hashlock {
+        hashlock: Hashlock {
+            function,
+            hash: hash.to_string(),
+        },
+    }
+}
+
+/// Append the thresholds of `policy` to `nodes` in pre-order, returning the item referencing it.
+fn flatten_policy(
+    policy: &SemanticPolicy<BdkDescriptorPublicKey>,
+    nodes: &mut Vec<ThresholdNode>,
+) -> PolicyItem {
+    match policy {
+        SemanticPolicy::Unsatisfiable => PolicyItem::Unsatisfiable,
+        SemanticPolicy::Trivial => PolicyItem::Trivial,
+        SemanticPolicy::Key(key) => PolicyItem::Key {
+            key: key.to_string(),
+        },
+        SemanticPolicy::After(lock_time) => PolicyItem::AbsoluteTimelock {
+            lock_time: BdkLockTime::from(*lock_time).into(),
+        },
+        SemanticPolicy::Older(lock_time) => PolicyItem::RelativeTimelock {
+            sequence: lock_time.to_consensus_u32(),
+        },
+        SemanticPolicy::Thresh(thresh) => {
+            let index = nodes.len();
+            nodes.push(ThresholdNode {
+                threshold: thresh.k() as u32,
+                items: Vec::new(),
+            });
+            let items = thresh
+                .iter()
+                .map(|item| flatten_policy(item, nodes))
+                .collect();
+            nodes[index].items = items;
+            PolicyItem::Threshold {
+                index: index as u32,
+            }
+        }
+        SemanticPolicy::Sha256(hash) => hashlock_item(HashFunction::Sha256, hash),
+        SemanticPolicy::Hash256(hash) => hashlock_item(HashFunction::Hash256, hash),
+        SemanticPolicy::Ripemd160(hash) => hashlock_item(HashFunction::Ripemd160, hash),
+        SemanticPolicy::Hash160(hash) => hashlock_item(HashFunction::Hash160, hash),
+    }
+}
+
+/// The minimal sets of conditions satisfying `policy`, one per combination of thresholds items,
+/// or `None` if there are more than `MAX_SPENDING_PATHS`.
+fn condition_sets(
+    policy: &SemanticPolicy<BdkDescriptorPublicKey>,
+) -> Option<Vec<Vec<&SemanticPolicy<BdkDescriptorPublicKey>>>> {
+    match policy {
+        SemanticPolicy::Unsatisfiable => Some(Vec::new()),
+        SemanticPolicy::Trivial => Some(vec![Vec::new()]),
+        SemanticPolicy::Thresh(thresh) => {
+            let items = thresh
+                .iter()
+                .map(|item| condition_sets(item))
+                .collect::<Option<Vec<_>>>()?;
+            threshold_alternatives(
+                &items,
+                thresh.k(),
+                |_| Vec::new(),
+                |set, item| set.iter().chain(item).copied().collect(),
+            )
+        }
+        leaf => Some(vec![vec![leaf]]),
+    }
+}
+
+/// Plan the satisfaction of every set of conditions of the first single path of `descriptor`.
+/// Sets that cannot be satisfied together, like timelocks in different units, are dropped.
+///
+/// Returns `None` for descriptors with hardened wildcards, whose keys cannot be derived without
+/// the secret keys and so cannot be planned.
+fn satisfaction_paths(
+    descriptor: &ExtendedDescriptor,
+) -> Result<Option<Vec<SatisfactionPath>>, DescriptorError> {
+    let miniscript_error = |e: bdk_wallet::miniscript::Error| DescriptorError::Miniscript {
+        error_message: e.to_string(),
+    };
+    let single = descriptor
+        .clone()
+        .into_single_descriptors()
+        .map_err(miniscript_error)?
+        .remove(0);
+    let policy = single.lift().map_err(|e| DescriptorError::Miniscript {
+        error_message: e.to_string(),
+    })?;
+    let hardened_wildcard = single.for_any_key(|key| {
+        matches!(key, BdkDescriptorPublicKey::XPub(xkey) if xkey.wildcard == Wildcard::Hardened)
+    });
+    if hardened_wildcard {
+        return Ok(None);
+    }
+    let definite = single.at_derivation_index(0).map_err(|error| match error {
+        ConversionError::HardenedChild => DescriptorError::HardenedDerivationXpub,
+        ConversionError::MultiKey => DescriptorError::MultiPath,
+    })?;
+
+    // Nobody can sign for the unspendable key of taproot descriptors without a key path.
+    let unspendable = |condition: &&SemanticPolicy<BdkDescriptorPublicKey>| matches!(condition, SemanticPolicy::Key(key) if key.to_string() == UNSPENDABLE_KEY);
+    let paths = condition_sets(&policy)
+        .ok_or(DescriptorError::TooManySpendingPaths {
+            max: MAX_SPENDING_PATHS as u64,
+        })?
+        .into_iter()
+        .filter(|conditions| !conditions.iter().any(unspendable))
+        .filter_map(|conditions| {
+            let mut keys = Vec::new();
+            let mut absolute_timelock: Option<BdkLockTime> = None;
+            let mut relative_timelock: Option<RelLockTime> = None;
+            let mut hashlocks = Vec::new();
+            let mut assets = Assets::new();
+            for condition in conditions {
+                match condition {
+                    SemanticPolicy::Key(key) => keys.push(key.clone()),
+                    SemanticPolicy::After(lock_time) => {
+                        let lock_time = BdkLockTime::from(*lock_time);
+                        if absolute_timelock.is_none_or(|current| {
+                            current.to_consensus_u32() < lock_time.to_consensus_u32()
+                        }) {
+                            absolute_timelock = Some(lock_time);
+                        }
+                    }
+                    SemanticPolicy::Older(lock_time) => {
+                        if relative_timelock.is_none_or(|current| {
+                            current.to_consensus_u32() < lock_time.to_consensus_u32()
+                        }) {
+                            relative_timelock = Some(*lock_time);
+                        }
+                    }
+                    SemanticPolicy::Sha256(hash) => assets = assets.add(*hash),
+                    SemanticPolicy::Hash256(hash) => assets = assets.add(*hash),
+                    SemanticPolicy::Ripemd160(hash) => assets = assets.add(*hash),
+                    SemanticPolicy::Hash160(hash) => assets = assets.add(*hash),
+                    _ => {}
+                }
+                hashlocks.extend(hashlock(condition));
+            }
+            assets = assets.add(keys.clone());
+            if let Some(lock_time) = absolute_timelock {
+                assets = assets.after(lock_time);
+            }
+            if let Some(lock_time) = relative_timelock {
+                assets = assets.older(lock_time.into());
+            }
+
+            let plan = definite.clone().plan(&assets).ok()?;
+            Some(SatisfactionPath {
+                keys: keys.iter().map(|key| key.to_string()).collect(),
+                absolute_timelock: absolute_timelock.map(LockTime::from),
+                relative_timelock: relative_timelock.map(|lock_time| lock_time.to_consensus_u32()),
+                hashlocks,
+                satisfaction_weight: plan.satisfaction_weight() as u64,
+            })
+        })
+        .collect();
+    Ok(Some(paths))
+}
+
+/// The x-only public key with no known discrete logarithm suggested by BIP-341.
+const UNSPENDABLE_KEY: &str = "50929b74c1a04954b78b4b6035e97a5e078a5a0f28ec96d547bfee9ace803ac0";
+
+/// Parse a concrete policy, keeping the keys as strings so that secret keys survive the
+/// compilation and end up in the key map.
+fn parse_policy(policy: &str) -> Result<ConcretePolicy<String>, DescriptorError> {
+    ConcretePolicy::from_str(policy).map_err(|e| DescriptorError::Policy {
+        error_message: e.to_string(),
+    })
+}
+
+fn compiler_error(error: bdk_wallet::miniscript::Error) -> DescriptorError {
+    DescriptorError::Miniscript {
+        error_message: MiniscriptError::from(error).to_string(),
+    }
+}
+
+/// Check that a key given to a template is a single public or secret descriptor key expression, so
+/// that splicing it into the template cannot change the spending conditions. The key is left out
+/// of the error since it may be secret.
+fn check_template_key(name: &str, key: &str) -> Result<(), DescriptorError> {
+    if BdkDescriptorPublicKey::from_str(key).is_ok()
+        || BdkDescriptorSecretKey::from_str(key).is_ok()
+    {
+        return Ok(());
+    }
+    Err(DescriptorError::Key {
+        error_message: format!("{name} is not a descriptor key expression"),
+    })
+}
+
+/// Check that a relative timelock in blocks is one `older` can express.
+fn check_relative_timelock(delay: u32) -> Result<(), DescriptorError> {
+    match delay {
+        1..=65535 => Ok(()),
+        _ => Err(DescriptorError::InvalidRelativeTimelock { delay }),
+    }
+}
+
+/// A `thresh` miniscript fragment where every satisfied timelock counts as a signature.
+fn decaying_thresh(
+    threshold: u32,
+    keys: &[Zeroizing<String>],
+    decay_delays: &[u32],
+) -> Result<Zeroizing<String>, DescriptorError> {
+    if threshold == 0 || threshold as usize > keys.len() {
+        return Err(DescriptorError::Policy {
+            error_message: format!("threshold {threshold} is invalid for {} keys", keys.len()),
+        });
+    }
+    if decay_delays.len() >= threshold as usize {
+        return Err(DescriptorError::Policy {
+            error_message: format!(
+                "{} decay steps leave no signature required with a threshold of {threshold}",
+                decay_delays.len()
+            ),
+        });
+    }
+
+    for (i, key) in keys.iter().enumerate() {
+        check_template_key(&format!("key {i}"), key)?;
+    }
+    for delay in decay_delays {
+        check_relative_timelock(*delay)?;
+    }
+
+    let subs = keys
+        .iter()
+        .enumerate()
+        .map(|(i, key)| match i {
+            0 => Zeroizing::new(format!("pk({})", **key)),
+            _ => Zeroizing::new(format!("s:pk({})", **key)),
+        })
+        .chain(
+            decay_delays
+                .iter()
+                .map(|delay| Zeroizing::new(format!("sln:older({delay})"))),
+        )
+        .collect::<Vec<_>>();
+    let subs = Zeroizing::new(
+        subs.iter()
+            .map(|sub| sub.as_str())
+            .collect::<Vec<_>>()
+            .join(","),
+    );
+    Ok(Zeroizing::new(format!("thresh({threshold},{})", *subs)))
+}
+
 impl Display for Descriptor {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "{}", self.extended_descriptor)
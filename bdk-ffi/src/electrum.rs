@@ -3,6 +3,7 @@ use crate::error::ElectrumError;
 use crate::types::KeychainKind;
 use crate::types::Update;
 use crate::types::{FullScanRequest, SyncRequest};
+use crate::wallet::Wallet;
 
 use bdk_electrum::electrum_client::HeaderNotification as BdkHeaderNotification;
 use bdk_electrum::electrum_client::ServerFeaturesRes as BdkServerFeaturesRes;
@@ -12,19 +13,20 @@ use bdk_wallet::chain::spk_client::FullScanRequest as BdkFullScanRequest;
 use bdk_wallet::chain::spk_client::FullScanResponse as BdkFullScanResponse;
 use bdk_wallet::chain::spk_client::SyncRequest as BdkSyncRequest;
 use bdk_wallet::chain::spk_client::SyncResponse as BdkSyncResponse;
+use bdk_wallet::chain::CheckPoint;
 use bdk_wallet::Update as BdkUpdate;
 
-use bdk_electrum::electrum_client::ElectrumApi;
+use bdk_electrum::electrum_client::{Config, ElectrumApi, Error as BdkElectrumError};
 use bdk_wallet::bitcoin::hex::{Case, DisplayHex};
 use std::collections::BTreeMap;
 use std::convert::TryFrom;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 use std::time::Duration;
 
 /// Wrapper around an electrum_client::ElectrumApi which includes an internal in-memory transaction
 /// cache to avoid re-fetching already downloaded transactions.
 #[derive(uniffi::Object)]
-pub struct ElectrumClient(BdkBdkElectrumClient<bdk_electrum::electrum_client::Client>);
+pub struct ElectrumClient(pub(crate) BdkBdkElectrumClient<bdk_electrum::electrum_client::Client>);
 
 #[uniffi::export]
 impl ElectrumClient {
@@ -41,21 +43,9 @@ impl ElectrumClient {
         retry: Option<u8>,
         validate_domain: bool,
     ) -> Result<Self, ElectrumError> {
-        let mut config = bdk_electrum::electrum_client::ConfigBuilder::new();
-        config = config.validate_domain(validate_domain);
-        if let Some(timeout) = timeout {
-            config = config.timeout(Some(Duration::from_secs(timeout.into())));
-        }
-        if let Some(retry) = retry {
-            config = config.retry(retry);
-        }
-        if let Some(socks5) = socks5 {
-            config = config.socks5(Some(bdk_electrum::electrum_client::Socks5Config::new(
-                socks5.as_str(),
-            )));
-        }
+        let config = electrum_config(socks5, timeout, retry, validate_domain);
         let inner_client =
-            bdk_electrum::electrum_client::Client::from_config(url.as_str(), config.build())?;
+            bdk_electrum::electrum_client::Client::from_config(url.as_str(), config)?;
         let client = BdkBdkElectrumClient::new(inner_client);
         Ok(Self(client))
     }
@@ -231,6 +221,298 @@ impl ElectrumClient {
     }
 }
 
+type BdkClient = BdkBdkElectrumClient<bdk_electrum::electrum_client::Client>;
+
+/// A pool of Electrum servers failing over to the next server when one cannot be reached.
+///
+/// Servers are connected to lazily and tried in the given order, starting from the last server
+/// that answered. Requests that can be repeated are retried on every server before failing with
+/// `ElectrumError::AllServersFailed`. Errors reported by a server, such as a rejected
+/// transaction, are returned right away as `ElectrumError::Protocol` without trying the others.
+/// Full scans and syncs build their request from the wallet for every server tried, so a scan
+/// interrupted by a server dropping the connection starts over on the next server.
+///
+/// When cross-checking headers, the chain tip of every scan or sync and every fetched block header
+/// is compared with the other servers, and a disagreement fails with
+/// `ElectrumError::HeaderMismatch`. Servers that cannot be reached, or have not seen the block
+/// yet, are not counted as disagreeing.
+#[derive(uniffi::Object)]
+pub struct ElectrumPool {
+    servers: Vec<PoolServer>,
+    config: Config,
+    /// The index of the server tried first.
+    current: Mutex<usize>,
+    cross_check_headers: bool,
+}
+
+struct PoolServer {
+    url: String,
+    client: Mutex<Option<Arc<BdkClient>>>,
+}
+
+#[uniffi::export]
+impl ElectrumPool {
+    /// Creates a pool from the urls of its servers, in order of preference. The proxy, timeout,
+    /// retry attempts and TLS validation apply to every server, as in `ElectrumClient::new`.
+    #[uniffi::constructor(default(
+        socks5 = None,
+        timeout = None,
+        retry = None,
+        validate_domain = true,
+        cross_check_headers = false
+    ))]
+    pub fn new(
+        urls: Vec<String>,
+        socks5: Option<String>,
+        timeout: Option<u8>,
+        retry: Option<u8>,
+        validate_domain: bool,
+        cross_check_headers: bool,
+    ) -> Result<Self, ElectrumError> {
+        if urls.is_empty() {
+            return Err(ElectrumError::NoServers);
+        }
+        let servers = urls
+            .into_iter()
+            .map(|url| PoolServer {
+                url,
+                client: Mutex::new(None),
+            })
+            .collect();
+        Ok(Self {
+            servers,
+            config: electrum_config(socks5, timeout, retry, validate_domain),
+            current: Mutex::new(0),
+            cross_check_headers,
+        })
+    }
+
+    /// The url of the server tried first by the next request.
+    pub fn current_url(&self) -> String {
+        self.servers[*self.current.lock().unwrap()].url.clone()
+    }
+
+    /// Ping every server and ask for its features, returning the status of each server in the
+    /// order they were given. The next request starts from the first healthy server.
+    pub fn health_check(&self) -> Vec<ElectrumServerStatus> {
+        let statuses: Vec<ElectrumServerStatus> = (0..self.servers.len())
+            .map(|index| {
+                let features = self
+                    .call(index, |client| {
+                        client.inner.ping()?;
+                        client.inner.server_features()
+                    })
+                    .map_err(ElectrumError::from)
+                    .and_then(ServerFeaturesRes::try_from);
+                let url = self.servers[index].url.clone();
+                match features {
+                    Ok(features) => ElectrumServerStatus {
+                        url,
+                        features: Some(features),
+                        error_message: None,
+                    },
+                    Err(error) => ElectrumServerStatus {
+                        url,
+                        features: None,
+                        error_message: Some(error.to_string()),
+                    },
+                }
+            })
+            .collect();
+        if let Some(index) = statuses.iter().position(|status| status.features.is_some()) {
+            *self.current.lock().unwrap() = index;
+        }
+        statuses
+    }
+
+    /// Full scan the keychain scripts of the wallet with the blockchain, see
+    /// `ElectrumClient::full_scan`. The request is built with `Wallet::start_full_scan`.
+    pub fn full_scan(
+        &self,
+        wallet: &Wallet,
+        stop_gap: u64,
+        batch_size: u64,
+        fetch_prev_txouts: bool,
+    ) -> Result<Arc<Update>, ElectrumError> {
+        let (index, full_scan_result): (usize, BdkFullScanResponse<KeychainKind>) = self
+            .with_failover(|client| {
+                let request = wallet.get_wallet().start_full_scan().build();
+                client.full_scan(
+                    request,
+                    stop_gap as usize,
+                    batch_size as usize,
+                    fetch_prev_txouts,
+                )
+            })?;
+        if let Some(tip) = &full_scan_result.chain_update {
+            self.cross_check_tip(index, tip)?;
+        }
+
+        let update = BdkUpdate {
+            last_active_indices: full_scan_result.last_active_indices,
+            tx_update: full_scan_result.tx_update,
+            chain: full_scan_result.chain_update,
+        };
+
+        Ok(Arc::new(Update(update)))
+    }
+
+    /// Sync the revealed scripts of the wallet with the blockchain, see `ElectrumClient::sync`.
+    /// The request is built with `Wallet::start_sync_with_revealed_spks`.
+    pub fn sync(
+        &self,
+        wallet: &Wallet,
+        batch_size: u64,
+        fetch_prev_txouts: bool,
+    ) -> Result<Arc<Update>, ElectrumError> {
+        let (index, sync_result): (usize, BdkSyncResponse) = self.with_failover(|client| {
+            let request = wallet.get_wallet().start_sync_with_revealed_spks().build();
+            client.sync(request, batch_size as usize, fetch_prev_txouts)
+        })?;
+        if let Some(tip) = &sync_result.chain_update {
+            self.cross_check_tip(index, tip)?;
+        }
+
+        let update = BdkUpdate {
+            last_active_indices: BTreeMap::default(),
+            tx_update: sync_result.tx_update,
+            chain: sync_result.chain_update,
+        };
+
+        Ok(Arc::new(Update(update)))
+    }
+
+    /// Broadcasts a transaction to the network through the first server that can be reached.
+    pub fn transaction_broadcast(&self, tx: &Transaction) -> Result<Arc<Txid>, ElectrumError> {
+        let bdk_transaction: BdkTransaction = tx.into();
+        self.with_failover(|client| client.transaction_broadcast(&bdk_transaction))
+            .map(|(_, txid)| Arc::new(Txid(txid)))
+    }
+
+    /// Fetch transaction of given `Txid`.
+    pub fn fetch_tx(&self, txid: Arc<Txid>) -> Result<Arc<Transaction>, ElectrumError> {
+        self.with_failover(|client| client.fetch_tx(txid.0))
+            .map(|(_, tx)| Arc::new(Transaction::from(tx.as_ref().clone())))
+    }
+
+    /// Estimates the fee required in bitcoin per kilobyte to confirm a transaction in `number` blocks.
+    pub fn estimate_fee(&self, number: u64) -> Result<f64, ElectrumError> {
+        self.with_failover(|client| client.inner.estimate_fee(number as usize, None))
+            .map(|(_, fee)| fee)
+    }
+
+    /// Gets the block header for height `height`.
+    pub fn block_header(&self, height: u64) -> Result<Header, ElectrumError> {
+        let (index, header) =
+            self.with_failover(|client| client.inner.block_header(height as usize))?;
+        self.cross_check(index, height as u32, header.block_hash())?;
+        Ok(Header::from(header))
+    }
+}
+
+impl ElectrumPool {
+    /// Run a request on the server at `index`, connecting to it first if needed. The connection is
+    /// dropped when the server cannot be reached, so it is reconnected to the next time.
+    fn call<T>(
+        &self,
+        index: usize,
+        request: impl FnOnce(&BdkClient) -> Result<T, BdkElectrumError>,
+    ) -> Result<T, BdkElectrumError> {
+        let server = &self.servers[index];
+        let mut lock = server.client.lock().unwrap();
+        let client = match lock.as_ref() {
+            Some(client) => Arc::clone(client),
+            None => {
+                let inner = bdk_electrum::electrum_client::Client::from_config(
+                    &server.url,
+                    self.config.clone(),
+                )?;
+                let client = Arc::new(BdkBdkElectrumClient::new(inner));
+                *lock = Some(Arc::clone(&client));
+                client
+            }
+        };
+        drop(lock);
+
+        request(&client).inspect_err(|error| {
+            if is_transport_error(error) {
+                *server.client.lock().unwrap() = None;
+                let mut current = self.current.lock().unwrap();
+                if *current == index {
+                    *current = (index + 1) % self.servers.len();
+                }
+            }
+        })
+    }
+
+    /// Run a request on each server in turn until one answers, returning the index of that server
+    /// along with its answer. An error reported by a server is returned without trying the others.
+    fn with_failover<T>(
+        &self,
+        request: impl Fn(&BdkClient) -> Result<T, BdkElectrumError>,
+    ) -> Result<(usize, T), ElectrumError> {
+        let start = *self.current.lock().unwrap();
+        let mut errors = Vec::new();
+        for offset in 0..self.servers.len() {
+            let index = (start + offset) % self.servers.len();
+            match self.call(index, &request) {
+                Ok(response) => {
+                    *self.current.lock().unwrap() = index;
+                    return Ok((index, response));
+                }
+                Err(error) if is_transport_error(&error) => {
+                    errors.push(format!("{}: {error}", self.servers[index].url))
+                }
+                Err(error) => return Err(ElectrumError::from(error)),
+            }
+        }
+        Err(ElectrumError::AllServersFailed {
+            error_message: errors.join(", "),
+        })
+    }
+
+    fn cross_check_tip(&self, index: usize, tip: &CheckPoint) -> Result<(), ElectrumError> {
+        self.cross_check(index, tip.height(), tip.hash())
+    }
+
+    /// Compare the block at `height` reported by the server at `index` with the other servers.
+    fn cross_check(
+        &self,
+        index: usize,
+        height: u32,
+        hash: bdk_wallet::bitcoin::BlockHash,
+    ) -> Result<(), ElectrumError> {
+        if !self.cross_check_headers {
+            return Ok(());
+        }
+        for other in (0..self.servers.len()).filter(|&other| other != index) {
+            let Ok(header) = self.call(other, |client| client.inner.block_header(height as usize))
+            else {
+                continue;
+            };
+            if header.block_hash() != hash {
+                return Err(ElectrumError::HeaderMismatch {
+                    height,
+                    url: self.servers[index].url.clone(),
+                    other_url: self.servers[other].url.clone(),
+                });
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The result of checking a server of an `ElectrumPool`.
+#[derive(uniffi::Record)]
+pub struct ElectrumServerStatus {
+    /// The url of the server.
+    pub url: String,
+    /// The capabilities of the server, if it answered.
+    pub features: Option<ServerFeaturesRes>,
+    /// Why the server could not be reached, if it did not answer.
+    pub error_message: Option<String>,
+}
+
 /// Response to an ElectrumClient.server_features request.
 #[derive(uniffi::Record)]
 pub struct ServerFeaturesRes {
@@ -289,3 +571,41 @@ impl From<BdkHeaderNotification> for HeaderNotification {
         }
     }
 }
+
+/// Whether the error comes from the connection to the server rather than from its answer, so that
+/// another server may succeed.
+fn is_transport_error(error: &BdkElectrumError) -> bool {
+    match error {
+        BdkElectrumError::IOError(_)
+        | BdkElectrumError::SharedIOError(_)
+        | BdkElectrumError::CouldntLockReader
+        | BdkElectrumError::Mpsc
+        | BdkElectrumError::CouldNotCreateConnection(_)
+        | BdkElectrumError::InvalidDNSNameError(_)
+        | BdkElectrumError::MissingDomain => true,
+        BdkElectrumError::AllAttemptsErrored(errors) => errors.iter().all(is_transport_error),
+        _ => false,
+    }
+}
+
+fn electrum_config(
+    socks5: Option<String>,
+    timeout: Option<u8>,
+    retry: Option<u8>,
+    validate_domain: bool,
+) -> Config {
+    let mut config = bdk_electrum::electrum_client::ConfigBuilder::new();
+    config = config.validate_domain(validate_domain);
+    if let Some(timeout) = timeout {
+        config = config.timeout(Some(Duration::from_secs(timeout.into())));
+    }
+    if let Some(retry) = retry {
+        config = config.retry(retry);
+    }
+    if let Some(socks5) = socks5 {
+        config = config.socks5(Some(bdk_electrum::electrum_client::Socks5Config::new(
+            socks5.as_str(),
+        )));
+    }
+    config.build()
+}
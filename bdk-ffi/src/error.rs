@@ -1,3 +1,4 @@
+use crate::secret::Wiped;
 use crate::OutPoint;
 
 use bdk_electrum::electrum_client::Error as BdkElectrumError;
@@ -140,6 +141,31 @@ pub enum Bip39Error {
 
     #[error("ambiguous languages detected: {languages}")]
     AmbiguousLanguages { languages: String },
+
+    #[error("the mnemonic has been wiped")]
+    Wiped,
+}
+
+#[derive(Debug, thiserror::Error, uniffi::Error)]
+#[uniffi::export(Debug, Display)]
+pub enum Bip85Error {
+    #[error("bip 85 derivation requires an extended private key without wildcard")]
+    InvalidKeyType,
+
+    #[error("bip 85 derivation requires a master key without derivation path")]
+    NotMasterKey,
+
+    #[error("error bip 32 related: {error_message}")]
+    Bip32 { error_message: String },
+
+    #[error("the language {language} has no bip 85 code")]
+    UnsupportedLanguage { language: String },
+
+    #[error("{length} bytes of entropy is not between 16 and 64")]
+    InvalidEntropyLength { length: u64 },
+
+    #[error("the secret key has been wiped")]
+    Wiped,
 }
 
 #[derive(Debug, thiserror::Error, uniffi::Error)]
@@ -166,6 +192,23 @@ pub enum CbfError {
     NodeStopped,
 }
 
+#[derive(Debug, thiserror::Error, uniffi::Error)]
+#[uniffi::export(Debug, Display)]
+pub enum CoinSelectionError {
+    #[error("coin selection error: {error_message}")]
+    Reason { error_message: String },
+}
+
+#[derive(Debug, thiserror::Error, uniffi::Error)]
+#[uniffi::export(Debug, Display)]
+pub enum ConsolidationError {
+    #[error("a consolidation transaction needs at least 2 inputs, but max inputs is {max_inputs}")]
+    InvalidMaxInputs { max_inputs: u32 },
+
+    #[error("descriptor error: {error_message}")]
+    Descriptor { error_message: String },
+}
+
 #[derive(Debug, thiserror::Error, uniffi::Error)]
 #[uniffi::export(Debug, Display)]
 pub enum CreateTxError {
@@ -208,6 +251,9 @@ pub enum CreateTxError {
     #[error("coin selection failed: {error_message}")]
     CoinSelection { error_message: String },
 
+    #[error("custom coin selection failed: {error}")]
+    CoinSelector { error: CoinSelectionError },
+
     #[error("insufficient funds: needed {needed} sat, available {available} sat")]
     InsufficientFunds { needed: u64, available: u64 },
 
@@ -234,6 +280,21 @@ pub enum CreateTxError {
 
     #[error("invalid lock time value")]
     LockTimeConversionError,
+
+    #[error("output is already confirmed: {outpoint}")]
+    OutputAlreadyConfirmed { outpoint: String },
+
+    #[error("cannot calculate the fee of the parent transaction: {error_message}")]
+    ParentFeeUnknown { error_message: String },
+
+    #[error("no recipient at index {index}")]
+    InvalidRecipientIndex { index: u64 },
+
+    #[error("recipient at index {index} cannot pay its share of the fee ({fee} sat) without going below the dust limit")]
+    RecipientCannotPayFee { index: u64, fee: u64 },
+
+    #[error("address {address} is not valid for network {network}")]
+    AddressNetworkMismatch { address: String, network: String },
 }
 
 #[derive(Debug, thiserror::Error, uniffi::Error)]
@@ -290,6 +351,15 @@ pub enum DescriptorError {
 
     #[error("external and internal descriptors are the same")]
     ExternalAndInternalAreTheSame,
+
+    #[error("the policy has more than {max} spending paths")]
+    TooManySpendingPaths { max: u64 },
+
+    #[error("relative timelock {delay} is not between 1 and 65535 blocks")]
+    InvalidRelativeTimelock { delay: u32 },
+
+    #[error("the secret key has been wiped")]
+    Wiped,
 }
 
 #[derive(Debug, thiserror::Error, uniffi::Error)]
@@ -306,6 +376,9 @@ pub enum DescriptorKeyError {
 
     #[error("you cannot change a wildcard from hardened to unhardened or the opposite")]
     CannotChangeWildcardType,
+
+    #[error("the secret key has been wiped")]
+    Wiped,
 }
 
 #[derive(Debug, thiserror::Error, uniffi::Error)]
@@ -361,6 +434,19 @@ pub enum ElectrumError {
 
     #[error("the request has already been consumed")]
     RequestAlreadyConsumed,
+
+    #[error("no electrum servers were given")]
+    NoServers,
+
+    #[error("all electrum servers failed: {error_message}")]
+    AllServersFailed { error_message: String },
+
+    #[error("{url} and {other_url} disagree on the block at height {height}")]
+    HeaderMismatch {
+        height: u32,
+        url: String,
+        other_url: String,
+    },
 }
 
 #[derive(Debug, thiserror::Error, uniffi::Error)]
@@ -461,6 +547,28 @@ pub enum HashParseError {
     InvalidHexString { hex: String },
 }
 
+#[derive(Debug, thiserror::Error, uniffi::Error)]
+#[uniffi::export(Debug, Display)]
+pub enum KeystoreError {
+    #[error("invalid keystore: {error_message}")]
+    InvalidKeystore { error_message: String },
+
+    #[error("unsupported keystore version {version}")]
+    UnsupportedVersion { version: u32 },
+
+    #[error("the keystore contains a {content}")]
+    WrongContent { content: String },
+
+    #[error("wrong password")]
+    WrongPassword,
+
+    #[error("the descriptor has no secret keys to encrypt")]
+    NoSecretKeys,
+
+    #[error("the secret has been wiped")]
+    Wiped,
+}
+
 #[derive(Debug, thiserror::Error, uniffi::Error)]
 #[uniffi::export(Debug, Display)]
 pub enum LoadWithPersistError {
@@ -501,6 +609,9 @@ pub enum MiniscriptError {
     #[error("too many keys in checkmultisig: {keys}")]
     CmsTooManyKeys { keys: u32 },
 
+    #[error("compiler error: {error_message}")]
+    CompilerError { error_message: String },
+
     #[error("context error: {error_message}")]
     ContextError { error_message: String },
 
@@ -612,11 +723,48 @@ pub enum ParseAmountError {
     OtherParseAmountErr,
 }
 
+#[derive(Debug, thiserror::Error, uniffi::Error)]
+#[uniffi::export(Debug, Display)]
+pub enum PaymentUriError {
+    #[error("the uri does not use the bitcoin scheme")]
+    InvalidScheme,
+
+    #[error("invalid address: {error_message}")]
+    InvalidAddress { error_message: String },
+
+    #[error("invalid amount: {error_message}")]
+    InvalidAmount { error_message: String },
+
+    #[error("invalid percent-encoding in parameter {parameter}")]
+    InvalidEncoding { parameter: String },
+
+    #[error("parameter {parameter} appears more than once")]
+    DuplicateParameter { parameter: String },
+
+    #[error("unsupported required parameter {parameter}")]
+    UnsupportedRequiredParameter { parameter: String },
+
+    #[error("the uri has no amount and none was given")]
+    MissingAmount,
+}
+
 #[derive(Debug, thiserror::Error, uniffi::Error)]
 #[uniffi::export(Debug, Display)]
 pub enum PersistenceError {
     #[error("persistence error: {error_message}")]
     Reason { error_message: String },
+
+    #[error("the encryption key must be 32 bytes, not {length}")]
+    InvalidKeyLength { length: u64 },
+
+    #[error("the persister is not encrypted")]
+    NotEncrypted,
+
+    #[error("the encrypted database was modified outside of the persister")]
+    Tampered,
+
+    #[error("the database is encrypted with a different key")]
+    WrongKey,
 }
 
 #[derive(Debug, thiserror::Error, uniffi::Error)]
@@ -761,6 +909,19 @@ pub enum PsbtParseError {
     Base64Encoding { error_message: String },
 }
 
+#[derive(Debug, thiserror::Error, uniffi::Error)]
+#[uniffi::export(Debug, Display)]
+pub enum RebroadcastError {
+    #[error("esplora broadcast failed: {error}")]
+    Esplora { error: EsploraError },
+
+    #[error("electrum broadcast failed: {error}")]
+    Electrum { error: ElectrumError },
+
+    #[error("cbf broadcast failed: {error}")]
+    Cbf { error: CbfError },
+}
+
 #[derive(Debug, thiserror::Error, uniffi::Error)]
 #[uniffi::export(Debug, Display)]
 pub enum RequestBuilderError {
@@ -830,6 +991,95 @@ pub enum SignerError {
     Psbt { error_message: String },
 }
 
+#[derive(Debug, thiserror::Error, uniffi::Error)]
+#[uniffi::export(Debug, Display)]
+pub enum Slip39Error {
+    #[error("{provided} of the {required} groups needed to recover the secret were provided")]
+    InsufficientGroups { required: u8, provided: u8 },
+
+    #[error("group {group_index} has {provided} of the {required} shares needed to recover it")]
+    InsufficientShares {
+        group_index: u8,
+        required: u8,
+        provided: u8,
+    },
+
+    #[error("checksum of share {index} is invalid")]
+    InvalidChecksum { index: u64 },
+
+    #[error("the digest of the recovered secret does not match, one of the shares is invalid")]
+    InvalidDigest,
+
+    #[error("invalid groups: {error_message}")]
+    InvalidGroups { error_message: String },
+
+    #[error("the recovered secret is not a valid master key: {error_message}")]
+    InvalidMasterKey { error_message: String },
+
+    #[error("the passphrase must only contain printable ASCII characters")]
+    InvalidPassphrase,
+
+    #[error("share {index} is invalid: {error_message}")]
+    InvalidShare { index: u64, error_message: String },
+
+    #[error("the shares belong to different backups or have inconsistent parameters")]
+    MismatchedShares,
+
+    #[error("the shares hold a mnemonic, recover them as a mnemonic")]
+    MnemonicShares,
+
+    #[error(
+        "the shares do not hold a mnemonic, they are standard shares or the passphrase is wrong"
+    )]
+    NotMnemonicShares,
+
+    #[error("no shares were provided")]
+    NoShares,
+
+    #[error("a secret of {length} bytes is not supported")]
+    UnsupportedSecretLength { length: u64 },
+
+    #[error("the mnemonic has been wiped")]
+    Wiped,
+}
+
+#[derive(Debug, thiserror::Error, uniffi::Error)]
+#[uniffi::export(Debug, Display)]
+pub enum SweepError {
+    #[error("invalid WIF private key: {error_message}")]
+    InvalidWif { error_message: String },
+
+    #[error("invalid BIP38 encrypted private key: {error_message}")]
+    InvalidBip38 { error_message: String },
+
+    #[error("wrong passphrase for the BIP38 encrypted private key")]
+    WrongPassphrase,
+
+    #[error("there are no utxos to sweep")]
+    NoUtxos,
+
+    #[error("utxo {outpoint} cannot be spent by the swept key")]
+    UnknownScript { outpoint: String },
+
+    #[error("failed to create the sweep transaction: {error_message}")]
+    CreateTx { error_message: String },
+
+    #[error("failed to sign the sweep transaction: {error_message}")]
+    Signer { error_message: String },
+
+    #[error("the server listed utxo {outpoint} which its transaction does not have")]
+    InvalidUtxo { outpoint: String },
+
+    #[error("esplora error: {error_message}")]
+    Esplora { error_message: String },
+
+    #[error("electrum error: {error_message}")]
+    Electrum { error_message: String },
+
+    #[error("the private key has been wiped")]
+    Wiped,
+}
+
 #[derive(Debug, thiserror::Error, uniffi::Error)]
 #[uniffi::export(Debug, Display)]
 pub enum TransactionError {
@@ -856,6 +1106,19 @@ pub enum TransactionError {
     OtherTransactionErr,
 }
 
+#[derive(Debug, thiserror::Error, uniffi::Error)]
+#[uniffi::export(Debug, Display)]
+pub enum TxDraftError {
+    #[error("invalid draft: {error_message}")]
+    InvalidDraft { error_message: String },
+
+    #[error("unsupported draft version {version}")]
+    UnsupportedVersion { version: u32 },
+
+    #[error("a custom coin selection algorithm cannot be saved in a draft")]
+    CustomCoinSelection,
+}
+
 #[derive(Debug, thiserror::Error, uniffi::Error)]
 #[uniffi::export(Debug, Display)]
 pub enum TxidParseError {
@@ -1023,6 +1286,26 @@ impl From<BdkBip39Error> for Bip39Error {
     }
 }
 
+impl From<Wiped> for Bip39Error {
+    fn from(_: Wiped) -> Self {
+        Bip39Error::Wiped
+    }
+}
+
+impl From<BdkBip32Error> for Bip85Error {
+    fn from(error: BdkBip32Error) -> Self {
+        Bip85Error::Bip32 {
+            error_message: error.to_string(),
+        }
+    }
+}
+
+impl From<Wiped> for Bip85Error {
+    fn from(_: Wiped) -> Self {
+        Bip85Error::Wiped
+    }
+}
+
 impl From<BdkCalculateFeeError> for CalculateFeeError {
     fn from(error: BdkCalculateFeeError) -> Self {
         match error {
@@ -1222,6 +1505,12 @@ impl From<BdkDescriptorError> for DescriptorError {
     }
 }
 
+impl From<Wiped> for DescriptorError {
+    fn from(_: Wiped) -> Self {
+        DescriptorError::Wiped
+    }
+}
+
 impl From<BdkDescriptorKeyParseError> for DescriptorKeyError {
     fn from(err: BdkDescriptorKeyParseError) -> DescriptorKeyError {
         DescriptorKeyError::Parse {
@@ -1238,6 +1527,12 @@ impl From<BdkBip32Error> for DescriptorKeyError {
     }
 }
 
+impl From<Wiped> for DescriptorKeyError {
+    fn from(_: Wiped) -> Self {
+        DescriptorKeyError::Wiped
+    }
+}
+
 impl From<BdkEsploraError> for EsploraError {
     fn from(error: BdkEsploraError) -> Self {
         match error {
@@ -1359,6 +1654,12 @@ impl From<BdkFromScriptError> for FromScriptError {
     }
 }
 
+impl From<Wiped> for KeystoreError {
+    fn from(_: Wiped) -> Self {
+        KeystoreError::Wiped
+    }
+}
+
 impl From<BdkLoadWithPersistError<chain::rusqlite::Error>> for LoadWithPersistError {
     fn from(error: BdkLoadWithPersistError<chain::rusqlite::Error>) -> Self {
         match error {
@@ -1435,6 +1736,9 @@ impl From<bdk_wallet::miniscript::Error> for MiniscriptError {
             }
             BdkMiniscriptError::BareDescriptorAddr => MiniscriptError::BareDescriptorAddr,
             BdkMiniscriptError::CmsTooManyKeys(n) => MiniscriptError::CmsTooManyKeys { keys: n },
+            BdkMiniscriptError::CompilerError(e) => MiniscriptError::CompilerError {
+                error_message: e.to_string(),
+            },
             BdkMiniscriptError::ContextError(e) => MiniscriptError::ContextError {
                 error_message: e.to_string(),
             },
@@ -1665,6 +1969,42 @@ impl From<BdkSignerError> for SignerError {
     }
 }
 
+impl From<Wiped> for Slip39Error {
+    fn from(_: Wiped) -> Self {
+        Slip39Error::Wiped
+    }
+}
+
+impl From<AddForeignUtxoError> for SweepError {
+    fn from(error: AddForeignUtxoError) -> Self {
+        SweepError::CreateTx {
+            error_message: error.to_string(),
+        }
+    }
+}
+
+impl From<CreateTxError> for SweepError {
+    fn from(error: CreateTxError) -> Self {
+        SweepError::CreateTx {
+            error_message: error.to_string(),
+        }
+    }
+}
+
+impl From<SignerError> for SweepError {
+    fn from(error: SignerError) -> Self {
+        SweepError::Signer {
+            error_message: error.to_string(),
+        }
+    }
+}
+
+impl From<Wiped> for SweepError {
+    fn from(_: Wiped) -> Self {
+        SweepError::Wiped
+    }
+}
+
 impl From<BdkEncodeError> for TransactionError {
     fn from(error: BdkEncodeError) -> Self {
         match error {
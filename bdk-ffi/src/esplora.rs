@@ -26,7 +26,7 @@ use std::sync::Arc;
 /// Wrapper around an esplora_client::BlockingClient which includes an internal in-memory transaction
 /// cache to avoid re-fetching already downloaded transactions.
 #[derive(uniffi::Object)]
-pub struct EsploraClient(BlockingClient);
+pub struct EsploraClient(pub(crate) BlockingClient);
 
 #[uniffi::export]
 impl EsploraClient {
@@ -1,31 +1,46 @@
 use crate::bitcoin::{ChildNumber, NetworkKind};
-use crate::error::{Bip32Error, Bip39Error, DescriptorKeyError};
+use crate::descriptor::Descriptor;
+use crate::error::{
+    Bip32Error, Bip39Error, Bip85Error, DescriptorError, DescriptorKeyError, Slip39Error,
+};
+use crate::secret::{Erase, Secret};
+use crate::slip39::{self, Slip39Group};
 use crate::{impl_from_core_type, impl_into_core_type};
 
+use bdk_wallet::bitcoin::base58;
 use bdk_wallet::bitcoin::bip32::ChildNumber as BdkChildNumber;
 use bdk_wallet::bitcoin::bip32::DerivationPath as BdkDerivationPath;
+use bdk_wallet::bitcoin::bip32::{ChainCode, Error as BdkBip32Error, Fingerprint, Xpriv, Xpub};
+use bdk_wallet::bitcoin::hashes::hmac::{Hmac, HmacEngine};
+use bdk_wallet::bitcoin::hashes::{sha512, Hash, HashEngine};
+use bdk_wallet::bitcoin::hex::DisplayHex;
 use bdk_wallet::bitcoin::key::Secp256k1;
 use bdk_wallet::bitcoin::secp256k1::rand;
 use bdk_wallet::bitcoin::secp256k1::rand::Rng;
+use bdk_wallet::bitcoin::secp256k1::SecretKey;
+use bdk_wallet::bitcoin::{NetworkKind as BdkNetworkKind, PrivateKey};
 use bdk_wallet::keys::bip39::WordCount;
 use bdk_wallet::keys::bip39::{Language, Mnemonic as BdkMnemonic};
 use bdk_wallet::keys::{
-    DerivableKey, DescriptorPublicKey as BdkDescriptorPublicKey,
-    DescriptorSecretKey as BdkDescriptorSecretKey, ExtendedKey, GeneratableKey, GeneratedKey,
+    DescriptorPublicKey as BdkDescriptorPublicKey, DescriptorSecretKey as BdkDescriptorSecretKey,
+    GeneratableKey, GeneratedKey,
 };
 use bdk_wallet::miniscript::descriptor::{DescriptorXKey, Wildcard};
 use bdk_wallet::miniscript::BareCtx;
 
-use crate::types::WildcardType;
+use crate::types::{Bip48ScriptType, Cosigner, KeychainKind, Slip132ScriptType, WildcardType};
+use zeroize::{Zeroize, Zeroizing};
+
+use std::borrow::Cow;
 use std::convert::TryFrom;
 use std::fmt::Display;
 use std::str::FromStr;
-use std::sync::Arc;
+use std::sync::{Arc, PoisonError, RwLock};
 
 /// A mnemonic seed phrase to recover a BIP-32 wallet.
 #[derive(uniffi::Object)]
 #[uniffi::export(Display)]
-pub struct Mnemonic(BdkMnemonic);
+pub struct Mnemonic(pub(crate) Secret<BdkMnemonic>, Language);
 
 #[uniffi::export]
 impl Mnemonic {
@@ -39,14 +54,36 @@ impl Mnemonic {
         let generated_key: GeneratedKey<_, BareCtx> =
             BdkMnemonic::generate_with_entropy((word_count, Language::English), entropy).unwrap();
         let mnemonic = BdkMnemonic::parse_in(Language::English, generated_key.to_string()).unwrap();
-        Mnemonic(mnemonic)
+        Mnemonic::from(mnemonic)
+    }
+
+    /// Generate a mnemonic given a word count, using the wordlist of a language.
+    #[uniffi::constructor]
+    pub fn new_in(word_count: WordCount, language: Language) -> Self {
+        let mut rng = rand::thread_rng();
+        let mut entropy = [0u8; 32];
+        rng.fill(&mut entropy);
+
+        let generated_key: GeneratedKey<_, BareCtx> =
+            BdkMnemonic::generate_with_entropy((word_count, language), entropy).unwrap();
+        let mnemonic = BdkMnemonic::parse_in(language, generated_key.to_string()).unwrap();
+        Mnemonic::from(mnemonic)
     }
 
-    /// Parse a string as a mnemonic seed phrase.
+    /// Parse a string as a mnemonic seed phrase. The language is detected from the words.
     #[uniffi::constructor]
     pub fn from_string(mnemonic: String) -> Result<Self, Bip39Error> {
         BdkMnemonic::from_str(&mnemonic)
-            .map(Mnemonic)
+            .map(Mnemonic::from)
+            .map_err(Bip39Error::from)
+    }
+
+    /// Parse a string as a mnemonic seed phrase in the given language, for phrases whose words
+    /// appear in more than one wordlist.
+    #[uniffi::constructor]
+    pub fn from_string_in(mnemonic: String, language: Language) -> Result<Self, Bip39Error> {
+        BdkMnemonic::parse_in(language, mnemonic)
+            .map(Mnemonic::from)
             .map_err(Bip39Error::from)
     }
 
@@ -57,14 +94,214 @@ impl Mnemonic {
     #[uniffi::constructor]
     pub fn from_entropy(entropy: Vec<u8>) -> Result<Self, Bip39Error> {
         BdkMnemonic::from_entropy(entropy.as_slice())
-            .map(Mnemonic)
+            .map(Mnemonic::from)
+            .map_err(Bip39Error::from)
+    }
+
+    /// Construct a mnemonic in the given language from caller-provided entropy, see
+    /// `Mnemonic::from_entropy`.
+    #[uniffi::constructor]
+    pub fn from_entropy_in(entropy: Vec<u8>, language: Language) -> Result<Self, Bip39Error> {
+        BdkMnemonic::from_entropy_in(language, entropy.as_slice())
+            .map(Mnemonic::from)
             .map_err(Bip39Error::from)
     }
+
+    /// Recover a mnemonic from the SLIP-39 shares created with `Mnemonic::to_slip39_shares`,
+    /// in the language it was split in.
+    ///
+    /// The shares hold the entropy of the mnemonic rather than the BIP-32 seed defined by SLIP-39,
+    /// so this is not interoperable with other SLIP-39 implementations: shares made by hardware
+    /// wallets are restored with `DescriptorSecretKey::from_slip39_shares`, and are rejected here
+    /// with `Slip39Error::NotMnemonicShares`. So is a wrong passphrase, in most cases.
+    #[uniffi::constructor(default(passphrase = None))]
+    pub fn from_slip39_shares(
+        shares: Vec<String>,
+        passphrase: Option<String>,
+    ) -> Result<Self, Slip39Error> {
+        let secret = Zeroizing::new(slip39::combine(
+            &shares,
+            passphrase.as_deref().unwrap_or_default(),
+        )?);
+        let (language, entropy) =
+            slip39_mnemonic_secret(&secret).ok_or(Slip39Error::NotMnemonicShares)?;
+        BdkMnemonic::from_entropy_in(language, entropy)
+            .map(Mnemonic::from)
+            .map_err(|_| Slip39Error::UnsupportedSecretLength {
+                length: entropy.len() as u64,
+            })
+    }
+
+    /// The language of the words of the mnemonic.
+    pub fn language(&self) -> Language {
+        self.1
+    }
+
+    /// Erase the mnemonic from memory without waiting for the object to be dropped. Afterwards the
+    /// methods needing the mnemonic fail with a `Wiped` error and it displays as `<wiped>`.
+    ///
+    /// Keys and keystores already created from the mnemonic are not erased.
+    pub fn wipe(&self) {
+        self.0.wipe()
+    }
+
+    /// Split the entropy of the mnemonic into SLIP-39 shares, one list of share mnemonics per
+    /// group. Recovering the mnemonic takes `group_threshold` of the groups, each restored from
+    /// its member threshold of shares.
+    ///
+    /// The optional passphrase encrypts the entropy and is required again for recovery.
+    ///
+    /// The shares hold the language and entropy of the mnemonic rather than a BIP-32 seed, so other
+    /// SLIP-39 implementations restore a different wallet from them. They can only be recovered
+    /// with `Mnemonic::from_slip39_shares`. They can be told apart from standard shares by their
+    /// length: they have 22, 25, 28, 31 or 35 words, where shares of a 128 or 256-bit seed have 20
+    /// or 33.
+    #[uniffi::method(default(passphrase = None))]
+    pub fn to_slip39_shares(
+        &self,
+        group_threshold: u8,
+        groups: Vec<Slip39Group>,
+        passphrase: Option<String>,
+    ) -> Result<Vec<Vec<String>>, Slip39Error> {
+        let mnemonic = self.0.read()?;
+        let language = SLIP39_LANGUAGES
+            .iter()
+            .position(|language| *language == mnemonic.language())
+            .expect("every language has a code") as u8;
+        let mut secret = Zeroizing::new(vec![SLIP39_MNEMONIC_MAGIC, language]);
+        secret.extend_from_slice(&Zeroizing::new(mnemonic.to_entropy()));
+        drop(mnemonic);
+        slip39::split(
+            &secret,
+            group_threshold,
+            &groups,
+            passphrase.as_deref().unwrap_or_default(),
+        )
+    }
+}
+
+/// The first byte of the secret split by `Mnemonic::to_slip39_shares`, followed by the code of the
+/// language of the mnemonic and its entropy. Standard secrets are BIP-32 seeds of 16 or 32 bytes,
+/// while this header makes the secret of a mnemonic 18 to 34 bytes long.
+const SLIP39_MNEMONIC_MAGIC: u8 = 0xb3;
+
+/// The languages of mnemonics split into SLIP-39 shares, indexed by their code.
+const SLIP39_LANGUAGES: [Language; 10] = [
+    Language::English,
+    Language::SimplifiedChinese,
+    Language::TraditionalChinese,
+    Language::Czech,
+    Language::French,
+    Language::Italian,
+    Language::Japanese,
+    Language::Korean,
+    Language::Portuguese,
+    Language::Spanish,
+];
+
+/// The language and entropy of a SLIP-39 secret made by `Mnemonic::to_slip39_shares`, or `None`
+/// for a standard secret.
+fn slip39_mnemonic_secret(secret: &[u8]) -> Option<(Language, &[u8])> {
+    match secret {
+        [SLIP39_MNEMONIC_MAGIC, language, entropy @ ..] if entropy.len() % 4 == 0 => {
+            Some((*SLIP39_LANGUAGES.get(*language as usize)?, entropy))
+        }
+        _ => None,
+    }
+}
+
+/// A BIP-39 wordlist, to help users enter their mnemonic.
+#[derive(Debug, uniffi::Object)]
+pub struct Wordlist(Language);
+
+#[uniffi::export]
+impl Wordlist {
+    #[uniffi::constructor]
+    pub fn new(language: Language) -> Self {
+        Wordlist(language)
+    }
+
+    /// The language of the wordlist.
+    pub fn language(&self) -> Language {
+        self.0
+    }
+
+    /// The 2048 words of the wordlist.
+    pub fn words(&self) -> Vec<String> {
+        self.0
+            .word_list()
+            .iter()
+            .map(|word| word.to_string())
+            .collect()
+    }
+
+    /// The words starting with `prefix`, to suggest completions while the user types.
+    pub fn suggestions(&self, prefix: String) -> Vec<String> {
+        self.0
+            .words_by_prefix(&normalize(prefix))
+            .iter()
+            .map(|word| word.to_string())
+            .collect()
+    }
+
+    /// Whether the word is in the wordlist.
+    pub fn is_valid_word(&self, word: String) -> bool {
+        self.0.find_word(&normalize(word)).is_some()
+    }
+
+    /// Every word completing `words` into a mnemonic with a valid checksum. The words must be one
+    /// fewer than a valid mnemonic length (e.g. 11 or 23).
+    pub fn final_words(&self, words: Vec<String>) -> Result<Vec<String>, Bip39Error> {
+        let word_count = words.len() + 1;
+        if !word_count.is_multiple_of(3) || !(12..=24).contains(&word_count) {
+            return Err(Bip39Error::BadWordCount {
+                word_count: word_count as u64,
+            });
+        }
+        let words = words.into_iter().map(normalize).collect::<Vec<_>>();
+        if let Some(index) = words
+            .iter()
+            .position(|word| self.0.find_word(word).is_none())
+        {
+            return Err(Bip39Error::UnknownWord {
+                index: index as u64,
+            });
+        }
+
+        // Only the checksum bits of the last word are constrained, try them all.
+        let phrase = words.join(" ");
+        Ok(self
+            .0
+            .word_list()
+            .iter()
+            .filter(|word| {
+                BdkMnemonic::parse_in_normalized(self.0, &format!("{phrase} {word}")).is_ok()
+            })
+            .map(|word| word.to_string())
+            .collect())
+    }
+}
+
+/// Normalize user input to the NFKD form of the wordlists.
+fn normalize(word: String) -> String {
+    let mut word = Cow::Owned(word.trim().to_lowercase());
+    BdkMnemonic::normalize_utf8_cow(&mut word);
+    word.into_owned()
+}
+
+impl From<BdkMnemonic> for Mnemonic {
+    fn from(mnemonic: BdkMnemonic) -> Self {
+        let language = mnemonic.language();
+        Mnemonic(mnemonic.into(), language)
+    }
 }
 
 impl Display for Mnemonic {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{}", self.0)
+        match self.0.read() {
+            Ok(mnemonic) => write!(f, "{}", *mnemonic),
+            Err(_) => write!(f, "<wiped>"),
+        }
     }
 }
 
@@ -135,24 +372,126 @@ impl_from_core_type!(BdkDerivationPath, DerivationPath);
 impl_into_core_type!(DerivationPath, BdkDerivationPath);
 
 /// The descriptor secret key, either a single private key or an xprv.
-#[derive(Debug, uniffi::Object)]
+#[derive(uniffi::Object)]
 #[uniffi::export(Debug, Display)]
-pub struct DescriptorSecretKey(pub(crate) BdkDescriptorSecretKey);
+pub struct DescriptorSecretKey(
+    pub(crate) Secret<BdkDescriptorSecretKey>,
+    RwLock<Option<Arc<WipedKey>>>,
+);
+
+/// The public keys a wiped [`DescriptorSecretKey`] still provides, taken from it before erasing it.
+struct WipedKey {
+    public_key: BdkDescriptorPublicKey,
+    fingerprint: Fingerprint,
+    /// The account keys of the BIP-44, 49, 84 and 86 templates, by purpose and network kind.
+    accounts: Vec<(u32, BdkNetworkKind, Xpub)>,
+}
+
+impl WipedKey {
+    fn new(descriptor_secret_key: &BdkDescriptorSecretKey) -> Self {
+        let secp = Secp256k1::new();
+        let public_key = descriptor_secret_key.to_public(&secp).unwrap();
+        let BdkDescriptorSecretKey::XPrv(descriptor_x_key) = descriptor_secret_key else {
+            return WipedKey {
+                public_key,
+                fingerprint: Fingerprint::default(),
+                accounts: Vec::new(),
+            };
+        };
+        let xkey = &descriptor_x_key.xkey;
+        let mut accounts = Vec::new();
+        for purpose in [44, 49, 84, 86] {
+            for (network_kind, coin_type) in [(BdkNetworkKind::Main, 0), (BdkNetworkKind::Test, 1)]
+            {
+                let path = [purpose, coin_type, 0]
+                    .iter()
+                    .map(|&index| BdkChildNumber::from_hardened_idx(index).unwrap())
+                    .collect::<Vec<_>>();
+                let mut account = xkey.derive_priv(&secp, &path).unwrap();
+                accounts.push((purpose, network_kind, Xpub::from_priv(&secp, &account)));
+                account.erase();
+            }
+        }
+        WipedKey {
+            public_key,
+            fingerprint: xkey.fingerprint(&secp),
+            accounts,
+        }
+    }
+}
+
+/// The key a descriptor template is built from.
+pub(crate) enum TemplateKey {
+    Secret(Xpriv),
+    /// The account key and master fingerprint of a wiped key.
+    Public(Xpub, Fingerprint),
+}
 
 #[uniffi::export]
 impl DescriptorSecretKey {
-    /// Construct a secret descriptor key using a mnemonic.
+    /// Construct a secret descriptor key using a mnemonic. The BIP-32 seed is erased from memory
+    /// once the master key is derived from it.
+    ///
+    /// A wiped mnemonic gives a wiped key, made from a random seed that is erased right away.
     #[uniffi::constructor]
     pub fn new(network_kind: NetworkKind, mnemonic: &Mnemonic, password: Option<String>) -> Self {
-        let mnemonic = mnemonic.0.clone();
-        let xkey: ExtendedKey = (mnemonic, password).into_extended_key().unwrap();
+        let password = password.map(Zeroizing::new);
+        let (mut seed, wiped) = match mnemonic.0.read() {
+            Ok(mnemonic) => (
+                mnemonic.to_seed(password.as_deref().map(String::as_str).unwrap_or_default()),
+                false,
+            ),
+            Err(_) => {
+                let mut seed = [0u8; 64];
+                rand::thread_rng().fill(&mut seed);
+                (seed, true)
+            }
+        };
+        let xprv = Xpriv::new_master(network_kind, &seed).unwrap();
+        seed.zeroize();
+        let descriptor_secret_key = Self::from(BdkDescriptorSecretKey::XPrv(DescriptorXKey {
+            origin: None,
+            xkey: xprv,
+            derivation_path: BdkDerivationPath::master(),
+            wildcard: Wildcard::None,
+        }));
+        if wiped {
+            descriptor_secret_key.wipe();
+        }
+        descriptor_secret_key
+    }
+
+    /// Recover a master key from SLIP-39 shares, such as the shares backing up a hardware wallet.
+    /// As defined by SLIP-39, the recovered master secret is the BIP-32 seed of the key.
+    ///
+    /// Shares made with `Mnemonic::to_slip39_shares` are rejected with
+    /// `Slip39Error::MnemonicShares`: they are restored with `Mnemonic::from_slip39_shares`.
+    ///
+    /// A wrong passphrase cannot be detected and recovers a different, valid key.
+    #[uniffi::constructor(default(passphrase = None))]
+    pub fn from_slip39_shares(
+        network_kind: NetworkKind,
+        shares: Vec<String>,
+        passphrase: Option<String>,
+    ) -> Result<Self, Slip39Error> {
+        let seed = Zeroizing::new(slip39::combine(
+            &shares,
+            passphrase.as_deref().unwrap_or_default(),
+        )?);
+        if slip39_mnemonic_secret(&seed).is_some() {
+            return Err(Slip39Error::MnemonicShares);
+        }
+        let xprv = Xpriv::new_master(network_kind, &seed);
+        let xprv = xprv.map_err(|e| Slip39Error::InvalidMasterKey {
+            error_message: e.to_string(),
+        })?;
         let descriptor_secret_key = BdkDescriptorSecretKey::XPrv(DescriptorXKey {
             origin: None,
-            xkey: xkey.into_xprv(network_kind).unwrap(),
+            xkey: xprv,
             derivation_path: BdkDerivationPath::master(),
             wildcard: Wildcard::None,
         });
-        Self(descriptor_secret_key)
+        Ok(Self::from(descriptor_secret_key))
     }
 
     /// Attempt to parse a string as a descriptor secret key.
@@ -160,14 +499,14 @@ impl DescriptorSecretKey {
     pub fn from_string(private_key: String) -> Result<Self, DescriptorKeyError> {
         let descriptor_secret_key = BdkDescriptorSecretKey::from_str(private_key.as_str())
             .map_err(DescriptorKeyError::from)?;
-        Ok(Self(descriptor_secret_key))
+        Ok(Self::from(descriptor_secret_key))
     }
 
     /// Derive a descriptor secret key at a given derivation path.
     pub fn derive(&self, path: &DerivationPath) -> Result<Arc<Self>, DescriptorKeyError> {
         let secp = Secp256k1::new();
-        let descriptor_secret_key = &self.0;
-        match descriptor_secret_key {
+        let descriptor_secret_key = self.0.read()?;
+        match &*descriptor_secret_key {
             BdkDescriptorSecretKey::Single(_) => Err(DescriptorKeyError::InvalidKeyType),
             BdkDescriptorSecretKey::XPrv(descriptor_x_key) => {
                 let derived_xprv = descriptor_x_key
@@ -184,7 +523,7 @@ impl DescriptorSecretKey {
                     derivation_path: BdkDerivationPath::default(),
                     wildcard: descriptor_x_key.wildcard,
                 });
-                Ok(Arc::new(Self(derived_descriptor_secret_key)))
+                Ok(Arc::new(Self::from(derived_descriptor_secret_key)))
             }
             BdkDescriptorSecretKey::MultiXPrv(_) => Err(DescriptorKeyError::InvalidKeyType),
         }
@@ -192,8 +531,8 @@ impl DescriptorSecretKey {
 
     /// Extend the descriptor secret key by the derivation path.
     pub fn extend(&self, path: &DerivationPath) -> Result<Arc<Self>, DescriptorKeyError> {
-        let descriptor_secret_key = &self.0;
-        match descriptor_secret_key {
+        let descriptor_secret_key = self.0.read()?;
+        match &*descriptor_secret_key {
             BdkDescriptorSecretKey::Single(_) => Err(DescriptorKeyError::InvalidKeyType),
             BdkDescriptorSecretKey::XPrv(descriptor_x_key) => {
                 let extended_path = descriptor_x_key.derivation_path.extend(&path.0);
@@ -203,7 +542,7 @@ impl DescriptorSecretKey {
                     derivation_path: extended_path,
                     wildcard: descriptor_x_key.wildcard,
                 });
-                Ok(Arc::new(Self(extended_descriptor_secret_key)))
+                Ok(Arc::new(Self::from(extended_descriptor_secret_key)))
             }
             BdkDescriptorSecretKey::MultiXPrv(_) => Err(DescriptorKeyError::InvalidKeyType),
         }
@@ -217,8 +556,8 @@ impl DescriptorSecretKey {
         &self,
         wildcard_type: WildcardType,
     ) -> Result<Arc<Self>, DescriptorKeyError> {
-        let descriptor_secret_key = &self.0;
-        match descriptor_secret_key {
+        let descriptor_secret_key = self.0.read()?;
+        match &*descriptor_secret_key {
             BdkDescriptorSecretKey::Single(_) => Err(DescriptorKeyError::InvalidKeyType),
             BdkDescriptorSecretKey::XPrv(descriptor_x_key) => {
                 let same_wildcard = (descriptor_x_key.wildcard == Wildcard::Unhardened
@@ -236,7 +575,7 @@ impl DescriptorSecretKey {
                             derivation_path: descriptor_x_key.derivation_path.clone(),
                             wildcard: descriptor_x_key.wildcard,
                         });
-                    Ok(Arc::new(Self(descriptor_secret_key_with_wildcard)))
+                    Ok(Arc::new(Self::from(descriptor_secret_key_with_wildcard)))
                 } else if descriptor_x_key.wildcard == Wildcard::None {
                     // If the descriptor doesn't have a wildcard, add the requested wildcard
                     let descriptor_secret_key_with_wildcard =
@@ -246,7 +585,7 @@ impl DescriptorSecretKey {
                             derivation_path: descriptor_x_key.derivation_path.clone(),
                             wildcard: wildcard_type.into(),
                         });
-                    Ok(Arc::new(Self(descriptor_secret_key_with_wildcard)))
+                    Ok(Arc::new(Self::from(descriptor_secret_key_with_wildcard)))
                 } else {
                     // If the descriptor already has a wildcard of a different type, return an error
                     Err(DescriptorKeyError::CannotChangeWildcardType)
@@ -256,17 +595,110 @@ impl DescriptorSecretKey {
         }
     }
 
-    /// Return the descriptor public key corresponding to this secret.
+    /// Derive the BIP-48 multisig account key `m/48'/coin'/account'/script_type'` from this
+    /// master key, with its origin. The coin type is 0 for mainnet and 1 for the test networks.
+    pub fn derive_bip48_account(
+        &self,
+        script_type: Bip48ScriptType,
+        account: u32,
+        network_kind: NetworkKind,
+    ) -> Result<Arc<Self>, DescriptorKeyError> {
+        self.derive(&bip48_account_path(script_type, account, network_kind)?)
+    }
+
+    /// Derive a BIP-39 mnemonic from this master key following
+    /// [BIP-85](https://github.com/bitcoin/bips/blob/master/bip-0085.mediawiki), to seed a separate
+    /// wallet. Each index gives an unrelated mnemonic.
+    ///
+    /// Like the other BIP-85 methods, this fails with `Bip85Error::NotMasterKey` for keys that
+    /// are not the master key itself, such as account keys.
+    pub fn bip85_mnemonic(
+        &self,
+        word_count: WordCount,
+        language: Language,
+        index: u32,
+    ) -> Result<Arc<Mnemonic>, Bip85Error> {
+        let language_code = match language {
+            Language::English => 0,
+            Language::Japanese => 1,
+            Language::Korean => 2,
+            Language::Spanish => 3,
+            Language::SimplifiedChinese => 4,
+            Language::TraditionalChinese => 5,
+            Language::French => 6,
+            Language::Italian => 7,
+            Language::Czech => 8,
+            Language::Portuguese => {
+                return Err(Bip85Error::UnsupportedLanguage {
+                    language: format!("{language:?}"),
+                })
+            }
+        };
+        let entropy_bits = word_count as usize;
+        let words = (entropy_bits * 3 / 32) as u32;
+        let entropy = self.bip85_entropy(&[39, language_code, words, index])?;
+        let mnemonic = BdkMnemonic::from_entropy_in(language, &entropy[..entropy_bits / 8])
+            .expect("valid entropy length");
+        Ok(Arc::new(Mnemonic::from(mnemonic)))
+    }
+
+    /// Derive a private key in Wallet Import Format from this master key following BIP-85.
+    pub fn bip85_wif(&self, index: u32) -> Result<String, Bip85Error> {
+        let entropy = self.bip85_entropy(&[2, index])?;
+        let secret_key = SecretKey::from_slice(&entropy[..32]).map_err(BdkBip32Error::Secp256k1)?;
+        Ok(PrivateKey::new(secret_key, self.bip85_network_kind()?).to_wif())
+    }
+
+    /// Derive a master extended private key from this master key following BIP-85.
+    pub fn bip85_xprv(&self, index: u32) -> Result<Arc<Self>, Bip85Error> {
+        let entropy = self.bip85_entropy(&[32, index])?;
+        let xprv = Xpriv {
+            network: self.bip85_network_kind()?,
+            depth: 0,
+            parent_fingerprint: Fingerprint::default(),
+            child_number: BdkChildNumber::from_normal_idx(0)?,
+            chain_code: ChainCode::from(<[u8; 32]>::try_from(&entropy[..32]).unwrap()),
+            private_key: SecretKey::from_slice(&entropy[32..]).map_err(BdkBip32Error::Secp256k1)?,
+        };
+        let descriptor_secret_key = BdkDescriptorSecretKey::XPrv(DescriptorXKey {
+            origin: None,
+            xkey: xprv,
+            derivation_path: BdkDerivationPath::master(),
+            wildcard: Wildcard::None,
+        });
+        Ok(Arc::new(Self::from(descriptor_secret_key)))
+    }
+
+    /// Derive `length` bytes of entropy, between 16 and 64, from this master key following
+    /// BIP-85, returned as hex.
+    pub fn bip85_hex(&self, length: u8, index: u32) -> Result<String, Bip85Error> {
+        if !(16..=64).contains(&length) {
+            return Err(Bip85Error::InvalidEntropyLength {
+                length: length.into(),
+            });
+        }
+        let entropy = self.bip85_entropy(&[128169, length.into(), index])?;
+        Ok(entropy[..usize::from(length)].to_lower_hex_string())
+    }
+
+    /// Return the descriptor public key corresponding to this secret. It is kept when the key is
+    /// wiped.
     pub fn as_public(&self) -> Arc<DescriptorPublicKey> {
-        let secp = Secp256k1::new();
-        let descriptor_public_key = self.0.to_public(&secp).unwrap();
+        let descriptor_public_key = match self.0.read() {
+            Ok(descriptor_secret_key) => {
+                descriptor_secret_key.to_public(&Secp256k1::new()).unwrap()
+            }
+            Err(_) => self.wiped_key().public_key.clone(),
+        };
         Arc::new(DescriptorPublicKey(descriptor_public_key))
     }
 
-    /// Return the bytes of this descriptor secret key.
+    /// Return the bytes of this descriptor secret key, or no bytes if the key has been wiped.
     pub fn secret_bytes(&self) -> Vec<u8> {
-        let inner = &self.0;
-        let secret_bytes: Vec<u8> = match inner {
+        let Ok(inner) = self.0.read() else {
+            return Vec::new();
+        };
+        let secret_bytes: Vec<u8> = match &*inner {
             BdkDescriptorSecretKey::Single(single_key) => {
                 single_key.key.inner.secret_bytes().to_vec()
             }
@@ -282,11 +714,109 @@ impl DescriptorSecretKey {
 
         secret_bytes
     }
+
+    /// Erase the secret key from memory without waiting for the object to be dropped. Afterwards
+    /// the methods needing the key fail with a `Wiped` error, `secret_bytes` returns no bytes and
+    /// the key displays as `<wiped>`.
+    ///
+    /// The public key stays available through `as_public`, and the `Descriptor::new_bip*`
+    /// templates give the public descriptor of a wiped key.
+    ///
+    /// Keys, descriptors, signers and wallets already created from the key are not erased.
+    pub fn wipe(&self) {
+        let mut wiped_key = self.1.write().unwrap_or_else(PoisonError::into_inner);
+        if let Ok(descriptor_secret_key) = self.0.read() {
+            *wiped_key = Some(Arc::new(WipedKey::new(&descriptor_secret_key)));
+        }
+        self.0.wipe()
+    }
+}
+
+impl DescriptorSecretKey {
+    /// The key to build the template of `purpose` from, falling back to the account key kept
+    /// from a wiped key.
+    pub(crate) fn template_key(&self, purpose: u32, network_kind: BdkNetworkKind) -> TemplateKey {
+        if let Ok(descriptor_secret_key) = self.0.read() {
+            return match &*descriptor_secret_key {
+                BdkDescriptorSecretKey::XPrv(descriptor_x_key) => {
+                    TemplateKey::Secret(descriptor_x_key.xkey)
+                }
+                _ => unreachable!(),
+            };
+        }
+        let wiped_key = self.wiped_key();
+        match wiped_key
+            .accounts
+            .iter()
+            .find(|(account_purpose, account_network_kind, _)| {
+                *account_purpose == purpose && *account_network_kind == network_kind
+            }) {
+            Some((_, _, xpub)) => TemplateKey::Public(*xpub, wiped_key.fingerprint),
+            None => unreachable!(),
+        }
+    }
+
+    /// The public keys kept from the key when it was wiped.
+    fn wiped_key(&self) -> Arc<WipedKey> {
+        let wiped_key = self.1.read().unwrap_or_else(PoisonError::into_inner);
+        Arc::clone(
+            wiped_key
+                .as_ref()
+                .expect("the public keys are kept before wiping"),
+        )
+    }
+    /// The BIP-85 entropy of the key derived at `m/83696968'` followed by the hardened `path`.
+    fn bip85_entropy(&self, path: &[u32]) -> Result<[u8; 64], Bip85Error> {
+        let secret_key = self.0.read()?;
+        let descriptor_x_key = match &*secret_key {
+            BdkDescriptorSecretKey::XPrv(descriptor_x_key)
+                if descriptor_x_key.wildcard == Wildcard::None =>
+            {
+                descriptor_x_key
+            }
+            _ => return Err(Bip85Error::InvalidKeyType),
+        };
+        if descriptor_x_key.xkey.depth != 0 || !descriptor_x_key.derivation_path.is_master() {
+            return Err(Bip85Error::NotMasterKey);
+        }
+        let path = std::iter::once(83696968)
+            .chain(path.iter().copied())
+            .map(BdkChildNumber::from_hardened_idx)
+            .collect::<Result<Vec<_>, _>>()?;
+        let secp = Secp256k1::new();
+        let derived = descriptor_x_key.xkey.derive_priv(&secp, &path)?;
+
+        let mut engine = HmacEngine::<sha512::Hash>::new(b"bip-entropy-from-k");
+        engine.input(&derived.private_key.secret_bytes());
+        Ok(Hmac::from_engine(engine).to_byte_array())
+    }
+
+    fn bip85_network_kind(&self) -> Result<BdkNetworkKind, Bip85Error> {
+        match &*self.0.read()? {
+            BdkDescriptorSecretKey::XPrv(descriptor_x_key) => Ok(descriptor_x_key.xkey.network),
+            _ => Ok(BdkNetworkKind::Main),
+        }
+    }
+}
+
+impl From<BdkDescriptorSecretKey> for DescriptorSecretKey {
+    fn from(descriptor_secret_key: BdkDescriptorSecretKey) -> Self {
+        DescriptorSecretKey(descriptor_secret_key.into(), RwLock::new(None))
+    }
+}
+
+impl std::fmt::Debug for DescriptorSecretKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("DescriptorSecretKey").field(&self.0).finish()
+    }
 }
 
 impl Display for DescriptorSecretKey {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        self.0.fmt(f)
+        match self.0.read() {
+            Ok(descriptor_secret_key) => descriptor_secret_key.fmt(f),
+            Err(_) => write!(f, "<wiped>"),
+        }
     }
 }
 
@@ -400,6 +930,222 @@ impl DescriptorPublicKey {
     pub fn master_fingerprint(&self) -> String {
         self.0.master_fingerprint().to_string()
     }
+
+    /// Encode the extended key of this descriptor key with the SLIP-132 version of `script_type`,
+    /// for wallets expecting e.g. a `zpub`. Origin and derivation steps are not part of the
+    /// encoding.
+    pub fn to_slip132(&self, script_type: Slip132ScriptType) -> Result<String, DescriptorKeyError> {
+        match &self.0 {
+            BdkDescriptorPublicKey::XPub(descriptor_x_key) => {
+                let xpub = descriptor_x_key.xkey;
+                let mut data = xpub.encode();
+                data[..4].copy_from_slice(&slip132_version(script_type, xpub.network));
+                Ok(base58::encode_check(&data))
+            }
+            _ => Err(DescriptorKeyError::InvalidKeyType),
+        }
+    }
+
+    /// Export this account key, which must be an xpub with its origin and without further
+    /// derivation steps, as a multisig cosigner.
+    pub fn to_cosigner(&self) -> Result<Cosigner, DescriptorKeyError> {
+        match &self.0 {
+            BdkDescriptorPublicKey::XPub(DescriptorXKey {
+                origin: Some((fingerprint, path)),
+                xkey,
+                derivation_path,
+                wildcard: Wildcard::None,
+            }) if derivation_path.is_master() => Ok(Cosigner {
+                master_fingerprint: fingerprint.to_string(),
+                derivation_path: Arc::new(DerivationPath(path.clone())),
+                xpub: xkey.to_string(),
+            }),
+            _ => Err(DescriptorKeyError::InvalidKeyType),
+        }
+    }
+}
+
+/// An extended public key with a SLIP-132 version (`ypub`, `zpub`, `Zpub`, `vpub`...), as exported
+/// by Electrum and other wallets. The version tells which script type the key is used with.
+#[derive(Debug, uniffi::Object)]
+#[uniffi::export(Debug)]
+pub struct Slip132Key {
+    xpub: Xpub,
+    script_type: Slip132ScriptType,
+}
+
+#[uniffi::export]
+impl Slip132Key {
+    /// Parse a base58 extended public key with any of the SLIP-132 versions.
+    #[uniffi::constructor]
+    pub fn new(key: String) -> Result<Self, DescriptorKeyError> {
+        let parse_error = |error_message: String| DescriptorKeyError::Parse { error_message };
+        let mut data = base58::decode_check(&key).map_err(|e| parse_error(e.to_string()))?;
+        if data.len() != 78 {
+            return Err(parse_error(format!(
+                "invalid extended key length {}",
+                data.len()
+            )));
+        }
+        let (script_type, network_kind) = SLIP132_VERSIONS
+            .iter()
+            .find(|(version, _, _)| data[..4] == version[..])
+            .map(|(_, script_type, network_kind)| (*script_type, *network_kind))
+            .ok_or_else(|| parse_error(format!("unknown SLIP-132 version {}", &key[..4])))?;
+        data[..4].copy_from_slice(&slip132_version(Slip132ScriptType::P2pkh, network_kind));
+        let xpub = Xpub::decode(&data).map_err(|e| DescriptorKeyError::Bip32 {
+            error_message: e.to_string(),
+        })?;
+        Ok(Slip132Key { xpub, script_type })
+    }
+
+    /// The script type implied by the version of the key.
+    pub fn script_type(&self) -> Slip132ScriptType {
+        self.script_type
+    }
+
+    /// Whether the key is for mainnet or the test networks.
+    pub fn network_kind(&self) -> NetworkKind {
+        self.xpub.network
+    }
+
+    /// The key as a descriptor public key, using the standard `xpub`/`tpub` version.
+    pub fn descriptor_public_key(&self) -> Arc<DescriptorPublicKey> {
+        Arc::new(DescriptorPublicKey(BdkDescriptorPublicKey::XPub(
+            DescriptorXKey {
+                origin: None,
+                xkey: self.xpub,
+                derivation_path: BdkDerivationPath::master(),
+                wildcard: Wildcard::None,
+            },
+        )))
+    }
+
+    /// The single-sig descriptor of a keychain of the account, e.g. `wpkh(xpub/0/*)` for a
+    /// `zpub`. Multisig key versions cannot be turned into a descriptor on their own.
+    pub fn descriptor(&self, keychain: KeychainKind) -> Result<Arc<Descriptor>, DescriptorError> {
+        let branch = match keychain {
+            KeychainKind::External => 0,
+            KeychainKind::Internal => 1,
+        };
+        let key = format!("{}/{branch}/*", self.xpub);
+        let descriptor = match self.script_type {
+            Slip132ScriptType::P2pkh => format!("pkh({key})"),
+            Slip132ScriptType::P2shP2wpkh => format!("sh(wpkh({key}))"),
+            Slip132ScriptType::P2wpkh => format!("wpkh({key})"),
+            Slip132ScriptType::P2shP2wsh | Slip132ScriptType::P2wsh => {
+                return Err(DescriptorError::Key {
+                    error_message: "multisig keys need the keys of the other cosigners".to_string(),
+                })
+            }
+        };
+        Descriptor::new(descriptor, self.xpub.network).map(Arc::new)
+    }
+}
+
+const SLIP132_VERSIONS: [([u8; 4], Slip132ScriptType, NetworkKind); 10] = [
+    (
+        [0x04, 0x88, 0xb2, 0x1e],
+        Slip132ScriptType::P2pkh,
+        NetworkKind::Main,
+    ),
+    (
+        [0x04, 0x9d, 0x7c, 0xb2],
+        Slip132ScriptType::P2shP2wpkh,
+        NetworkKind::Main,
+    ),
+    (
+        [0x04, 0xb2, 0x47, 0x46],
+        Slip132ScriptType::P2wpkh,
+        NetworkKind::Main,
+    ),
+    (
+        [0x02, 0x95, 0xb4, 0x3f],
+        Slip132ScriptType::P2shP2wsh,
+        NetworkKind::Main,
+    ),
+    (
+        [0x02, 0xaa, 0x7e, 0xd3],
+        Slip132ScriptType::P2wsh,
+        NetworkKind::Main,
+    ),
+    (
+        [0x04, 0x35, 0x87, 0xcf],
+        Slip132ScriptType::P2pkh,
+        NetworkKind::Test,
+    ),
+    (
+        [0x04, 0x4a, 0x52, 0x62],
+        Slip132ScriptType::P2shP2wpkh,
+        NetworkKind::Test,
+    ),
+    (
+        [0x04, 0x5f, 0x1c, 0xf6],
+        Slip132ScriptType::P2wpkh,
+        NetworkKind::Test,
+    ),
+    (
+        [0x02, 0x42, 0x89, 0xef],
+        Slip132ScriptType::P2shP2wsh,
+        NetworkKind::Test,
+    ),
+    (
+        [0x02, 0x57, 0x54, 0x83],
+        Slip132ScriptType::P2wsh,
+        NetworkKind::Test,
+    ),
+];
+
+fn slip132_version(script_type: Slip132ScriptType, network_kind: NetworkKind) -> [u8; 4] {
+    SLIP132_VERSIONS
+        .iter()
+        .find(|(_, other_type, other_network)| {
+            *other_type == script_type && *other_network == network_kind
+        })
+        .map(|(version, _, _)| *version)
+        .expect("every script type has a version on both networks")
+}
+
+/// The BIP-48 derivation path `m/48'/coin'/account'/script_type'`.
+pub(crate) fn bip48_account_path(
+    script_type: Bip48ScriptType,
+    account: u32,
+    network_kind: NetworkKind,
+) -> Result<DerivationPath, DescriptorKeyError> {
+    let path = [
+        48,
+        bip48_coin_type(network_kind),
+        account,
+        script_type.index(),
+    ]
+    .iter()
+    .map(|index| BdkChildNumber::from_hardened_idx(*index))
+    .collect::<Result<Vec<_>, _>>()
+    .map_err(|e| DescriptorKeyError::Bip32 {
+        error_message: e.to_string(),
+    })?;
+    Ok(DerivationPath(BdkDerivationPath::from(path)))
+}
+
+/// Whether `path` is a BIP-48 account path for the script type and network.
+pub(crate) fn is_bip48_account_path(
+    path: &BdkDerivationPath,
+    script_type: Bip48ScriptType,
+    network_kind: NetworkKind,
+) -> bool {
+    match path.as_ref() {
+        [BdkChildNumber::Hardened { index: 48 }, BdkChildNumber::Hardened { index: coin }, BdkChildNumber::Hardened { .. }, BdkChildNumber::Hardened { index: script }] => {
+            *coin == bip48_coin_type(network_kind) && *script == script_type.index()
+        }
+        _ => false,
+    }
+}
+
+fn bip48_coin_type(network_kind: NetworkKind) -> u32 {
+    match network_kind {
+        NetworkKind::Main => 0,
+        NetworkKind::Test => 1,
+    }
 }
 
 impl Display for DescriptorPublicKey {
@@ -0,0 +1,299 @@
+use crate::bitcoin::NetworkKind;
+use crate::descriptor::Descriptor;
+use crate::error::KeystoreError;
+use crate::keys::{DescriptorSecretKey, Mnemonic};
+
+use bdk_wallet::bitcoin::hex::{DisplayHex, FromHex};
+use bdk_wallet::bitcoin::secp256k1::rand;
+use bdk_wallet::bitcoin::secp256k1::rand::Rng;
+use bdk_wallet::keys::bip39::Mnemonic as BdkMnemonic;
+use bdk_wallet::keys::DescriptorSecretKey as BdkDescriptorSecretKey;
+use bdk_wallet::serde::{Deserialize, Serialize};
+use bdk_wallet::serde_json;
+use chacha20_poly1305::{ChaCha20Poly1305, Key, Nonce};
+use scrypt::{scrypt, Params};
+use unicode_normalization::UnicodeNormalization;
+use zeroize::Zeroizing;
+
+use std::fmt::Display;
+use std::str::FromStr;
+use std::sync::Arc;
+
+/// The version of the keystore format, increased on incompatible changes.
+const KEYSTORE_VERSION: u32 = 1;
+
+// scrypt parameters of new keystores, using 32 MiB of memory.
+const SCRYPT_LOG_N: u8 = 15;
+const SCRYPT_R: u32 = 8;
+const SCRYPT_P: u32 = 1;
+// Keystores asking for more than 2 GiB of memory or 16 passes are rejected rather than
+// exhausting the device.
+const MAX_SCRYPT_LOG_N: u8 = 20;
+const MAX_SCRYPT_R: u32 = 16;
+const MAX_SCRYPT_P: u32 = 16;
+
+const SALT_LENGTH: usize = 16;
+const NONCE_LENGTH: usize = 12;
+const TAG_LENGTH: usize = 16;
+
+/// The kind of secret stored in a [`Keystore`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, uniffi::Enum)]
+#[serde(crate = "bdk_wallet::serde", rename_all = "snake_case")]
+pub enum KeystoreContent {
+    Mnemonic,
+    SecretKey,
+    Descriptor,
+}
+
+impl Display for KeystoreContent {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            KeystoreContent::Mnemonic => write!(f, "mnemonic"),
+            KeystoreContent::SecretKey => write!(f, "secret key"),
+            KeystoreContent::Descriptor => write!(f, "descriptor"),
+        }
+    }
+}
+
+/// A mnemonic, secret key or private descriptor encrypted with a password.
+///
+/// The encryption key is derived from the password with scrypt and the secret is encrypted with
+/// ChaCha20-Poly1305, so a wrong password is detected when decrypting. The keystore is saved and
+/// restored as a versioned JSON string with `Keystore::to_string` and `Keystore::from_string`.
+///
+/// The plaintext secret and the encryption key are erased from memory once used. Copies made by
+/// the scrypt and parsing libraries, and the password strings passed in, are not.
+#[derive(Debug, uniffi::Object)]
+#[uniffi::export(Display)]
+pub struct Keystore(KeystoreFile);
+
+#[uniffi::export]
+impl Keystore {
+    /// Encrypt a mnemonic with a password.
+    #[uniffi::constructor]
+    pub fn encrypt_mnemonic(mnemonic: &Mnemonic, password: String) -> Result<Self, KeystoreError> {
+        let plaintext = Zeroizing::new(mnemonic.0.read()?.to_string());
+        Ok(Keystore(encrypt(
+            KeystoreContent::Mnemonic,
+            plaintext.as_bytes(),
+            &password,
+        )))
+    }
+
+    /// Encrypt a descriptor secret key with a password.
+    #[uniffi::constructor]
+    pub fn encrypt_secret_key(
+        secret_key: &DescriptorSecretKey,
+        password: String,
+    ) -> Result<Self, KeystoreError> {
+        let plaintext = Zeroizing::new(secret_key.0.read()?.to_string());
+        Ok(Keystore(encrypt(
+            KeystoreContent::SecretKey,
+            plaintext.as_bytes(),
+            &password,
+        )))
+    }
+
+    /// Encrypt a descriptor, including its secret keys, with a password. Fails for descriptors
+    /// without secret keys, including descriptors whose keys have been wiped.
+    #[uniffi::constructor]
+    pub fn encrypt_descriptor(
+        descriptor: &Descriptor,
+        password: String,
+    ) -> Result<Self, KeystoreError> {
+        let key_map = descriptor.key_map.read()?;
+        if key_map.is_empty() {
+            return Err(KeystoreError::NoSecretKeys);
+        }
+        let plaintext = Zeroizing::new(
+            descriptor
+                .extended_descriptor
+                .to_string_with_secret(&key_map),
+        );
+        Ok(Keystore(encrypt(
+            KeystoreContent::Descriptor,
+            plaintext.as_bytes(),
+            &password,
+        )))
+    }
+
+    /// Parse a keystore saved with `Keystore::to_string`.
+    #[uniffi::constructor]
+    pub fn from_string(keystore: String) -> Result<Self, KeystoreError> {
+        #[derive(Deserialize)]
+        #[serde(crate = "bdk_wallet::serde")]
+        struct Versioned {
+            keystore_version: u32,
+        }
+
+        let invalid = |error: serde_json::Error| KeystoreError::InvalidKeystore {
+            error_message: error.to_string(),
+        };
+        let Versioned { keystore_version } = serde_json::from_str(&keystore).map_err(invalid)?;
+        if keystore_version != KEYSTORE_VERSION {
+            return Err(KeystoreError::UnsupportedVersion {
+                version: keystore_version,
+            });
+        }
+        serde_json::from_str(&keystore)
+            .map(Keystore)
+            .map_err(invalid)
+    }
+
+    /// The kind of secret stored in the keystore.
+    pub fn content(&self) -> KeystoreContent {
+        self.0.content
+    }
+
+    /// Decrypt the mnemonic stored in the keystore.
+    pub fn decrypt_mnemonic(&self, password: String) -> Result<Arc<Mnemonic>, KeystoreError> {
+        let mnemonic = self.decrypt(KeystoreContent::Mnemonic, &password)?;
+        BdkMnemonic::from_str(&mnemonic)
+            .map(|mnemonic| Arc::new(Mnemonic::from(mnemonic)))
+            .map_err(|error| KeystoreError::InvalidKeystore {
+                error_message: error.to_string(),
+            })
+    }
+
+    /// Decrypt the descriptor secret key stored in the keystore.
+    pub fn decrypt_secret_key(
+        &self,
+        password: String,
+    ) -> Result<Arc<DescriptorSecretKey>, KeystoreError> {
+        let secret_key = self.decrypt(KeystoreContent::SecretKey, &password)?;
+        BdkDescriptorSecretKey::from_str(&secret_key)
+            .map(|secret_key| Arc::new(DescriptorSecretKey::from(secret_key)))
+            .map_err(|error| KeystoreError::InvalidKeystore {
+                error_message: error.to_string(),
+            })
+    }
+
+    /// Decrypt the descriptor stored in the keystore, with its secret keys.
+    pub fn decrypt_descriptor(
+        &self,
+        password: String,
+        network_kind: NetworkKind,
+    ) -> Result<Arc<Descriptor>, KeystoreError> {
+        let descriptor = self.decrypt(KeystoreContent::Descriptor, &password)?;
+        Descriptor::parse(&descriptor, network_kind)
+            .map(Arc::new)
+            .map_err(|error| KeystoreError::InvalidKeystore {
+                error_message: error.to_string(),
+            })
+    }
+}
+
+impl Keystore {
+    fn decrypt(
+        &self,
+        content: KeystoreContent,
+        password: &str,
+    ) -> Result<Zeroizing<String>, KeystoreError> {
+        let file = &self.0;
+        if file.content != content {
+            return Err(KeystoreError::WrongContent {
+                content: file.content.to_string(),
+            });
+        }
+        let invalid = |error_message: &str| KeystoreError::InvalidKeystore {
+            error_message: error_message.to_string(),
+        };
+        if file.kdf.log_n > MAX_SCRYPT_LOG_N
+            || file.kdf.r > MAX_SCRYPT_R
+            || file.kdf.p > MAX_SCRYPT_P
+        {
+            return Err(invalid("scrypt parameters are too expensive"));
+        }
+        let salt = Vec::<u8>::from_hex(&file.kdf.salt).map_err(|_| invalid("invalid salt"))?;
+        let nonce =
+            <[u8; NONCE_LENGTH]>::from_hex(&file.nonce).map_err(|_| invalid("invalid nonce"))?;
+        let mut plaintext = Zeroizing::new(
+            Vec::<u8>::from_hex(&file.ciphertext).map_err(|_| invalid("invalid ciphertext"))?,
+        );
+        if plaintext.len() < TAG_LENGTH {
+            return Err(invalid("invalid ciphertext"));
+        }
+        let mut tag = [0u8; TAG_LENGTH];
+        let tag_start = plaintext.len() - TAG_LENGTH;
+        tag.copy_from_slice(&plaintext[tag_start..]);
+        plaintext.truncate(tag_start);
+        let params = Params::new(file.kdf.log_n, file.kdf.r, file.kdf.p, 32)
+            .map_err(|_| invalid("invalid scrypt parameters"))?;
+
+        let key = derive_key(password, &salt, &params);
+        ChaCha20Poly1305::new(Key::new(*key), Nonce::new(nonce))
+            .decrypt(&mut plaintext, tag, Some(&associated_data(content)))
+            .map_err(|_| KeystoreError::WrongPassword)?;
+        std::str::from_utf8(&plaintext)
+            .map(|plaintext| Zeroizing::new(plaintext.to_string()))
+            .map_err(|_| invalid("invalid plaintext"))
+    }
+}
+
+impl Display for Keystore {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", serde_json::to_string(&self.0).unwrap())
+    }
+}
+
+/// The saved form of a [`Keystore`], binary fields are hex encoded.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(crate = "bdk_wallet::serde")]
+struct KeystoreFile {
+    keystore_version: u32,
+    content: KeystoreContent,
+    kdf: ScryptParams,
+    nonce: String,
+    /// The encrypted secret followed by the authentication tag.
+    ciphertext: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(crate = "bdk_wallet::serde")]
+struct ScryptParams {
+    log_n: u8,
+    r: u32,
+    p: u32,
+    salt: String,
+}
+
+fn encrypt(content: KeystoreContent, secret: &[u8], password: &str) -> KeystoreFile {
+    let mut rng = rand::thread_rng();
+    let mut salt = [0u8; SALT_LENGTH];
+    rng.fill(&mut salt);
+    let mut nonce = [0u8; NONCE_LENGTH];
+    rng.fill(&mut nonce);
+    let params =
+        Params::new(SCRYPT_LOG_N, SCRYPT_R, SCRYPT_P, 32).expect("valid scrypt parameters");
+
+    let key = derive_key(password, &salt, &params);
+    let mut ciphertext = secret.to_vec();
+    let tag = ChaCha20Poly1305::new(Key::new(*key), Nonce::new(nonce))
+        .encrypt(&mut ciphertext, Some(&associated_data(content)));
+    ciphertext.extend(tag);
+    KeystoreFile {
+        keystore_version: KEYSTORE_VERSION,
+        content,
+        kdf: ScryptParams {
+            log_n: SCRYPT_LOG_N,
+            r: SCRYPT_R,
+            p: SCRYPT_P,
+            salt: salt.to_lower_hex_string(),
+        },
+        nonce: nonce.to_lower_hex_string(),
+        ciphertext: ciphertext.to_lower_hex_string(),
+    }
+}
+
+fn derive_key(password: &str, salt: &[u8], params: &Params) -> Zeroizing<[u8; 32]> {
+    // The same password typed on different platforms may be composed differently.
+    let password = Zeroizing::new(password.nfc().collect::<String>());
+    let mut key = Zeroizing::new([0u8; 32]);
+    scrypt(password.as_bytes(), salt, params, &mut *key).expect("valid scrypt output length");
+    key
+}
+
+/// Authenticates the format version and content type along with the secret.
+fn associated_data(content: KeystoreContent) -> Vec<u8> {
+    format!("bdk keystore {KEYSTORE_VERSION} {content}").into_bytes()
+}
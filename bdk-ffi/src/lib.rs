@@ -4,10 +4,17 @@ mod electrum;
 mod error;
 mod esplora;
 mod keys;
+mod keystore;
 mod kyoto;
 mod macros;
+mod outbox;
+mod privacy;
+mod secret;
 mod signer;
+mod slip39;
+mod spending_path;
 mod store;
+mod sweep;
 mod tx_builder;
 mod types;
 mod wallet;
@@ -0,0 +1,249 @@
+use crate::bitcoin::{Transaction, Txid};
+use crate::electrum::ElectrumClient;
+use crate::error::RebroadcastError;
+use crate::esplora::EsploraClient;
+use crate::kyoto::CbfClient;
+use crate::wallet::{Wallet, CANCELLED_AT};
+
+use bdk_wallet::bitcoin::Txid as BitcoinTxid;
+use bdk_wallet::bitcoin::{OutPoint as BdkOutPoint, Transaction as BdkTransaction};
+use bdk_wallet::Update as BdkUpdate;
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// Keeps track of transactions spending from the wallet until they confirm or are abandoned.
+///
+/// The outbox has no storage of its own: transactions are kept in the wallet's transaction graph,
+/// so they are saved whenever the wallet is persisted and are picked up again after it is loaded.
+/// A transaction leaves the outbox once it is confirmed, once a conflicting transaction replaces it
+/// in the best chain, or once it is abandoned.
+#[derive(uniffi::Object)]
+pub struct TxOutbox {
+    wallet: Arc<Wallet>,
+}
+
+#[uniffi::export]
+impl TxOutbox {
+    /// Create an outbox for the transactions of the given wallet.
+    #[uniffi::constructor]
+    pub fn new(wallet: Arc<Wallet>) -> Self {
+        TxOutbox { wallet }
+    }
+
+    /// Add a signed transaction to the outbox, inserting it into the wallet without a last seen
+    /// time (see [`Wallet::insert_tx`]). Call this before broadcasting: the transaction is reported
+    /// as [`OutboxTxStatus::Unbroadcast`] until one of the `rebroadcast_*` methods gets it
+    /// accepted, which marks it as seen.
+    ///
+    /// Returns `false`, leaving the wallet unchanged, if the transaction does not spend from the
+    /// wallet or was abandoned earlier. Adding a transaction that is already known leaves it as is.
+    ///
+    /// You must persist the wallet afterwards for the transaction to be kept across restarts.
+    pub fn add(&self, tx: &Transaction) -> bool {
+        let tx = BdkTransaction::from(tx);
+        let txid = tx.compute_txid();
+        let mut wallet = self.wallet.get_wallet_mut();
+        let (sent, _) = wallet.sent_and_received(&tx);
+        if sent.to_sat() == 0 {
+            return false;
+        }
+        let graph = wallet.tx_graph();
+        if graph.get_last_evicted(txid) == Some(CANCELLED_AT) {
+            return false;
+        }
+        if graph.get_tx(txid).is_none() {
+            let mut update = BdkUpdate::default();
+            update.tx_update.txs.push(Arc::new(tx));
+            wallet
+                .apply_update(update)
+                .expect("update without a chain update always connects");
+        }
+        true
+    }
+
+    /// List the transactions currently in the outbox.
+    pub fn list(&self) -> Vec<OutboxTx> {
+        self.outbox_txs()
+            .into_iter()
+            .map(|(tx, status, last_seen)| OutboxTx {
+                txid: Arc::new(Txid(tx.compute_txid())),
+                tx: Arc::new(tx.as_ref().into()),
+                status,
+                last_seen,
+            })
+            .collect()
+    }
+
+    /// Abandon a transaction so that it is no longer rebroadcast. Its inputs become spendable
+    /// again, unless it ends up confirming anyway. See [`Wallet::cancel_tx`].
+    ///
+    /// Returns `false` if the transaction is not in the outbox.
+    pub fn abandon(&self, txid: Arc<Txid>) -> bool {
+        match self
+            .outbox_txs()
+            .into_iter()
+            .find(|(tx, _, _)| tx.compute_txid() == txid.0)
+        {
+            Some((tx, _, _)) => {
+                self.wallet.cancel_tx(&tx.as_ref().into());
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Broadcast every transaction in the outbox through an Esplora server. Transactions that are
+    /// accepted are marked as seen at `now` (a unix timestamp).
+    pub fn rebroadcast_esplora(&self, client: &EsploraClient, now: u64) -> Vec<RebroadcastResult> {
+        self.rebroadcast_with(now, |tx| {
+            client
+                .broadcast(&tx.into())
+                .map_err(|error| RebroadcastError::Esplora { error })
+        })
+    }
+
+    /// Broadcast every transaction in the outbox through an Electrum server. Transactions that are
+    /// accepted are marked as seen at `now` (a unix timestamp).
+    pub fn rebroadcast_electrum(
+        &self,
+        client: &ElectrumClient,
+        now: u64,
+    ) -> Vec<RebroadcastResult> {
+        self.rebroadcast_with(now, |tx| {
+            client
+                .transaction_broadcast(&tx.into())
+                .map(|_| ())
+                .map_err(|error| RebroadcastError::Electrum { error })
+        })
+    }
+
+    /// Broadcast every transaction in the outbox to the peers of a compact block filter node.
+    /// Transactions that are accepted are marked as seen at `now` (a unix timestamp).
+    pub async fn rebroadcast_cbf(&self, client: &CbfClient, now: u64) -> Vec<RebroadcastResult> {
+        let txs = self.outbox_txs();
+        let mut results = Vec::with_capacity(txs.len());
+        for (tx, _, _) in txs {
+            let result = client
+                .broadcast(&tx.as_ref().into())
+                .await
+                .map(|_| ())
+                .map_err(|error| RebroadcastError::Cbf { error });
+            results.push(self.record_broadcast(&tx, result, now));
+        }
+        results
+    }
+}
+
+impl TxOutbox {
+    /// Transactions spending from the wallet that are not confirmed, replaced or abandoned, along
+    /// with their status and last seen timestamp.
+    fn outbox_txs(&self) -> Vec<(Arc<BdkTransaction>, OutboxTxStatus, Option<u64>)> {
+        let wallet = self.wallet.get_wallet();
+
+        let mut canonical_spends: HashMap<BdkOutPoint, BitcoinTxid> = HashMap::new();
+        let mut canonical_unconfirmed: HashMap<BitcoinTxid, bool> = HashMap::new();
+        for wallet_tx in wallet.transactions() {
+            let txid = wallet_tx.tx_node.txid;
+            for input in &wallet_tx.tx_node.tx.input {
+                canonical_spends.insert(input.previous_output, txid);
+            }
+            canonical_unconfirmed.insert(txid, !wallet_tx.chain_position.is_confirmed());
+        }
+
+        let graph = wallet.tx_graph();
+        let mut txs = Vec::new();
+        for node in graph.full_txs() {
+            let (sent, _) = wallet.sent_and_received(&node.tx);
+            if sent.to_sat() == 0 {
+                continue;
+            }
+            let status = match canonical_unconfirmed.get(&node.txid) {
+                Some(true) => OutboxTxStatus::Pending,
+                Some(false) => continue,
+                None => {
+                    if graph.get_last_evicted(node.txid) == Some(CANCELLED_AT) {
+                        continue;
+                    }
+                    let replaced = node.tx.input.iter().any(|input| {
+                        canonical_spends
+                            .get(&input.previous_output)
+                            .is_some_and(|spender| *spender != node.txid)
+                    });
+                    if replaced {
+                        continue;
+                    }
+                    match node.last_seen {
+                        Some(_) => OutboxTxStatus::Dropped,
+                        None => OutboxTxStatus::Unbroadcast,
+                    }
+                }
+            };
+            txs.push((node.tx.clone(), status, node.last_seen));
+        }
+        txs
+    }
+
+    fn rebroadcast_with<F>(&self, now: u64, broadcast: F) -> Vec<RebroadcastResult>
+    where
+        F: Fn(&BdkTransaction) -> Result<(), RebroadcastError>,
+    {
+        self.outbox_txs()
+            .into_iter()
+            .map(|(tx, _, _)| {
+                let result = broadcast(&tx);
+                self.record_broadcast(&tx, result, now)
+            })
+            .collect()
+    }
+
+    fn record_broadcast(
+        &self,
+        tx: &Arc<BdkTransaction>,
+        result: Result<(), RebroadcastError>,
+        now: u64,
+    ) -> RebroadcastResult {
+        if result.is_ok() {
+            self.wallet
+                .get_wallet_mut()
+                .apply_unconfirmed_txs([(tx.clone(), now)]);
+        }
+        RebroadcastResult {
+            txid: Arc::new(Txid(tx.compute_txid())),
+            error: result.err(),
+        }
+    }
+}
+
+/// A transaction waiting in the [`TxOutbox`].
+#[derive(uniffi::Record)]
+pub struct OutboxTx {
+    /// The transaction id.
+    pub txid: Arc<Txid>,
+    /// The signed transaction.
+    pub tx: Arc<Transaction>,
+    /// Whether the transaction is expected to be in the mempool.
+    pub status: OutboxTxStatus,
+    /// The last time the transaction was seen in the mempool or successfully broadcast, if ever.
+    pub last_seen: Option<u64>,
+}
+
+/// The state of a transaction in the [`TxOutbox`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, uniffi::Enum)]
+pub enum OutboxTxStatus {
+    /// The transaction was added but has not been accepted by a broadcast or seen in the mempool.
+    Unbroadcast,
+    /// The transaction is unconfirmed and believed to be in the mempool.
+    Pending,
+    /// The transaction was evicted from the mempool and needs to be broadcast again.
+    Dropped,
+}
+
+/// The outcome of broadcasting one transaction from the [`TxOutbox`].
+#[derive(uniffi::Record)]
+pub struct RebroadcastResult {
+    /// The transaction id.
+    pub txid: Arc<Txid>,
+    /// The reason the broadcast failed, or `None` if it was accepted.
+    pub error: Option<RebroadcastError>,
+}
@@ -0,0 +1,253 @@
+use crate::bitcoin::{Address, OutPoint, Txid};
+use crate::types::KeychainKind;
+
+use bdk_wallet::bitcoin::{
+    Address as BdkAddress, Amount as BdkAmount, Script as BdkScript, ScriptBuf as BdkScriptBuf,
+    Txid as BdkTxid,
+};
+use bdk_wallet::Wallet as BdkWallet;
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::sync::Arc;
+
+/// Payments that are a multiple of this many satoshis are considered round numbers.
+const ROUND_AMOUNT_SATS: u64 = 10_000;
+
+/// A report of on-chain privacy leaks of a wallet, see `Wallet::privacy_report`.
+#[derive(uniffi::Record)]
+pub struct PrivacyReport {
+    /// Addresses of the wallet that received funds in more than one transaction.
+    pub reused_addresses: Vec<AddressReuse>,
+    /// Groups of addresses of the wallet that were spent together in the same transaction, and
+    /// can therefore be assumed to have the same owner (common-input-ownership heuristic), along
+    /// with the unspent outputs this links together.
+    pub linked_addresses: Vec<LinkedAddresses>,
+    /// Change outputs of wallet transactions that can be told apart from the payment because the
+    /// payment is a round amount and the change is not.
+    pub round_number_change: Vec<ExposedChange>,
+    /// Change outputs of wallet transactions that can be told apart from the payment because their
+    /// script type differs from the payment's.
+    pub script_type_change: Vec<ExposedChange>,
+    /// The unspent outputs of the wallet grouped by script type, if they are of more than one
+    /// type. Spending outputs of different types together reveals that they have the same owner,
+    /// and the change output then betrays which of them the wallet uses.
+    pub mixed_script_types: Vec<ScriptTypeUtxos>,
+}
+
+/// An address that received funds in more than one transaction.
+#[derive(uniffi::Record)]
+pub struct AddressReuse {
+    /// The reused address.
+    pub address: Arc<Address>,
+    /// The keychain the address belongs to.
+    pub keychain: KeychainKind,
+    /// The derivation index of the address.
+    pub index: u32,
+    /// The number of transactions sending to the address.
+    pub tx_count: u32,
+}
+
+/// Addresses linked together by being spent in the same transactions.
+#[derive(uniffi::Record)]
+pub struct LinkedAddresses {
+    /// The linked addresses.
+    pub addresses: Vec<Arc<Address>>,
+    /// The transactions spending from more than one of the addresses.
+    pub txids: Vec<Arc<Txid>>,
+    /// The unspent outputs of the wallet at the linked addresses, which an observer can already
+    /// attribute to the same owner.
+    pub utxos: Vec<OutPoint>,
+}
+
+/// Unspent outputs of the wallet sharing a script type.
+#[derive(uniffi::Record)]
+pub struct ScriptTypeUtxos {
+    /// The script type of the outputs.
+    pub script_type: ScriptType,
+    /// The unspent outputs.
+    pub utxos: Vec<OutPoint>,
+}
+
+/// A change output that an observer can identify.
+#[derive(uniffi::Record)]
+pub struct ExposedChange {
+    /// The transaction containing the change output.
+    pub txid: Arc<Txid>,
+    /// The index of the change output in the transaction.
+    pub vout: u32,
+}
+
+pub(crate) fn privacy_report(wallet: &BdkWallet) -> PrivacyReport {
+    let network = wallet.network();
+    let address = |script: &BdkScript| {
+        BdkAddress::from_script(script, network)
+            .ok()
+            .map(|address| Arc::new(Address(address)))
+    };
+
+    // Address reuse, from every output of the wallet, spent or not.
+    let mut receiving_txs: BTreeMap<(KeychainKind, u32), (BdkScriptBuf, BTreeSet<_>)> =
+        BTreeMap::new();
+    for output in wallet.list_output() {
+        receiving_txs
+            .entry((output.keychain, output.derivation_index))
+            .or_insert_with(|| (output.txout.script_pubkey.clone(), BTreeSet::new()))
+            .1
+            .insert(output.outpoint.txid);
+    }
+    let reused_addresses = receiving_txs
+        .into_iter()
+        .filter(|(_, (_, txids))| txids.len() > 1)
+        .filter_map(|((keychain, index), (script, txids))| {
+            Some(AddressReuse {
+                address: address(&script)?,
+                keychain,
+                index,
+                tx_count: txids.len() as u32,
+            })
+        })
+        .collect();
+
+    let index = wallet.spk_index();
+    let mut clusters = Clusters::default();
+    let mut round_number_change = Vec::new();
+    let mut script_type_change = Vec::new();
+    for wallet_tx in wallet.transactions() {
+        let tx = &wallet_tx.tx_node.tx;
+        let txid = wallet_tx.tx_node.txid;
+
+        let spent_scripts = tx
+            .input
+            .iter()
+            .filter_map(|input| index.txout(input.previous_output))
+            .map(|(_, txout)| txout.script_pubkey.clone())
+            .collect::<BTreeSet<_>>();
+        if spent_scripts.is_empty() {
+            continue;
+        }
+        if spent_scripts.len() > 1 {
+            clusters.link(spent_scripts, txid);
+        }
+
+        // Only simple payments with a single recipient and a single change output are analyzed.
+        let (change, payments): (Vec<_>, Vec<_>) = tx
+            .output
+            .iter()
+            .enumerate()
+            .partition(|(_, txout)| index.index_of_spk(txout.script_pubkey.clone()).is_some());
+        let ((change_vout, change), (_, payment)) = match (change.as_slice(), payments.as_slice()) {
+            ([change], [payment]) => (*change, *payment),
+            _ => continue,
+        };
+        let exposed = || ExposedChange {
+            txid: Arc::new(Txid(txid)),
+            vout: change_vout as u32,
+        };
+        if is_round(payment.value) && !is_round(change.value) {
+            round_number_change.push(exposed());
+        }
+        if script_type(&change.script_pubkey) != script_type(&payment.script_pubkey) {
+            script_type_change.push(exposed());
+        }
+    }
+
+    let utxos = wallet.list_unspent().collect::<Vec<_>>();
+    let linked_addresses = clusters
+        .0
+        .into_iter()
+        .map(|(scripts, txids)| LinkedAddresses {
+            addresses: scripts
+                .iter()
+                .filter_map(|script| address(script))
+                .collect(),
+            txids: txids.into_iter().map(|txid| Arc::new(Txid(txid))).collect(),
+            utxos: utxos
+                .iter()
+                .filter(|utxo| scripts.contains(&utxo.txout.script_pubkey))
+                .map(|utxo| utxo.outpoint.into())
+                .collect(),
+        })
+        .collect();
+
+    let mut utxos_by_type: BTreeMap<ScriptType, Vec<OutPoint>> = BTreeMap::new();
+    for utxo in &utxos {
+        utxos_by_type
+            .entry(script_type(&utxo.txout.script_pubkey))
+            .or_default()
+            .push(utxo.outpoint.into());
+    }
+    let mixed_script_types = if utxos_by_type.len() > 1 {
+        utxos_by_type
+            .into_iter()
+            .map(|(script_type, utxos)| ScriptTypeUtxos { script_type, utxos })
+            .collect()
+    } else {
+        Vec::new()
+    };
+
+    PrivacyReport {
+        reused_addresses,
+        linked_addresses,
+        round_number_change,
+        script_type_change,
+        mixed_script_types,
+    }
+}
+
+fn is_round(amount: BdkAmount) -> bool {
+    amount.to_sat().is_multiple_of(ROUND_AMOUNT_SATS)
+}
+
+/// The type of an output script.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, uniffi::Enum)]
+pub enum ScriptType {
+    /// Pay to public key hash (legacy).
+    P2pkh,
+    /// Pay to script hash, including wrapped segwit.
+    P2sh,
+    /// Pay to witness public key hash (native segwit v0).
+    P2wpkh,
+    /// Pay to witness script hash (native segwit v0).
+    P2wsh,
+    /// Pay to taproot (segwit v1).
+    P2tr,
+    /// Any other script.
+    Other,
+}
+
+fn script_type(script: &BdkScript) -> ScriptType {
+    if script.is_p2pkh() {
+        ScriptType::P2pkh
+    } else if script.is_p2sh() {
+        ScriptType::P2sh
+    } else if script.is_p2wpkh() {
+        ScriptType::P2wpkh
+    } else if script.is_p2wsh() {
+        ScriptType::P2wsh
+    } else if script.is_p2tr() {
+        ScriptType::P2tr
+    } else {
+        ScriptType::Other
+    }
+}
+
+/// Groups of scripts merged whenever they are spent together, with the transactions spending them.
+#[derive(Default)]
+struct Clusters(Vec<(BTreeSet<BdkScriptBuf>, BTreeSet<BdkTxid>)>);
+
+impl Clusters {
+    fn link(&mut self, scripts: BTreeSet<BdkScriptBuf>, txid: BdkTxid) {
+        let mut merged = (scripts, BTreeSet::from([txid]));
+        let mut disjoint = Vec::with_capacity(self.0.len());
+        for cluster in self.0.drain(..) {
+            if cluster.0.is_disjoint(&merged.0) {
+                disjoint.push(cluster);
+            } else {
+                merged.0.extend(cluster.0);
+                merged.1.extend(cluster.1);
+            }
+        }
+        disjoint.push(merged);
+        self.0 = disjoint;
+    }
+}
@@ -0,0 +1,135 @@
+use bdk_wallet::bitcoin::bip32::{ChainCode, Xpriv};
+use bdk_wallet::bitcoin::secp256k1::ffi::non_secure_erase_impl;
+use bdk_wallet::bitcoin::PrivateKey as BdkPrivateKey;
+use bdk_wallet::keys::bip39::Mnemonic as BdkMnemonic;
+use bdk_wallet::keys::{DescriptorSecretKey as BdkDescriptorSecretKey, KeyMap};
+use zeroize::Zeroize;
+
+use std::fmt;
+use std::ops::Deref;
+use std::sync::{PoisonError, RwLock, RwLockReadGuard};
+
+/// Secret material that can be overwritten in place.
+pub(crate) trait Erase {
+    fn erase(&mut self);
+}
+
+/// A secret held by an FFI object, erased from memory when the object is dropped or wiped.
+///
+/// A wiped secret stays erased in place and can no longer be read, so the methods of its holder
+/// return an error or a value revealing nothing about the secret.
+pub(crate) struct Secret<T: Erase>(RwLock<State<T>>);
+
+struct State<T> {
+    secret: T,
+    wiped: bool,
+}
+
+/// The error of reading a wiped [`Secret`].
+#[derive(Debug)]
+pub(crate) struct Wiped;
+
+impl<T: Erase> Secret<T> {
+    pub(crate) fn read(&self) -> Result<SecretGuard<'_, T>, Wiped> {
+        let state = self.0.read().unwrap_or_else(PoisonError::into_inner);
+        if state.wiped {
+            return Err(Wiped);
+        }
+        Ok(SecretGuard(state))
+    }
+
+    pub(crate) fn wipe(&self) {
+        let mut state = self.0.write().unwrap_or_else(PoisonError::into_inner);
+        state.secret.erase();
+        state.wiped = true;
+    }
+}
+
+impl<T: Erase + Default> Secret<T> {
+    /// Erase the secret and replace it with an empty one, for holders that remain usable without it.
+    pub(crate) fn clear(&self) {
+        let mut state = self.0.write().unwrap_or_else(PoisonError::into_inner);
+        state.secret.erase();
+        state.secret = T::default();
+    }
+}
+
+impl<T: Erase> From<T> for Secret<T> {
+    fn from(secret: T) -> Self {
+        Secret(RwLock::new(State {
+            secret,
+            wiped: false,
+        }))
+    }
+}
+
+impl<T: Erase + Clone> Clone for Secret<T> {
+    fn clone(&self) -> Self {
+        let state = self.0.read().unwrap_or_else(PoisonError::into_inner);
+        Secret(RwLock::new(State {
+            secret: state.secret.clone(),
+            wiped: state.wiped,
+        }))
+    }
+}
+
+impl<T: Erase> Drop for Secret<T> {
+    fn drop(&mut self) {
+        self.0
+            .get_mut()
+            .unwrap_or_else(PoisonError::into_inner)
+            .secret
+            .erase();
+    }
+}
+
+impl<T: Erase> fmt::Debug for Secret<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "<redacted>")
+    }
+}
+
+pub(crate) struct SecretGuard<'a, T>(RwLockReadGuard<'a, State<T>>);
+
+impl<T> Deref for SecretGuard<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.0.secret
+    }
+}
+
+impl Erase for BdkMnemonic {
+    fn erase(&mut self) {
+        self.zeroize();
+    }
+}
+
+impl Erase for BdkPrivateKey {
+    fn erase(&mut self) {
+        self.inner.non_secure_erase();
+    }
+}
+
+impl Erase for Xpriv {
+    fn erase(&mut self) {
+        self.private_key.non_secure_erase();
+        non_secure_erase_impl(&mut self.chain_code, ChainCode::from([0u8; 32]));
+    }
+}
+
+impl Erase for BdkDescriptorSecretKey {
+    fn erase(&mut self) {
+        match self {
+            BdkDescriptorSecretKey::Single(single) => single.key.erase(),
+            BdkDescriptorSecretKey::XPrv(descriptor_x_key) => descriptor_x_key.xkey.erase(),
+            BdkDescriptorSecretKey::MultiXPrv(descriptor_x_key) => descriptor_x_key.xkey.erase(),
+        }
+    }
+}
+
+impl Erase for KeyMap {
+    fn erase(&mut self) {
+        self.values_mut().for_each(Erase::erase);
+    }
+}
@@ -3,10 +3,14 @@ use crate::descriptor::Descriptor;
 use bdk_wallet::bitcoin::key::Secp256k1;
 use bdk_wallet::signer::SignersContainer as BdkSignersContainer;
 
+use std::fmt;
 use std::sync::Arc;
 
 /// Container for multiple signers.
-#[derive(Debug, uniffi::Object)]
+///
+/// The signers hold their own copies of the secret keys, which are not erased from memory when
+/// the container is dropped or when the descriptor it was built from is wiped.
+#[derive(uniffi::Object)]
 pub struct SignersContainer {
     pub(crate) inner: BdkSignersContainer,
 }
@@ -32,7 +36,11 @@ impl SignersContainer {
     ) -> Self {
         let secp = Secp256k1::new();
         let inner = BdkSignersContainer::build(
-            signer_descriptor.key_map.clone(),
+            signer_descriptor
+                .key_map
+                .read()
+                .map(|key_map| key_map.clone())
+                .unwrap_or_default(),
             &context_descriptor.extended_descriptor,
             &secp,
         );
@@ -50,3 +58,12 @@ impl SignersContainer {
         self.inner.signers().is_empty()
     }
 }
+
+// The signers of the wrapped container print their secret keys, so only their ids are shown.
+impl fmt::Debug for SignersContainer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SignersContainer")
+            .field("ids", &self.inner.ids())
+            .finish()
+    }
+}
@@ -0,0 +1,649 @@
+use crate::error::Slip39Error;
+
+use bdk_wallet::bitcoin::hashes::{hmac, sha256, Hash, HashEngine};
+use bdk_wallet::bitcoin::secp256k1::rand;
+use bdk_wallet::bitcoin::secp256k1::rand::Rng;
+use pbkdf2::pbkdf2_hmac;
+use sha2::Sha256;
+
+use std::collections::BTreeMap;
+
+/// A group of SLIP-39 shares, of which `member_threshold` out of `member_count` are needed to
+/// recover the group.
+#[derive(Debug, Clone, uniffi::Record)]
+pub struct Slip39Group {
+    /// The number of shares needed to recover the group.
+    pub member_threshold: u8,
+    /// The number of shares of the group, at most 16.
+    pub member_count: u8,
+}
+
+const MAX_SHARE_COUNT: u8 = 16;
+const ID_BITS: u32 = 15;
+const RADIX_BITS: usize = 10;
+// Two words of identifier and iteration exponent, two of share parameters and three of checksum.
+const METADATA_WORDS: usize = 7;
+const MIN_MNEMONIC_WORDS: usize = 20;
+const MIN_SECRET_BYTES: usize = 16;
+const DIGEST_BYTES: usize = 4;
+const DIGEST_INDEX: u8 = 254;
+const SECRET_INDEX: u8 = 255;
+const BASE_ITERATION_COUNT: u32 = 10000;
+const ROUND_COUNT: u8 = 4;
+const ITERATION_EXPONENT: u8 = 1;
+// New shares are not extendable so that they can be recovered by wallets predating the flag.
+const EXTENDABLE: bool = false;
+
+/// A single share, as encoded in a mnemonic.
+struct Share {
+    identifier: u16,
+    extendable: bool,
+    iteration_exponent: u8,
+    group_index: u8,
+    group_threshold: u8,
+    group_count: u8,
+    member_index: u8,
+    member_threshold: u8,
+    value: Vec<u8>,
+}
+
+impl Share {
+    fn parse(mnemonic: &str, index: usize) -> Result<Share, Slip39Error> {
+        let invalid = |error_message: String| Slip39Error::InvalidShare {
+            index: index as u64,
+            error_message,
+        };
+        let words = mnemonic
+            .split_whitespace()
+            .map(|word| {
+                let word = word.to_lowercase();
+                WORDLIST
+                    .binary_search(&word.as_str())
+                    .map(|i| i as u16)
+                    .map_err(|_| invalid(format!("unknown word '{word}'")))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        if words.len() < MIN_MNEMONIC_WORDS {
+            return Err(invalid(format!("{} words are too few", words.len())));
+        }
+        let padding = (RADIX_BITS * (words.len() - METADATA_WORDS)) % 16;
+        if padding > 8 {
+            return Err(invalid(format!(
+                "{} words is not a valid length",
+                words.len()
+            )));
+        }
+
+        let id_exp = (u32::from(words[0]) << RADIX_BITS) | u32::from(words[1]);
+        let extendable = (id_exp >> 4) & 1 == 1;
+        if rs1024_polymod(&words, extendable) != 1 {
+            return Err(Slip39Error::InvalidChecksum {
+                index: index as u64,
+            });
+        }
+        let params = (u32::from(words[2]) << RADIX_BITS) | u32::from(words[3]);
+        let param = |position: u32| ((params >> (4 * position)) & 0xf) as u8;
+        let share = Share {
+            identifier: (id_exp >> 5) as u16,
+            extendable,
+            iteration_exponent: (id_exp & 0xf) as u8,
+            group_index: param(4),
+            group_threshold: param(3) + 1,
+            group_count: param(2) + 1,
+            member_index: param(1),
+            member_threshold: param(0) + 1,
+            value: words_to_bytes(&words[4..words.len() - 3], padding)
+                .ok_or_else(|| invalid("invalid padding".to_string()))?,
+        };
+        if share.group_threshold > share.group_count {
+            return Err(invalid(
+                "group threshold is greater than the group count".to_string(),
+            ));
+        }
+        Ok(share)
+    }
+
+    fn to_mnemonic(&self) -> String {
+        let id_exp = (u32::from(self.identifier) << 5)
+            | (u32::from(self.extendable) << 4)
+            | u32::from(self.iteration_exponent);
+        let params = [
+            self.group_index,
+            self.group_threshold - 1,
+            self.group_count - 1,
+            self.member_index,
+            self.member_threshold - 1,
+        ]
+        .iter()
+        .fold(0u32, |params, param| (params << 4) | u32::from(*param));
+        let mut words = vec![
+            (id_exp >> RADIX_BITS) as u16,
+            (id_exp & 0x3ff) as u16,
+            (params >> RADIX_BITS) as u16,
+            (params & 0x3ff) as u16,
+        ];
+        words.extend(bytes_to_words(&self.value));
+        words.extend(rs1024_checksum(&words, self.extendable));
+        words
+            .iter()
+            .map(|word| WORDLIST[usize::from(*word)])
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    /// The parameters that all the shares of a backup have in common.
+    fn common_parameters(&self) -> (u16, bool, u8, u8, u8, usize) {
+        (
+            self.identifier,
+            self.extendable,
+            self.iteration_exponent,
+            self.group_threshold,
+            self.group_count,
+            self.value.len(),
+        )
+    }
+}
+
+/// Split a master secret into mnemonic shares following
+/// [SLIP-39](https://github.com/satoshilabs/slips/blob/master/slip-0039.md), one list of mnemonics
+/// per group.
+pub(crate) fn split(
+    master_secret: &[u8],
+    group_threshold: u8,
+    groups: &[Slip39Group],
+    passphrase: &str,
+) -> Result<Vec<Vec<String>>, Slip39Error> {
+    let invalid = |error_message: &str| Slip39Error::InvalidGroups {
+        error_message: error_message.to_string(),
+    };
+    check_passphrase(passphrase)?;
+    if master_secret.len() < MIN_SECRET_BYTES || !master_secret.len().is_multiple_of(2) {
+        return Err(Slip39Error::UnsupportedSecretLength {
+            length: master_secret.len() as u64,
+        });
+    }
+    if groups.is_empty() || groups.len() > usize::from(MAX_SHARE_COUNT) {
+        return Err(invalid("there must be between 1 and 16 groups"));
+    }
+    if group_threshold == 0 || usize::from(group_threshold) > groups.len() {
+        return Err(invalid(
+            "the group threshold must be between 1 and the number of groups",
+        ));
+    }
+    for group in groups {
+        if group.member_count == 0 || group.member_count > MAX_SHARE_COUNT {
+            return Err(invalid("a group must have between 1 and 16 shares"));
+        }
+        if group.member_threshold == 0 || group.member_threshold > group.member_count {
+            return Err(invalid(
+                "the member threshold must be between 1 and the number of shares of the group",
+            ));
+        }
+        // Such shares would all be copies of the group secret, use a single share instead.
+        if group.member_threshold == 1 && group.member_count > 1 {
+            return Err(invalid(
+                "a group with a member threshold of 1 must have a single share",
+            ));
+        }
+    }
+
+    let identifier = rand::thread_rng().gen::<u16>() >> (16 - ID_BITS);
+    let encrypted = feistel(
+        master_secret,
+        passphrase,
+        ITERATION_EXPONENT,
+        identifier,
+        EXTENDABLE,
+        true,
+    );
+    let group_secrets = split_secret(group_threshold, groups.len() as u8, &encrypted);
+    Ok(groups
+        .iter()
+        .zip(group_secrets)
+        .map(|(group, (group_index, group_secret))| {
+            split_secret(group.member_threshold, group.member_count, &group_secret)
+                .into_iter()
+                .map(|(member_index, value)| {
+                    Share {
+                        identifier,
+                        extendable: EXTENDABLE,
+                        iteration_exponent: ITERATION_EXPONENT,
+                        group_index,
+                        group_threshold,
+                        group_count: groups.len() as u8,
+                        member_index,
+                        member_threshold: group.member_threshold,
+                        value,
+                    }
+                    .to_mnemonic()
+                })
+                .collect()
+        })
+        .collect())
+}
+
+/// Recover the master secret from enough SLIP-39 mnemonic shares. Shares beyond the thresholds
+/// are ignored.
+pub(crate) fn combine(mnemonics: &[String], passphrase: &str) -> Result<Vec<u8>, Slip39Error> {
+    check_passphrase(passphrase)?;
+    let shares = mnemonics
+        .iter()
+        .enumerate()
+        .map(|(index, mnemonic)| Share::parse(mnemonic, index))
+        .collect::<Result<Vec<_>, _>>()?;
+    let first = shares.first().ok_or(Slip39Error::NoShares)?;
+    if shares
+        .iter()
+        .any(|share| share.common_parameters() != first.common_parameters())
+    {
+        return Err(Slip39Error::MismatchedShares);
+    }
+
+    let mut groups = BTreeMap::<u8, Vec<&Share>>::new();
+    for share in &shares {
+        let members = groups.entry(share.group_index).or_default();
+        if members
+            .iter()
+            .any(|member| member.member_threshold != share.member_threshold)
+        {
+            return Err(Slip39Error::MismatchedShares);
+        }
+        match members
+            .iter()
+            .find(|member| member.member_index == share.member_index)
+        {
+            Some(member) if member.value != share.value => {
+                return Err(Slip39Error::MismatchedShares)
+            }
+            Some(_) => {}
+            None => members.push(share),
+        }
+    }
+
+    if groups.len() < usize::from(first.group_threshold) {
+        return Err(Slip39Error::InsufficientGroups {
+            required: first.group_threshold,
+            provided: groups.len() as u8,
+        });
+    }
+    let complete = groups
+        .iter()
+        .filter(|(_, members)| members.len() >= usize::from(members[0].member_threshold))
+        .take(usize::from(first.group_threshold))
+        .collect::<Vec<_>>();
+    if complete.len() < usize::from(first.group_threshold) {
+        let (group_index, members) = groups
+            .iter()
+            .find(|(_, members)| members.len() < usize::from(members[0].member_threshold))
+            .expect("an incomplete group");
+        return Err(Slip39Error::InsufficientShares {
+            group_index: *group_index,
+            required: members[0].member_threshold,
+            provided: members.len() as u8,
+        });
+    }
+
+    let group_secrets = complete
+        .into_iter()
+        .map(|(group_index, members)| {
+            let threshold = members[0].member_threshold;
+            let member_shares = members
+                .iter()
+                .take(usize::from(threshold))
+                .map(|member| (member.member_index, member.value.clone()))
+                .collect::<Vec<_>>();
+            recover_secret(threshold, &member_shares).map(|secret| (*group_index, secret))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    let encrypted = recover_secret(first.group_threshold, &group_secrets)?;
+    Ok(feistel(
+        &encrypted,
+        passphrase,
+        first.iteration_exponent,
+        first.identifier,
+        first.extendable,
+        false,
+    ))
+}
+
+fn check_passphrase(passphrase: &str) -> Result<(), Slip39Error> {
+    if passphrase.bytes().all(|byte| (32..=126).contains(&byte)) {
+        Ok(())
+    } else {
+        Err(Slip39Error::InvalidPassphrase)
+    }
+}
+
+/// The four round Feistel network that encrypts, or decrypts, the master secret with the
+/// passphrase.
+fn feistel(
+    secret: &[u8],
+    passphrase: &str,
+    iteration_exponent: u8,
+    identifier: u16,
+    extendable: bool,
+    encrypt: bool,
+) -> Vec<u8> {
+    let half = secret.len() / 2;
+    let (mut left, mut right) = (secret[..half].to_vec(), secret[half..].to_vec());
+    let mut salt = Vec::new();
+    if !extendable {
+        salt.extend(b"shamir");
+        salt.extend(identifier.to_be_bytes());
+    }
+    let iterations = (BASE_ITERATION_COUNT << iteration_exponent) / u32::from(ROUND_COUNT);
+    let rounds = if encrypt {
+        (0..ROUND_COUNT).collect::<Vec<_>>()
+    } else {
+        (0..ROUND_COUNT).rev().collect()
+    };
+    for round in rounds {
+        let password = [&[round], passphrase.as_bytes()].concat();
+        let mut key = vec![0u8; half];
+        pbkdf2_hmac::<Sha256>(
+            &password,
+            &[salt.as_slice(), &right].concat(),
+            iterations,
+            &mut key,
+        );
+        let mixed = left.iter().zip(key).map(|(l, k)| l ^ k).collect();
+        left = right;
+        right = mixed;
+    }
+    [right, left].concat()
+}
+
+/// Split a secret into `count` Shamir shares of which `threshold` recover it, with a digest to
+/// detect invalid shares at recovery.
+fn split_secret(threshold: u8, count: u8, secret: &[u8]) -> Vec<(u8, Vec<u8>)> {
+    if threshold == 1 {
+        return (0..count).map(|index| (index, secret.to_vec())).collect();
+    }
+    let mut rng = rand::thread_rng();
+    let mut random_bytes = |length: usize| {
+        let mut bytes = vec![0u8; length];
+        rng.fill(bytes.as_mut_slice());
+        bytes
+    };
+    let mut shares = (0..threshold - 2)
+        .map(|index| (index, random_bytes(secret.len())))
+        .collect::<Vec<_>>();
+    let random_part = random_bytes(secret.len() - DIGEST_BYTES);
+    let digest_share = [&digest(&random_part, secret)[..], &random_part].concat();
+    let mut base_shares = shares.clone();
+    base_shares.push((DIGEST_INDEX, digest_share));
+    base_shares.push((SECRET_INDEX, secret.to_vec()));
+    shares.extend((threshold - 2..count).map(|index| (index, interpolate(&base_shares, index))));
+    shares
+}
+
+fn recover_secret(threshold: u8, shares: &[(u8, Vec<u8>)]) -> Result<Vec<u8>, Slip39Error> {
+    if threshold == 1 {
+        return Ok(shares[0].1.clone());
+    }
+    let secret = interpolate(shares, SECRET_INDEX);
+    let digest_share = interpolate(shares, DIGEST_INDEX);
+    if digest_share[..DIGEST_BYTES] != digest(&digest_share[DIGEST_BYTES..], &secret) {
+        return Err(Slip39Error::InvalidDigest);
+    }
+    Ok(secret)
+}
+
+fn digest(random_part: &[u8], secret: &[u8]) -> [u8; DIGEST_BYTES] {
+    let mut engine = hmac::HmacEngine::<sha256::Hash>::new(random_part);
+    engine.input(secret);
+    let mut digest = [0u8; DIGEST_BYTES];
+    digest.copy_from_slice(&hmac::Hmac::from_engine(engine).to_byte_array()[..DIGEST_BYTES]);
+    digest
+}
+
+/// Evaluate at `x` the polynomial over GF(256) going through the shares, whose indices must be
+/// distinct and values of the same length.
+fn interpolate(shares: &[(u8, Vec<u8>)], x: u8) -> Vec<u8> {
+    if let Some((_, value)) = shares.iter().find(|(index, _)| *index == x) {
+        return value.clone();
+    }
+    let log = |value: u8| u32::from(LOG[usize::from(value)]);
+    let log_product: u32 = shares.iter().map(|(index, _)| log(index ^ x)).sum();
+    let mut result = vec![0u8; shares[0].1.len()];
+    for (index, value) in shares {
+        let others: u32 = shares.iter().map(|(other, _)| log(index ^ other)).sum();
+        let log_basis = (255 * 16 + log_product - log(index ^ x) - others) % 255;
+        for (byte, share_byte) in result.iter_mut().zip(value) {
+            if *share_byte != 0 {
+                *byte ^= EXP[((log(*share_byte) + log_basis) % 255) as usize];
+            }
+        }
+    }
+    result
+}
+
+const fn gf256_tables() -> ([u8; 255], [u8; 256]) {
+    let mut exp = [0u8; 255];
+    let mut log = [0u8; 256];
+    let mut poly: u16 = 1;
+    let mut i = 0;
+    while i < 255 {
+        exp[i] = poly as u8;
+        log[poly as usize] = i as u8;
+        // Multiply by x + 1 and reduce by the Rijndael polynomial x^8 + x^4 + x^3 + x + 1.
+        poly ^= poly << 1;
+        if poly & 0x100 != 0 {
+            poly ^= 0x11b;
+        }
+        i += 1;
+    }
+    (exp, log)
+}
+
+const EXP: [u8; 255] = gf256_tables().0;
+const LOG: [u8; 256] = gf256_tables().1;
+
+fn rs1024_polymod(values: &[u16], extendable: bool) -> u32 {
+    const GEN: [u32; 10] = [
+        0xe0e040, 0x1c1c080, 0x3838100, 0x7070200, 0xe0e0009, 0x1c0c2412, 0x38086c24, 0x3090fc48,
+        0x21b1f890, 0x3f3f120,
+    ];
+    let customization: &[u8] = if extendable {
+        b"shamir_extendable"
+    } else {
+        b"shamir"
+    };
+    customization
+        .iter()
+        .map(|byte| u16::from(*byte))
+        .chain(values.iter().copied())
+        .fold(1, |checksum, value| {
+            let top = checksum >> 20;
+            let checksum = ((checksum & 0xfffff) << 10) ^ u32::from(value);
+            (0..10)
+                .filter(|i| (top >> i) & 1 == 1)
+                .fold(checksum, |checksum, i| checksum ^ GEN[i])
+        })
+}
+
+fn rs1024_checksum(values: &[u16], extendable: bool) -> [u16; 3] {
+    let polymod = rs1024_polymod(&[values, &[0, 0, 0]].concat(), extendable) ^ 1;
+    [
+        ((polymod >> 20) & 0x3ff) as u16,
+        ((polymod >> 10) & 0x3ff) as u16,
+        (polymod & 0x3ff) as u16,
+    ]
+}
+
+/// Encode bytes as 10 bit words, padding with zero bits on the left.
+fn bytes_to_words(bytes: &[u8]) -> Vec<u16> {
+    let word_count = (bytes.len() * 8).div_ceil(RADIX_BITS);
+    let mut bits = word_count * RADIX_BITS - bytes.len() * 8;
+    let mut accumulator = 0u32;
+    let mut words = Vec::with_capacity(word_count);
+    for byte in bytes {
+        accumulator = (accumulator << 8) | u32::from(*byte);
+        bits += 8;
+        if bits >= RADIX_BITS {
+            bits -= RADIX_BITS;
+            words.push((accumulator >> bits) as u16);
+            accumulator &= (1 << bits) - 1;
+        }
+    }
+    words
+}
+
+/// Decode 10 bit words into bytes, the `padding` leading bits must be zero.
+fn words_to_bytes(words: &[u16], padding: usize) -> Option<Vec<u8>> {
+    if words
+        .first()
+        .is_some_and(|word| word >> (RADIX_BITS - padding) != 0)
+    {
+        return None;
+    }
+    let mut bits = 0;
+    let mut skip = padding;
+    let mut accumulator = 0u32;
+    let mut bytes = Vec::with_capacity((words.len() * RADIX_BITS - padding) / 8);
+    for word in words {
+        accumulator = (accumulator << RADIX_BITS) | u32::from(*word);
+        bits += RADIX_BITS - skip;
+        accumulator &= (1 << bits) - 1;
+        skip = 0;
+        while bits >= 8 {
+            bits -= 8;
+            bytes.push((accumulator >> bits) as u8);
+            accumulator &= (1 << bits) - 1;
+        }
+    }
+    Some(bytes)
+}
+
+/// The SLIP-39 wordlist, whose words are uniquely identified by their first four letters.
+#[rustfmt::skip]
+const WORDLIST: [&str; 1024] = [
+    "academic", "acid", "acne", "acquire", "acrobat", "activity", "actress", "adapt",
+    "adequate", "adjust", "admit", "adorn", "adult", "advance", "advocate", "afraid",
+    "again", "agency", "agree", "aide", "aircraft", "airline", "airport", "ajar",
+    "alarm", "album", "alcohol", "alien", "alive", "alpha", "already", "alto",
+    "aluminum", "always", "amazing", "ambition", "amount", "amuse", "analysis", "anatomy",
+    "ancestor", "ancient", "angel", "angry", "animal", "answer", "antenna", "anxiety",
+    "apart", "aquatic", "arcade", "arena", "argue", "armed", "artist", "artwork",
+    "aspect", "auction", "august", "aunt", "average", "aviation", "avoid", "award",
+    "away", "axis", "axle", "beam", "beard", "beaver", "become", "bedroom",
+    "behavior", "being", "believe", "belong", "benefit", "best", "beyond", "bike",
+    "biology", "birthday", "bishop", "black", "blanket", "blessing", "blimp", "blind",
+    "blue", "body", "bolt", "boring", "born", "both", "boundary", "bracelet",
+    "branch", "brave", "breathe", "briefing", "broken", "brother", "browser", "bucket",
+    "budget", "building", "bulb", "bulge", "bumpy", "bundle", "burden", "burning",
+    "busy", "buyer", "cage", "calcium", "camera", "campus", "canyon", "capacity",
+    "capital", "capture", "carbon", "cards", "careful", "cargo", "carpet", "carve",
+    "category", "cause", "ceiling", "center", "ceramic", "champion", "change", "charity",
+    "check", "chemical", "chest", "chew", "chubby", "cinema", "civil", "class",
+    "clay", "cleanup", "client", "climate", "clinic", "clock", "clogs", "closet",
+    "clothes", "club", "cluster", "coal", "coastal", "coding", "column", "company",
+    "corner", "costume", "counter", "course", "cover", "cowboy", "cradle", "craft",
+    "crazy", "credit", "cricket", "criminal", "crisis", "critical", "crowd", "crucial",
+    "crunch", "crush", "crystal", "cubic", "cultural", "curious", "curly", "custody",
+    "cylinder", "daisy", "damage", "dance", "darkness", "database", "daughter", "deadline",
+    "deal", "debris", "debut", "decent", "decision", "declare", "decorate", "decrease",
+    "deliver", "demand", "density", "deny", "depart", "depend", "depict", "deploy",
+    "describe", "desert", "desire", "desktop", "destroy", "detailed", "detect", "device",
+    "devote", "diagnose", "dictate", "diet", "dilemma", "diminish", "dining", "diploma",
+    "disaster", "discuss", "disease", "dish", "dismiss", "display", "distance", "dive",
+    "divorce", "document", "domain", "domestic", "dominant", "dough", "downtown", "dragon",
+    "dramatic", "dream", "dress", "drift", "drink", "drove", "drug", "dryer",
+    "duckling", "duke", "duration", "dwarf", "dynamic", "early", "earth", "easel",
+    "easy", "echo", "eclipse", "ecology", "edge", "editor", "educate", "either",
+    "elbow", "elder", "election", "elegant", "element", "elephant", "elevator", "elite",
+    "else", "email", "emerald", "emission", "emperor", "emphasis", "employer", "empty",
+    "ending", "endless", "endorse", "enemy", "energy", "enforce", "engage", "enjoy",
+    "enlarge", "entrance", "envelope", "envy", "epidemic", "episode", "equation", "equip",
+    "eraser", "erode", "escape", "estate", "estimate", "evaluate", "evening", "evidence",
+    "evil", "evoke", "exact", "example", "exceed", "exchange", "exclude", "excuse",
+    "execute", "exercise", "exhaust", "exotic", "expand", "expect", "explain", "express",
+    "extend", "extra", "eyebrow", "facility", "fact", "failure", "faint", "fake",
+    "false", "family", "famous", "fancy", "fangs", "fantasy", "fatal", "fatigue",
+    "favorite", "fawn", "fiber", "fiction", "filter", "finance", "findings", "finger",
+    "firefly", "firm", "fiscal", "fishing", "fitness", "flame", "flash", "flavor",
+    "flea", "flexible", "flip", "float", "floral", "fluff", "focus", "forbid",
+    "force", "forecast", "forget", "formal", "fortune", "forward", "founder", "fraction",
+    "fragment", "frequent", "freshman", "friar", "fridge", "friendly", "frost", "froth",
+    "frozen", "fumes", "funding", "furl", "fused", "galaxy", "game", "garbage",
+    "garden", "garlic", "gasoline", "gather", "general", "genius", "genre", "genuine",
+    "geology", "gesture", "glad", "glance", "glasses", "glen", "glimpse", "goat",
+    "golden", "graduate", "grant", "grasp", "gravity", "gray", "greatest", "grief",
+    "grill", "grin", "grocery", "gross", "group", "grownup", "grumpy", "guard",
+    "guest", "guilt", "guitar", "gums", "hairy", "hamster", "hand", "hanger",
+    "harvest", "have", "havoc", "hawk", "hazard", "headset", "health", "hearing",
+    "heat", "helpful", "herald", "herd", "hesitate", "hobo", "holiday", "holy",
+    "home", "hormone", "hospital", "hour", "huge", "human", "humidity", "hunting",
+    "husband", "hush", "husky", "hybrid", "idea", "identify", "idle", "image",
+    "impact", "imply", "improve", "impulse", "include", "income", "increase", "index",
+    "indicate", "industry", "infant", "inform", "inherit", "injury", "inmate", "insect",
+    "inside", "install", "intend", "intimate", "invasion", "involve", "iris", "island",
+    "isolate", "item", "ivory", "jacket", "jerky", "jewelry", "join", "judicial",
+    "juice", "jump", "junction", "junior", "junk", "jury", "justice", "kernel",
+    "keyboard", "kidney", "kind", "kitchen", "knife", "knit", "laden", "ladle",
+    "ladybug", "lair", "lamp", "language", "large", "laser", "laundry", "lawsuit",
+    "leader", "leaf", "learn", "leaves", "lecture", "legal", "legend", "legs",
+    "lend", "length", "level", "liberty", "library", "license", "lift", "likely",
+    "lilac", "lily", "lips", "liquid", "listen", "literary", "living", "lizard",
+    "loan", "lobe", "location", "losing", "loud", "loyalty", "luck", "lunar",
+    "lunch", "lungs", "luxury", "lying", "lyrics", "machine", "magazine", "maiden",
+    "mailman", "main", "makeup", "making", "mama", "manager", "mandate", "mansion",
+    "manual", "marathon", "march", "market", "marvel", "mason", "material", "math",
+    "maximum", "mayor", "meaning", "medal", "medical", "member", "memory", "mental",
+    "merchant", "merit", "method", "metric", "midst", "mild", "military", "mineral",
+    "minister", "miracle", "mixed", "mixture", "mobile", "modern", "modify", "moisture",
+    "moment", "morning", "mortgage", "mother", "mountain", "mouse", "move", "much",
+    "mule", "multiple", "muscle", "museum", "music", "mustang", "nail", "national",
+    "necklace", "negative", "nervous", "network", "news", "nuclear", "numb", "numerous",
+    "nylon", "oasis", "obesity", "object", "observe", "obtain", "ocean", "often",
+    "olympic", "omit", "oral", "orange", "orbit", "order", "ordinary", "organize",
+    "ounce", "oven", "overall", "owner", "paces", "pacific", "package", "paid",
+    "painting", "pajamas", "pancake", "pants", "papa", "paper", "parcel", "parking",
+    "party", "patent", "patrol", "payment", "payroll", "peaceful", "peanut", "peasant",
+    "pecan", "penalty", "pencil", "percent", "perfect", "permit", "petition", "phantom",
+    "pharmacy", "photo", "phrase", "physics", "pickup", "picture", "piece", "pile",
+    "pink", "pipeline", "pistol", "pitch", "plains", "plan", "plastic", "platform",
+    "playoff", "pleasure", "plot", "plunge", "practice", "prayer", "preach", "predator",
+    "pregnant", "premium", "prepare", "presence", "prevent", "priest", "primary", "priority",
+    "prisoner", "privacy", "prize", "problem", "process", "profile", "program", "promise",
+    "prospect", "provide", "prune", "public", "pulse", "pumps", "punish", "puny",
+    "pupal", "purchase", "purple", "python", "quantity", "quarter", "quick", "quiet",
+    "race", "racism", "radar", "railroad", "rainbow", "raisin", "random", "ranked",
+    "rapids", "raspy", "reaction", "realize", "rebound", "rebuild", "recall", "receiver",
+    "recover", "regret", "regular", "reject", "relate", "remember", "remind", "remove",
+    "render", "repair", "repeat", "replace", "require", "rescue", "research", "resident",
+    "response", "result", "retailer", "retreat", "reunion", "revenue", "review", "reward",
+    "rhyme", "rhythm", "rich", "rival", "river", "robin", "rocky", "romantic",
+    "romp", "roster", "round", "royal", "ruin", "ruler", "rumor", "sack",
+    "safari", "salary", "salon", "salt", "satisfy", "satoshi", "saver", "says",
+    "scandal", "scared", "scatter", "scene", "scholar", "science", "scout", "scramble",
+    "screw", "script", "scroll", "seafood", "season", "secret", "security", "segment",
+    "senior", "shadow", "shaft", "shame", "shaped", "sharp", "shelter", "sheriff",
+    "short", "should", "shrimp", "sidewalk", "silent", "silver", "similar", "simple",
+    "single", "sister", "skin", "skunk", "slap", "slavery", "sled", "slice",
+    "slim", "slow", "slush", "smart", "smear", "smell", "smirk", "smith",
+    "smoking", "smug", "snake", "snapshot", "sniff", "society", "software", "soldier",
+    "solution", "soul", "source", "space", "spark", "speak", "species", "spelling",
+    "spend", "spew", "spider", "spill", "spine", "spirit", "spit", "spray",
+    "sprinkle", "square", "squeeze", "stadium", "staff", "standard", "starting", "station",
+    "stay", "steady", "step", "stick", "stilt", "story", "strategy", "strike",
+    "style", "subject", "submit", "sugar", "suitable", "sunlight", "superior", "surface",
+    "surprise", "survive", "sweater", "swimming", "swing", "switch", "symbolic", "sympathy",
+    "syndrome", "system", "tackle", "tactics", "tadpole", "talent", "task", "taste",
+    "taught", "taxi", "teacher", "teammate", "teaspoon", "temple", "tenant", "tendency",
+    "tension", "terminal", "testify", "texture", "thank", "that", "theater", "theory",
+    "therapy", "thorn", "threaten", "thumb", "thunder", "ticket", "tidy", "timber",
+    "timely", "ting", "tofu", "together", "tolerate", "total", "toxic", "tracks",
+    "traffic", "training", "transfer", "trash", "traveler", "treat", "trend", "trial",
+    "tricycle", "trip", "triumph", "trouble", "true", "trust", "twice", "twin",
+    "type", "typical", "ugly", "ultimate", "umbrella", "uncover", "undergo", "unfair",
+    "unfold", "unhappy", "union", "universe", "unkind", "unknown", "unusual", "unwrap",
+    "upgrade", "upstairs", "username", "usher", "usual", "valid", "valuable", "vampire",
+    "vanish", "various", "vegan", "velvet", "venture", "verdict", "verify", "very",
+    "veteran", "vexed", "victim", "video", "view", "vintage", "violence", "viral",
+    "visitor", "visual", "vitamins", "vocal", "voice", "volume", "voter", "voting",
+    "walnut", "warmth", "warn", "watch", "wavy", "wealthy", "weapon", "webcam",
+    "welcome", "welfare", "western", "width", "wildlife", "window", "wine", "wireless",
+    "wisdom", "withdraw", "wits", "wolf", "woman", "work", "worthy", "wrap",
+    "wrist", "writing", "wrote", "year", "yelp", "yield", "yoga", "zero",
+];
@@ -0,0 +1,298 @@
+use crate::error::DescriptorError;
+use crate::types::{KeychainKind, LockTime};
+
+use bdk_wallet::bitcoin::{absolute, relative};
+use bdk_wallet::chain::ChainPosition;
+use bdk_wallet::descriptor::policy::{
+    PkOrF as BdkPkOrF, Policy as BdkPolicy, SatisfiableItem as BdkSatisfiableItem,
+};
+use bdk_wallet::Wallet as BdkWallet;
+
+use std::collections::{BTreeMap, HashMap};
+
+/// The most spending paths listed for a policy. Large thresholds, and nested ones, have too many
+/// combinations of items to be listed.
+pub(crate) const MAX_SPENDING_PATHS: usize = 1_000;
+
+/// One of the ways the outputs of a keychain can be spent, see `Wallet::spending_paths`.
+#[derive(Debug, Clone, uniffi::Record)]
+pub struct SpendingPath {
+    /// The keychain this path spends from.
+    pub keychain: KeychainKind,
+    /// A human readable summary of the requirements of this path.
+    pub description: String,
+    /// The groups of keys that need to sign, all of them have to reach their threshold.
+    pub signers: Vec<SignerRequirement>,
+    /// The hashes whose preimages need to be revealed, prefixed with the hash function
+    /// (e.g. `sha256:<hex>`).
+    pub preimages: Vec<String>,
+    /// The relative timelock (nSequence consensus value) the spending inputs must satisfy.
+    pub relative_timelock: Option<u32>,
+    /// The absolute timelock the spending transaction must satisfy.
+    pub absolute_timelock: Option<LockTime>,
+    /// Whether the timelocks of this path are satisfied at the wallet's chain tip. For a relative
+    /// timelock it is enough that one of the unspent outputs of the keychain is old enough.
+    pub is_spendable_now: bool,
+    /// The policy node ids and selected items to pass to `TxBuilder::policy_path`.
+    pub policy_path: HashMap<String, Vec<u64>>,
+}
+
+/// A set of keys of which `threshold` need to sign.
+#[derive(Debug, Clone, uniffi::Record)]
+pub struct SignerRequirement {
+    /// The public keys, or the fingerprints of the extended keys, of the signers.
+    pub keys: Vec<String>,
+    /// The number of signatures required.
+    pub threshold: u64,
+}
+
+pub(crate) fn spending_paths(
+    wallet: &BdkWallet,
+    keychain: KeychainKind,
+    policy: &BdkPolicy,
+    current_time: u64,
+) -> Result<Vec<SpendingPath>, DescriptorError> {
+    let tip_height = wallet.latest_checkpoint().height();
+    // Height and time at which the keychain's confirmed unspent outputs were mined.
+    let confirmations = wallet
+        .list_unspent()
+        .filter(|output| output.keychain == keychain)
+        .filter_map(|output| match output.chain_position {
+            ChainPosition::Confirmed { anchor, .. } => {
+                Some((anchor.block_id.height, anchor.confirmation_time))
+            }
+            ChainPosition::Unconfirmed { .. } => None,
+        })
+        .collect::<Vec<_>>();
+
+    let branches = branches(policy).ok_or(DescriptorError::TooManySpendingPaths {
+        max: MAX_SPENDING_PATHS as u64,
+    })?;
+    let paths = branches
+        .into_iter()
+        .filter_map(|branch| {
+            // Paths mixing incompatible timelocks are rejected by bdk and cannot be spent.
+            let condition = policy.get_condition(&branch.selection).ok()?;
+            let relative = condition.csv.and_then(|csv| csv.to_relative_lock_time());
+            let relative_satisfied = relative.is_none_or(|lock| {
+                confirmations.iter().any(|(height, time)| match lock {
+                    relative::LockTime::Blocks(blocks) => {
+                        (tip_height + 1).saturating_sub(*height) >= u32::from(blocks.value())
+                    }
+                    relative::LockTime::Time(interval) => {
+                        current_time.saturating_sub(*time) >= u64::from(interval.value()) * 512
+                    }
+                })
+            });
+            let absolute_satisfied = condition.timelock.is_none_or(|lock| match lock {
+                absolute::LockTime::Blocks(height) => tip_height >= height.to_consensus_u32(),
+                absolute::LockTime::Seconds(time) => {
+                    current_time >= u64::from(time.to_consensus_u32())
+                }
+            });
+
+            Some(SpendingPath {
+                keychain,
+                description: branch.describe(relative, condition.timelock),
+                signers: branch.signers,
+                preimages: branch.preimages,
+                relative_timelock: condition.csv.map(|csv| csv.to_consensus_u32()),
+                absolute_timelock: condition.timelock.map(LockTime::from),
+                is_spendable_now: relative_satisfied && absolute_satisfied,
+                policy_path: branch
+                    .selection
+                    .into_iter()
+                    .map(|(id, items)| (id, items.into_iter().map(|i| i as u64).collect()))
+                    .collect(),
+            })
+        })
+        .collect();
+    Ok(paths)
+}
+
+/// The requirements collected while walking down one selection of a policy tree.
+#[derive(Clone, Default)]
+struct Branch {
+    selection: BTreeMap<String, Vec<usize>>,
+    signers: Vec<SignerRequirement>,
+    preimages: Vec<String>,
+}
+
+impl Branch {
+    fn join(&self, other: &Branch) -> Branch {
+        let mut joined = self.clone();
+        joined.selection.extend(other.selection.clone());
+        joined.signers.extend(other.signers.iter().cloned());
+        joined.preimages.extend(other.preimages.iter().cloned());
+        joined
+    }
+
+    fn describe(
+        &self,
+        relative: Option<relative::LockTime>,
+        absolute: Option<absolute::LockTime>,
+    ) -> String {
+        let mut parts = self
+            .signers
+            .iter()
+            .map(|signer| match signer.keys.as_slice() {
+                [key] => format!("signature from {key}"),
+                keys => format!(
+                    "{} of {} signatures from {}",
+                    signer.threshold,
+                    keys.len(),
+                    keys.join(", ")
+                ),
+            })
+            .chain(
+                self.preimages
+                    .iter()
+                    .map(|preimage| format!("preimage of {preimage}")),
+            )
+            .collect::<Vec<_>>();
+        match relative {
+            Some(relative::LockTime::Blocks(blocks)) => {
+                parts.push(format!("{} blocks after confirmation", blocks.value()))
+            }
+            Some(relative::LockTime::Time(interval)) => parts.push(format!(
+                "{} seconds after confirmation",
+                u32::from(interval.value()) * 512
+            )),
+            None => {}
+        }
+        match absolute {
+            Some(absolute::LockTime::Blocks(height)) => parts.push(format!("after block {height}")),
+            Some(absolute::LockTime::Seconds(time)) => parts.push(format!("after time {time}")),
+            None => {}
+        }
+        if parts.is_empty() {
+            "no requirements".to_string()
+        } else {
+            parts.join(", ")
+        }
+    }
+}
+
+/// The branches of `policy`, or `None` if there are more than `MAX_SPENDING_PATHS`.
+fn branches(policy: &BdkPolicy) -> Option<Vec<Branch>> {
+    let signer = |keys: &[BdkPkOrF], threshold: usize| Branch {
+        signers: vec![SignerRequirement {
+            keys: keys.iter().map(key_string).collect(),
+            threshold: threshold as u64,
+        }],
+        ..Default::default()
+    };
+    let preimage = |preimage: String| Branch {
+        preimages: vec![preimage],
+        ..Default::default()
+    };
+
+    match &policy.item {
+        BdkSatisfiableItem::EcdsaSignature(key) | BdkSatisfiableItem::SchnorrSignature(key) => {
+            Some(vec![signer(std::slice::from_ref(key), 1)])
+        }
+        BdkSatisfiableItem::Multisig { keys, threshold } => Some(vec![signer(keys, *threshold)]),
+        BdkSatisfiableItem::Sha256Preimage { hash } => {
+            Some(vec![preimage(format!("sha256:{hash}"))])
+        }
+        BdkSatisfiableItem::Hash256Preimage { hash } => {
+            Some(vec![preimage(format!("hash256:{hash}"))])
+        }
+        BdkSatisfiableItem::Ripemd160Preimage { hash } => {
+            Some(vec![preimage(format!("ripemd160:{hash}"))])
+        }
+        BdkSatisfiableItem::Hash160Preimage { hash } => {
+            Some(vec![preimage(format!("hash160:{hash}"))])
+        }
+        // Timelocks are read back from the condition of the whole path.
+        BdkSatisfiableItem::AbsoluteTimelock { .. }
+        | BdkSatisfiableItem::RelativeTimelock { .. } => Some(vec![Branch::default()]),
+        BdkSatisfiableItem::Thresh { items, threshold } => {
+            let children = items.iter().map(branches).collect::<Option<Vec<_>>>()?;
+            threshold_alternatives(
+                &children,
+                *threshold,
+                |selected| {
+                    let mut selection = BTreeMap::new();
+                    selection.insert(policy.id.clone(), selected.to_vec());
+                    Branch {
+                        selection,
+                        ..Default::default()
+                    }
+                },
+                Branch::join,
+            )
+        }
+    }
+}
+
+/// Every way of satisfying `threshold` of the `items`, each given by its own alternatives: every
+/// alternative of a selected item is joined with every alternative of the others, starting from
+/// the `root` of the selection. Returns `None` if there are more than `MAX_SPENDING_PATHS`.
+pub(crate) fn threshold_alternatives<T>(
+    items: &[Vec<T>],
+    threshold: usize,
+    root: impl Fn(&[usize]) -> T,
+    join: impl Fn(&T, &T) -> T,
+) -> Option<Vec<T>> {
+    if binomial(items.len(), threshold) > MAX_SPENDING_PATHS {
+        return None;
+    }
+    let mut alternatives = Vec::new();
+    for selected in combinations(items.len(), threshold) {
+        let mut partial = vec![root(&selected)];
+        for index in &selected {
+            if partial.len().saturating_mul(items[*index].len()) > MAX_SPENDING_PATHS {
+                return None;
+            }
+            partial = partial
+                .iter()
+                .flat_map(|alternative| {
+                    let join = &join;
+                    items[*index]
+                        .iter()
+                        .map(move |item| join(alternative, item))
+                })
+                .collect();
+        }
+        alternatives.extend(partial);
+        if alternatives.len() > MAX_SPENDING_PATHS {
+            return None;
+        }
+    }
+    Some(alternatives)
+}
+
+/// The number of subsets of `k` items out of `n`, saturating at `usize::MAX`.
+fn binomial(n: usize, k: usize) -> usize {
+    (0..k.min(n))
+        .try_fold(1usize, |count, i| {
+            count.checked_mul(n - i).map(|count| count / (i + 1))
+        })
+        .unwrap_or(usize::MAX)
+}
+
+/// All the sorted subsets of `k` indices out of `0..n`.
+fn combinations(n: usize, k: usize) -> Vec<Vec<usize>> {
+    if k == 0 {
+        return vec![vec![]];
+    }
+    (k - 1..n)
+        .flat_map(|last| {
+            combinations(last, k - 1)
+                .into_iter()
+                .map(move |mut subset| {
+                    subset.push(last);
+                    subset
+                })
+        })
+        .collect()
+}
+
+fn key_string(key: &BdkPkOrF) -> String {
+    match key {
+        BdkPkOrF::Pubkey(pubkey) => pubkey.to_string(),
+        BdkPkOrF::XOnlyPubkey(pubkey) => pubkey.to_string(),
+        BdkPkOrF::Fingerprint(fingerprint) => fingerprint.to_string(),
+    }
+}
@@ -1,12 +1,20 @@
 use crate::error::{PersistenceError, PreV1MigrationError};
 use crate::types::{ChangeSet, KeychainKind};
 
+use bdk_wallet::bitcoin::secp256k1::rand;
+use bdk_wallet::bitcoin::secp256k1::rand::Rng;
+use bdk_wallet::chain::Merge;
 use bdk_wallet::migration::{
     get_pre_v1_wallet_keychains as bdk_get_pre_v1_wallet_keychains,
     PreV1WalletKeychain as BdkPreV1WalletKeychain,
 };
+use bdk_wallet::rusqlite::{params, OptionalExtension};
+use bdk_wallet::serde_json;
 use bdk_wallet::{rusqlite::Connection as BdkConnection, WalletPersister};
+use chacha20_poly1305::{ChaCha20Poly1305, Key, Nonce};
+use zeroize::Zeroize;
 
+use std::convert::TryInto;
 use std::ops::DerefMut;
 use std::sync::{Arc, Mutex};
 
@@ -23,6 +31,7 @@ pub trait Persistence: Send + Sync {
 pub(crate) enum PersistenceType {
     Custom(Arc<dyn Persistence>),
     Sql(Mutex<BdkConnection>),
+    EncryptedSql(Mutex<EncryptedStore>),
 }
 
 /// `PreV1WalletKeychain` represents a structure that holds the keychain details
@@ -64,6 +73,18 @@ impl Persister {
         })
     }
 
+    /// Create a new Sqlite connection at the specified file path, storing the wallet data
+    /// encrypted with a 32 byte key. Opening an existing database with another key fails with
+    /// `PersistenceError::WrongKey`.
+    #[uniffi::constructor]
+    pub fn new_encrypted_sqlite(path: String, key: Vec<u8>) -> Result<Self, PersistenceError> {
+        let conn = BdkConnection::open(path)?;
+        let store = EncryptedStore::open(conn, encryption_key(&key)?)?;
+        Ok(Self {
+            inner: PersistenceType::EncryptedSql(store.into()).into(),
+        })
+    }
+
     /// Use a native persistence layer.
     #[uniffi::constructor]
     pub fn custom(persistence: Arc<dyn Persistence>) -> Self {
@@ -84,9 +105,26 @@ impl Persister {
                     .map(|keychains| keychains.into_iter().map(Into::into).collect())
                     .map_err(Into::into)
             }
+            PersistenceType::EncryptedSql(ref store) => {
+                let mut store_lock = store.lock().unwrap();
+                bdk_get_pre_v1_wallet_keychains(&mut store_lock.conn)
+                    .map(|keychains| keychains.into_iter().map(Into::into).collect())
+                    .map_err(Into::into)
+            }
             PersistenceType::Custom(_) => Err(PreV1MigrationError::SqliteOnly),
         }
     }
+
+    /// Re-encrypt the data of an encrypted Sqlite persister with a new 32 byte key, which has to
+    /// be used from then on to open the database.
+    pub fn rotate_key(&self, new_key: Vec<u8>) -> Result<(), PersistenceError> {
+        let new_key = encryption_key(&new_key)?;
+        let mut lock = self.inner.lock().unwrap();
+        match lock.deref_mut() {
+            PersistenceType::EncryptedSql(ref store) => store.lock().unwrap().rotate_key(new_key),
+            _ => Err(PersistenceError::NotEncrypted),
+        }
+    }
 }
 
 impl From<BdkPreV1WalletKeychain> for PreV1WalletKeychain {
@@ -109,6 +147,7 @@ impl WalletPersister for PersistenceType {
                 let deref = lock.deref_mut();
                 Ok(BdkConnection::initialize(deref)?)
             }
+            PersistenceType::EncryptedSql(ref store) => store.lock().unwrap().initialize(),
             PersistenceType::Custom(any) => any
                 .initialize()
                 .map(|changeset| changeset.as_ref().clone().into()),
@@ -122,6 +161,7 @@ impl WalletPersister for PersistenceType {
                 let deref = lock.deref_mut();
                 Ok(BdkConnection::persist(deref, changeset)?)
             }
+            PersistenceType::EncryptedSql(ref store) => store.lock().unwrap().persist(changeset),
             PersistenceType::Custom(any) => {
                 let ffi_changeset: ChangeSet = changeset.clone().into();
                 any.persist(Arc::new(ffi_changeset))
@@ -129,3 +169,205 @@ impl WalletPersister for PersistenceType {
         }
     }
 }
+
+const KEY_LENGTH: usize = 32;
+const NONCE_LENGTH: usize = 12;
+const TAG_LENGTH: usize = 16;
+
+// Separate the encryption of the key check from that of the changesets. Each changeset is
+// encrypted with its row id appended to the AAD, and the key check holds the number of rows, so
+// that deleting, reordering or truncating rows is detected.
+const KEY_CHECK_AAD: &[u8] = b"bdk encrypted store key check";
+const CHANGESET_AAD: &[u8] = b"bdk encrypted store changeset";
+
+/// A Sqlite database holding wallet changesets serialized as JSON and encrypted with
+/// ChaCha20-Poly1305. Every persisted changeset is appended as a row numbered from 1, and they are
+/// merged back together on initialization.
+pub(crate) struct EncryptedStore {
+    conn: BdkConnection,
+    key: [u8; KEY_LENGTH],
+    rows: u64,
+}
+
+impl EncryptedStore {
+    fn open(mut conn: BdkConnection, key: [u8; KEY_LENGTH]) -> Result<Self, PersistenceError> {
+        let tx = conn.transaction()?;
+        tx.execute_batch(
+            "CREATE TABLE IF NOT EXISTS bdk_encrypted_key_check (
+                id INTEGER PRIMARY KEY CHECK (id = 0),
+                data BLOB NOT NULL
+            );
+            CREATE TABLE IF NOT EXISTS bdk_encrypted_changeset (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                data BLOB NOT NULL
+            );",
+        )?;
+        // The row count encrypted with the key, to detect a wrong key before reading any data.
+        let key_check: Option<Vec<u8>> = tx
+            .query_row(
+                "SELECT data FROM bdk_encrypted_key_check WHERE id = 0",
+                [],
+                |row| row.get(0),
+            )
+            .optional()?;
+        let rows = match key_check {
+            Some(key_check) => {
+                let plaintext = decrypt(&key, &key_check, KEY_CHECK_AAD)?;
+                let rows: [u8; 8] = plaintext
+                    .as_slice()
+                    .try_into()
+                    .map_err(|_| PersistenceError::Tampered)?;
+                u64::from_be_bytes(rows)
+            }
+            None => {
+                tx.execute(
+                    "INSERT INTO bdk_encrypted_key_check (id, data) VALUES (0, ?1)",
+                    params![encrypt_key_check(&key, 0)],
+                )?;
+                0
+            }
+        };
+        tx.commit()?;
+        Ok(Self { conn, key, rows })
+    }
+
+    fn initialize(&mut self) -> Result<bdk_wallet::ChangeSet, PersistenceError> {
+        read_changesets(&self.conn, &self.key, self.rows)
+    }
+
+    fn persist(&mut self, changeset: &bdk_wallet::ChangeSet) -> Result<(), PersistenceError> {
+        if changeset.is_empty() {
+            return Ok(());
+        }
+        let id = self.rows + 1;
+        let tx = self.conn.transaction()?;
+        tx.execute(
+            "INSERT INTO bdk_encrypted_changeset (id, data) VALUES (?1, ?2)",
+            params![id, encrypt_changeset(&self.key, id, changeset)?],
+        )?;
+        tx.execute(
+            "UPDATE bdk_encrypted_key_check SET data = ?1 WHERE id = 0",
+            params![encrypt_key_check(&self.key, id)],
+        )?;
+        tx.commit()?;
+        self.rows = id;
+        Ok(())
+    }
+
+    /// Replace the changesets with their aggregate encrypted with the new key, in one transaction.
+    fn rotate_key(&mut self, new_key: [u8; KEY_LENGTH]) -> Result<(), PersistenceError> {
+        let tx = self.conn.transaction()?;
+        let changeset = read_changesets(&tx, &self.key, self.rows)?;
+        tx.execute("DELETE FROM bdk_encrypted_changeset", [])?;
+        let rows = if changeset.is_empty() { 0 } else { 1 };
+        if rows == 1 {
+            tx.execute(
+                "INSERT INTO bdk_encrypted_changeset (id, data) VALUES (?1, ?2)",
+                params![rows, encrypt_changeset(&new_key, rows, &changeset)?],
+            )?;
+        }
+        tx.execute(
+            "UPDATE bdk_encrypted_key_check SET data = ?1 WHERE id = 0",
+            params![encrypt_key_check(&new_key, rows)],
+        )?;
+        tx.commit()?;
+        self.key = new_key;
+        self.rows = rows;
+        Ok(())
+    }
+}
+
+impl Drop for EncryptedStore {
+    fn drop(&mut self) {
+        self.key.zeroize();
+    }
+}
+
+fn encryption_key(key: &[u8]) -> Result<[u8; KEY_LENGTH], PersistenceError> {
+    if key.len() != KEY_LENGTH {
+        return Err(PersistenceError::InvalidKeyLength {
+            length: key.len() as u64,
+        });
+    }
+    let mut encryption_key = [0u8; KEY_LENGTH];
+    encryption_key.copy_from_slice(key);
+    Ok(encryption_key)
+}
+
+/// Read and merge the changesets, which must be exactly the rows numbered 1 to `rows`.
+fn read_changesets(
+    conn: &BdkConnection,
+    key: &[u8; KEY_LENGTH],
+    rows: u64,
+) -> Result<bdk_wallet::ChangeSet, PersistenceError> {
+    let mut statement = conn.prepare("SELECT id, data FROM bdk_encrypted_changeset ORDER BY id")?;
+    let mut stored = statement.query_map([], |row| {
+        Ok((row.get::<_, u64>(0)?, row.get::<_, Vec<u8>>(1)?))
+    })?;
+    let mut aggregate = bdk_wallet::ChangeSet::default();
+    for expected in 1..=rows {
+        let (id, data) = stored.next().ok_or(PersistenceError::Tampered)??;
+        if id != expected {
+            return Err(PersistenceError::Tampered);
+        }
+        let plaintext =
+            decrypt(key, &data, &changeset_aad(id)).map_err(|_| PersistenceError::Tampered)?;
+        let changeset: bdk_wallet::ChangeSet =
+            serde_json::from_slice(&plaintext).map_err(|error| PersistenceError::Reason {
+                error_message: error.to_string(),
+            })?;
+        aggregate.merge(changeset);
+    }
+    if stored.next().is_some() {
+        return Err(PersistenceError::Tampered);
+    }
+    Ok(aggregate)
+}
+
+fn encrypt_changeset(
+    key: &[u8; KEY_LENGTH],
+    id: u64,
+    changeset: &bdk_wallet::ChangeSet,
+) -> Result<Vec<u8>, PersistenceError> {
+    let plaintext = serde_json::to_vec(changeset).map_err(|error| PersistenceError::Reason {
+        error_message: error.to_string(),
+    })?;
+    Ok(encrypt(key, &plaintext, &changeset_aad(id)))
+}
+
+fn encrypt_key_check(key: &[u8; KEY_LENGTH], rows: u64) -> Vec<u8> {
+    encrypt(key, &rows.to_be_bytes(), KEY_CHECK_AAD)
+}
+
+fn changeset_aad(id: u64) -> Vec<u8> {
+    [CHANGESET_AAD, &id.to_be_bytes()].concat()
+}
+
+/// Encrypt with a random nonce, returning the nonce, the ciphertext and the tag.
+fn encrypt(key: &[u8; KEY_LENGTH], plaintext: &[u8], aad: &[u8]) -> Vec<u8> {
+    let mut nonce = [0u8; NONCE_LENGTH];
+    rand::thread_rng().fill(&mut nonce);
+    let mut ciphertext = plaintext.to_vec();
+    let tag = ChaCha20Poly1305::new(Key::new(*key), Nonce::new(nonce))
+        .encrypt(&mut ciphertext, Some(aad));
+    [&nonce[..], &ciphertext, &tag].concat()
+}
+
+fn decrypt(key: &[u8; KEY_LENGTH], data: &[u8], aad: &[u8]) -> Result<Vec<u8>, PersistenceError> {
+    if data.len() < NONCE_LENGTH + TAG_LENGTH {
+        return Err(PersistenceError::Reason {
+            error_message: "encrypted data is too short".to_string(),
+        });
+    }
+    let (nonce, ciphertext) = data.split_at(NONCE_LENGTH);
+    let (ciphertext, tag) = ciphertext.split_at(ciphertext.len() - TAG_LENGTH);
+    let mut nonce_bytes = [0u8; NONCE_LENGTH];
+    nonce_bytes.copy_from_slice(nonce);
+    let mut tag_bytes = [0u8; TAG_LENGTH];
+    tag_bytes.copy_from_slice(tag);
+    let mut plaintext = ciphertext.to_vec();
+    ChaCha20Poly1305::new(Key::new(*key), Nonce::new(nonce_bytes))
+        .decrypt(&mut plaintext, tag_bytes, Some(aad))
+        .map_err(|_| PersistenceError::WrongKey)?;
+    Ok(plaintext)
+}
@@ -0,0 +1,409 @@
+use crate::bitcoin::{FeeRate, NetworkKind, OutPoint, Psbt, Script, Transaction, TxOut};
+use crate::descriptor::Descriptor;
+use crate::electrum::ElectrumClient;
+use crate::error::{ElectrumError, EsploraError, SweepError};
+use crate::esplora::EsploraClient;
+use crate::secret::{Secret, Wiped};
+use crate::signer::SignersContainer;
+use crate::tx_builder::TxBuilder;
+use crate::types::KeychainKind;
+use crate::wallet::Wallet;
+
+use aes::cipher::generic_array::GenericArray;
+use aes::cipher::{BlockDecrypt, KeyInit};
+use aes::Aes256;
+use bdk_electrum::electrum_client::ElectrumApi;
+use bdk_wallet::bitcoin::base58;
+use bdk_wallet::bitcoin::hashes::{sha256d, Hash};
+use bdk_wallet::bitcoin::key::Secp256k1;
+use bdk_wallet::bitcoin::psbt::Input as BdkInput;
+use bdk_wallet::bitcoin::secp256k1::{PublicKey as Secp256k1PublicKey, Scalar, SecretKey};
+use bdk_wallet::bitcoin::{
+    Address as BdkAddress, CompressedPublicKey, Network, PrivateKey as BdkPrivateKey,
+    PublicKey as BdkPublicKey, ScriptBuf as BdkScriptBuf,
+};
+use bdk_wallet::miniscript::psbt::PsbtExt;
+use scrypt::{scrypt, Params};
+use unicode_normalization::UnicodeNormalization;
+use zeroize::Zeroizing;
+
+use std::sync::Arc;
+
+/// A single private key holding funds to be moved into a wallet, such as one printed on a paper
+/// wallet.
+///
+/// The key may have received funds on any of the standard single-key script types: P2PKH, and for
+/// compressed keys also P2WPKH and P2SH-P2WPKH.
+#[derive(uniffi::Object)]
+pub struct SweepKey(Secret<BdkPrivateKey>);
+
+#[uniffi::export]
+impl SweepKey {
+    /// Parse a private key in Wallet Import Format.
+    #[uniffi::constructor]
+    pub fn from_wif(wif: String) -> Result<Self, SweepError> {
+        BdkPrivateKey::from_wif(&wif)
+            .map(|key| SweepKey(key.into()))
+            .map_err(|e| SweepError::InvalidWif {
+                error_message: e.to_string(),
+            })
+    }
+
+    /// Decrypt a BIP38 encrypted private key with its passphrase. Both the plain and the
+    /// EC-multiplied encryption modes are supported.
+    ///
+    /// BIP38 keys do not encode a network, so it must be given.
+    #[uniffi::constructor]
+    pub fn from_bip38(
+        encrypted_key: String,
+        passphrase: String,
+        network_kind: NetworkKind,
+    ) -> Result<Self, SweepError> {
+        decrypt_bip38(&encrypted_key, &passphrase, network_kind).map(|key| SweepKey(key.into()))
+    }
+
+    /// The scripts the key could have received funds on, or none if the key has been wiped.
+    pub fn scripts(&self) -> Vec<Arc<Script>> {
+        self.script_kinds()
+            .unwrap_or_default()
+            .into_iter()
+            .map(|(_, script)| Arc::new(Script(script)))
+            .collect()
+    }
+
+    /// Look up the unspent outputs sent to the key using an Esplora server.
+    pub fn fetch_utxos_esplora(
+        &self,
+        client: &EsploraClient,
+    ) -> Result<Vec<SweepUtxo>, SweepError> {
+        let esplora_error = |error| SweepError::Esplora {
+            error_message: EsploraError::from(error).to_string(),
+        };
+        let mut utxos = Vec::new();
+        for (_, script) in self.script_kinds()? {
+            for utxo in client
+                .0
+                .get_scripthash_utxos(&script)
+                .map_err(esplora_error)?
+            {
+                let tx = client.0.get_tx_no_opt(&utxo.txid).map_err(esplora_error)?;
+                utxos.push(SweepUtxo::new(tx.into(), utxo.vout)?);
+            }
+        }
+        Ok(utxos)
+    }
+
+    /// Look up the unspent outputs sent to the key using an Electrum server.
+    pub fn fetch_utxos_electrum(
+        &self,
+        client: &ElectrumClient,
+    ) -> Result<Vec<SweepUtxo>, SweepError> {
+        let electrum_error = |error| SweepError::Electrum {
+            error_message: ElectrumError::from(error).to_string(),
+        };
+        let mut utxos = Vec::new();
+        for (_, script) in self.script_kinds()? {
+            for utxo in client
+                .0
+                .inner
+                .script_list_unspent(&script)
+                .map_err(electrum_error)?
+            {
+                let tx = client.0.fetch_tx(utxo.tx_hash).map_err(electrum_error)?;
+                utxos.push(SweepUtxo::new(tx.as_ref().into(), utxo.tx_pos as u32)?);
+            }
+        }
+        Ok(utxos)
+    }
+
+    /// Build and sign a transaction spending all the given outputs to the next unused external
+    /// address of the wallet. The outputs are added as foreign utxos, so none of the wallet's own
+    /// coins are spent.
+    ///
+    /// The returned PSBT is finalized and ready to be extracted and broadcast.
+    pub fn sweep(
+        &self,
+        wallet: &Arc<Wallet>,
+        utxos: Vec<SweepUtxo>,
+        fee_rate: Arc<FeeRate>,
+    ) -> Result<Arc<Psbt>, SweepError> {
+        if utxos.is_empty() {
+            return Err(SweepError::NoUtxos);
+        }
+
+        let script_kinds = self.script_kinds()?;
+        let mut used_kinds = Vec::new();
+        let mut tx_builder = Arc::new(TxBuilder::new());
+        for utxo in utxos {
+            let kind = script_kinds
+                .iter()
+                .find(|(_, script)| *script == utxo.txout.script_pubkey.0)
+                .map(|(kind, _)| *kind)
+                .ok_or_else(|| SweepError::UnknownScript {
+                    outpoint: format!("{}:{}", utxo.outpoint.txid.0, utxo.outpoint.vout),
+                })?;
+            let descriptor = self.descriptor(kind)?;
+            let satisfaction_weight = descriptor
+                .max_weight_to_satisfy()
+                .expect("single key descriptors are always satisfiable");
+
+            let input = BdkInput {
+                non_witness_utxo: Some(utxo.tx.as_ref().into()),
+                witness_utxo: match kind {
+                    ScriptKind::Pkh => None,
+                    ScriptKind::Wpkh | ScriptKind::ShWpkh => Some(utxo.txout.clone().into()),
+                },
+                redeem_script: match kind {
+                    ScriptKind::ShWpkh => Some(self.p2wpkh_script()?),
+                    ScriptKind::Pkh | ScriptKind::Wpkh => None,
+                },
+                ..Default::default()
+            };
+            tx_builder =
+                tx_builder.add_foreign_utxo(utxo.outpoint, (&input).into(), satisfaction_weight)?;
+            if !used_kinds.contains(&kind) {
+                used_kinds.push(kind);
+            }
+        }
+
+        let drain_script = wallet
+            .next_unused_address(KeychainKind::External)
+            .address
+            .script_pubkey();
+        let psbt = tx_builder
+            .manually_selected_only()
+            .drain_to(&drain_script)
+            .fee_rate(&fee_rate)
+            .finish(wallet)?;
+
+        let signers = used_kinds
+            .into_iter()
+            .map(|kind| {
+                Ok(Arc::new(SignersContainer::from_descriptor(
+                    self.descriptor(kind)?,
+                )))
+            })
+            .collect::<Result<_, Wiped>>()?;
+        wallet.sign_with_signers(Arc::clone(&psbt), signers, None)?;
+        psbt.0
+            .lock()
+            .unwrap()
+            .finalize_mut(&Secp256k1::verification_only())
+            .map_err(|errors| SweepError::Signer {
+                error_message: errors
+                    .iter()
+                    .map(|e| e.to_string())
+                    .collect::<Vec<_>>()
+                    .join(", "),
+            })?;
+
+        Ok(psbt)
+    }
+
+    /// Erase the private key from memory without waiting for the object to be dropped. Afterwards
+    /// `scripts` returns no scripts and the other methods fail with `SweepError::Wiped`.
+    pub fn wipe(&self) {
+        self.0.wipe()
+    }
+}
+
+impl SweepKey {
+    fn public_key(&self) -> Result<BdkPublicKey, Wiped> {
+        Ok(self.0.read()?.public_key(&Secp256k1::signing_only()))
+    }
+
+    fn p2wpkh_script(&self) -> Result<BdkScriptBuf, Wiped> {
+        let public_key = CompressedPublicKey(self.public_key()?.inner);
+        Ok(BdkScriptBuf::new_p2wpkh(&public_key.wpubkey_hash()))
+    }
+
+    fn script_kinds(&self) -> Result<Vec<(ScriptKind, BdkScriptBuf)>, Wiped> {
+        let public_key = self.public_key()?;
+        let mut kinds = vec![(
+            ScriptKind::Pkh,
+            BdkScriptBuf::new_p2pkh(&public_key.pubkey_hash()),
+        )];
+        if public_key.compressed {
+            let p2wpkh = self.p2wpkh_script()?;
+            kinds.push((
+                ScriptKind::ShWpkh,
+                BdkScriptBuf::new_p2sh(&p2wpkh.script_hash()),
+            ));
+            kinds.push((ScriptKind::Wpkh, p2wpkh));
+        }
+        Ok(kinds)
+    }
+
+    fn descriptor(&self, kind: ScriptKind) -> Result<Arc<Descriptor>, Wiped> {
+        let secret_key = self.0.read()?;
+        let wif = Zeroizing::new(secret_key.to_wif());
+        let descriptor = Zeroizing::new(match kind {
+            ScriptKind::Pkh => format!("pkh({})", *wif),
+            ScriptKind::Wpkh => format!("wpkh({})", *wif),
+            ScriptKind::ShWpkh => format!("sh(wpkh({}))", *wif),
+        });
+        Ok(Arc::new(
+            Descriptor::parse(&descriptor, secret_key.network)
+                .expect("descriptor built from a valid private key"),
+        ))
+    }
+}
+
+/// An unspent output that can be spent by a [`SweepKey`].
+#[derive(uniffi::Record)]
+pub struct SweepUtxo {
+    /// The location of the output.
+    pub outpoint: OutPoint,
+    /// The output.
+    pub txout: TxOut,
+    /// The transaction that created the output, needed to sign non-segwit inputs.
+    pub tx: Arc<Transaction>,
+}
+
+impl SweepUtxo {
+    /// Fails if the server returned a transaction without the output it listed as unspent.
+    pub(crate) fn new(tx: Transaction, vout: u32) -> Result<Self, SweepError> {
+        let outpoint = OutPoint {
+            txid: tx.compute_txid(),
+            vout,
+        };
+        let txout =
+            tx.output()
+                .get(vout as usize)
+                .cloned()
+                .ok_or_else(|| SweepError::InvalidUtxo {
+                    outpoint: format!("{}:{}", outpoint.txid.0, outpoint.vout),
+                })?;
+        Ok(SweepUtxo {
+            outpoint,
+            txout,
+            tx: Arc::new(tx),
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ScriptKind {
+    Pkh,
+    Wpkh,
+    ShWpkh,
+}
+
+fn invalid_bip38(error_message: &str) -> SweepError {
+    SweepError::InvalidBip38 {
+        error_message: error_message.to_string(),
+    }
+}
+
+fn aes_decrypt(cipher: &Aes256, block: &[u8], xor: &[u8]) -> [u8; 16] {
+    let mut block = GenericArray::clone_from_slice(block);
+    cipher.decrypt_block(&mut block);
+    let mut decrypted = [0u8; 16];
+    for (i, byte) in decrypted.iter_mut().enumerate() {
+        *byte = block[i] ^ xor[i];
+    }
+    decrypted
+}
+
+fn scrypt_hash(password: &[u8], salt: &[u8], log_n: u8, r: u32, p: u32, output: &mut [u8]) {
+    let params = Params::new(log_n, r, p, output.len()).expect("valid scrypt parameters");
+    scrypt(password, salt, &params, output).expect("valid scrypt output length");
+}
+
+/// Decrypt a key following [BIP38](https://github.com/bitcoin/bips/blob/master/bip-0038.mediawiki).
+fn decrypt_bip38(
+    encrypted_key: &str,
+    passphrase: &str,
+    network_kind: NetworkKind,
+) -> Result<BdkPrivateKey, SweepError> {
+    let data = base58::decode_check(encrypted_key).map_err(|e| SweepError::InvalidBip38 {
+        error_message: e.to_string(),
+    })?;
+    if data.len() != 39 {
+        return Err(invalid_bip38("invalid length"));
+    }
+    let flag = data[2];
+    let compressed = flag & 0x20 != 0;
+    let address_hash = &data[3..7];
+    let passphrase = passphrase.nfc().collect::<String>();
+
+    let secret_key = match (data[0], data[1]) {
+        (0x01, 0x42) => {
+            let mut derived = [0u8; 64];
+            scrypt_hash(passphrase.as_bytes(), address_hash, 14, 8, 8, &mut derived);
+            let (derived_half1, derived_half2) = derived.split_at(32);
+            let cipher = Aes256::new(GenericArray::from_slice(derived_half2));
+
+            let mut secret = [0u8; 32];
+            secret[..16].copy_from_slice(&aes_decrypt(&cipher, &data[7..23], &derived_half1[..16]));
+            secret[16..].copy_from_slice(&aes_decrypt(
+                &cipher,
+                &data[23..39],
+                &derived_half1[16..],
+            ));
+            SecretKey::from_slice(&secret).map_err(|_| SweepError::WrongPassphrase)?
+        }
+        (0x01, 0x43) => {
+            let owner_entropy = &data[7..15];
+            let owner_salt = if flag & 0x04 != 0 {
+                &owner_entropy[..4]
+            } else {
+                owner_entropy
+            };
+            let mut pass_factor = [0u8; 32];
+            scrypt_hash(
+                passphrase.as_bytes(),
+                owner_salt,
+                14,
+                8,
+                8,
+                &mut pass_factor,
+            );
+            if flag & 0x04 != 0 {
+                pass_factor = sha256d::Hash::hash(&[&pass_factor[..], owner_entropy].concat())
+                    .to_byte_array();
+            }
+            let pass_factor =
+                SecretKey::from_slice(&pass_factor).map_err(|_| SweepError::WrongPassphrase)?;
+            let pass_point =
+                Secp256k1PublicKey::from_secret_key(&Secp256k1::signing_only(), &pass_factor);
+
+            let mut derived = [0u8; 64];
+            let salt = [address_hash, owner_entropy].concat();
+            scrypt_hash(&pass_point.serialize(), &salt, 10, 1, 1, &mut derived);
+            let (derived_half1, derived_half2) = derived.split_at(32);
+            let cipher = Aes256::new(GenericArray::from_slice(derived_half2));
+
+            let decrypted_part2 = aes_decrypt(&cipher, &data[23..39], &derived_half1[16..]);
+            let encrypted_part1 = [&data[15..23], &decrypted_part2[..8]].concat();
+            let decrypted_part1 = aes_decrypt(&cipher, &encrypted_part1, &derived_half1[..16]);
+            let seed_b = [&decrypted_part1[..], &decrypted_part2[8..]].concat();
+
+            let factor_b = sha256d::Hash::hash(&seed_b).to_byte_array();
+            let factor_b =
+                Scalar::from_be_bytes(factor_b).map_err(|_| SweepError::WrongPassphrase)?;
+            pass_factor
+                .mul_tweak(&factor_b)
+                .map_err(|_| SweepError::WrongPassphrase)?
+        }
+        _ => return Err(invalid_bip38("unknown prefix")),
+    };
+
+    let private_key = BdkPrivateKey {
+        compressed,
+        network: network_kind,
+        inner: secret_key,
+    };
+    // The address hash is computed from the mainnet P2PKH address of the key regardless of the
+    // network it is meant for.
+    let address = BdkAddress::p2pkh(
+        private_key.public_key(&Secp256k1::signing_only()),
+        Network::Bitcoin,
+    );
+    let checksum = sha256d::Hash::hash(address.to_string().as_bytes());
+    if checksum[..4] != *address_hash {
+        return Err(SweepError::WrongPassphrase);
+    }
+
+    Ok(private_key)
+}
@@ -1,5 +1,7 @@
-use crate::bitcoin::{Address, AddressData, Key, Network, ProprietaryKey, Psbt, Transaction};
-use crate::error::TransactionError;
+use crate::bitcoin::{
+    Address, AddressData, Key, Network, PaymentUri, ProprietaryKey, Psbt, Transaction,
+};
+use crate::error::{PaymentUriError, TransactionError};
 use bdk_electrum::bdk_core::bitcoin::hex::DisplayHex;
 
 #[test]
@@ -623,3 +625,55 @@ fn sample_psbt_taproot() -> Psbt {
     Psbt::new("cHNidP8BAH0CAAAAARblbcPN67JMY1pAsqbkYuqfh+OffiMD1PXBKuohxHUhAAAAAAD9////AkQRAAAAAAAAFgAU1Wm3y8dhQ9k1IXoe7Tu4/Vh4e2wVv/UFAAAAACJRILJL6QjSVc9B74yO2wV9qJ1D2HkxpgKV/LRX3dOOV+uMOAMAAAABASsA4fUFAAAAACJRIMSkYUKwqnaNBsaJxcZ1MKFYDd+ZEmqOaLTAGheYLSWeQRQZmDg8WRPva5p6l4cMrRyqdLSCYC74Gk1Mn1aimc9eDHAZu3+0gymYN/cLd5pvviwpc9YiW6HwxS7yCJ5umnS6QFa6MEJrll8dUVdGve8T2Q7nNfN27yTe0dWHAMEL4AvvpJddyZugvr1WuK5CfNdNvHUfuHsWalE8dXsM2XYvy4UiFcEZmDg8WRPva5p6l4cMrRyqdLSCYC74Gk1Mn1aimc9eDGkgGZg4PFkT72uaepeHDK0cqnS0gmAu+BpNTJ9WopnPXgysIGzdf1E91bpWIz3gwC+dFe5OS1a+SUQsP12wvvnaryY4uiCU7qCfqcnKJ7j6aL4hZr1iSn3Rrt04wcmnQwovyqPzWbpSnMAhFhmYODxZE+9rmnqXhwytHKp0tIJgLvgaTUyfVqKZz14MOQFwGbt/tIMpmDf3C3eab74sKXPWIluh8MUu8giebpp0ur6IapxWAACAAQAAgAAAAIAAAAAAAAAAACEWbN1/UT3VulYjPeDAL50V7k5LVr5JRCw/XbC++dqvJjg5AXAZu3+0gymYN/cLd5pvviwpc9YiW6HwxS7yCJ5umnS6WyNan1YAAIABAACAAAAAgAAAAAAAAAAAIRaU7qCfqcnKJ7j6aL4hZr1iSn3Rrt04wcmnQwovyqPzWTkBcBm7f7SDKZg39wt3mm++LClz1iJbofDFLvIInm6adLqsWpreVgAAgAEAAIAAAACAAAAAAAAAAAABFyAZmDg8WRPva5p6l4cMrRyqdLSCYC74Gk1Mn1aimc9eDAEYIHAZu3+0gymYN/cLd5pvviwpc9YiW6HwxS7yCJ5umnS6AAABBSAPfhtK8HCFezfCA8h1mRW3y5fvmffT2cUetRZ5HNtxRQEGawDAaCAPfhtK8HCFezfCA8h1mRW3y5fvmffT2cUetRZ5HNtxRawgoldONDrkvO54wrBh5Qjl6Be/p9isWgfyCsmznpkz3yC6IEANRlfXX/azlrWcSW9h4l1NL+SJx5Knd2gvMmVTh8vKulKcIQcPfhtK8HCFezfCA8h1mRW3y5fvmffT2cUetRZ5HNtxRTkBpcxekxLSoIeHxll9cboAcz0LEzV6rJUs5LlRnHL/wsW+iGqcVgAAgAEAAIAAAACAAQAAAAAAAAAhB0ANRlfXX/azlrWcSW9h4l1NL+SJx5Knd2gvMmVTh8vKOQGlzF6TEtKgh4fGWX1xugBzPQsTNXqslSzkuVGccv/Cxaxamt5WAACAAQAAgAAAAIABAAAAAAAAACEHoldONDrkvO54wrBh5Qjl6Be/p9isWgfyCsmznpkz3yA5AaXMXpMS0qCHh8ZZfXG6AHM9CxM1eqyVLOS5UZxy/8LFWyNan1YAAIABAACAAAAAgAEAAAAAAAAAAA==".to_string())
         .unwrap()
 }
+
+#[test]
+fn test_payment_uri() {
+    let uri = PaymentUri::new(
+        "BITCOIN:bc1qar0srrr7xfkvy5l643lydnw9re59gtzzwf5mdq?amount=0.0005&label=Luke-Jr&message=Donation%20for%20project%20xyz&foo=bar".to_string(),
+        Network::Bitcoin,
+    )
+    .unwrap();
+    assert_eq!(
+        uri.address().to_string(),
+        "bc1qar0srrr7xfkvy5l643lydnw9re59gtzzwf5mdq"
+    );
+    assert_eq!(uri.amount().unwrap().to_sat(), 50_000);
+    assert_eq!(uri.label().as_deref(), Some("Luke-Jr"));
+    assert_eq!(uri.message().as_deref(), Some("Donation for project xyz"));
+
+    let uri = PaymentUri::new(
+        "bitcoin:bc1qar0srrr7xfkvy5l643lydnw9re59gtzzwf5mdq".to_string(),
+        Network::Bitcoin,
+    )
+    .unwrap();
+    assert!(uri.amount().is_none());
+    assert!(uri.label().is_none());
+
+    let parse = |uri: &str| PaymentUri::new(uri.to_string(), Network::Bitcoin).unwrap_err();
+    assert!(matches!(
+        parse("litecoin:bc1qar0srrr7xfkvy5l643lydnw9re59gtzzwf5mdq"),
+        PaymentUriError::InvalidScheme
+    ));
+    assert!(matches!(
+        parse("bitcoin:tb1qhjys9wxlfykmte7ftryptx975uqgd6kcm6a7z4"),
+        PaymentUriError::InvalidAddress { .. }
+    ));
+    assert!(matches!(
+        parse("bitcoin:bc1qar0srrr7xfkvy5l643lydnw9re59gtzzwf5mdq?amount=1,5"),
+        PaymentUriError::InvalidAmount { .. }
+    ));
+    assert!(matches!(
+        parse("bitcoin:bc1qar0srrr7xfkvy5l643lydnw9re59gtzzwf5mdq?label=%ZZ"),
+        PaymentUriError::InvalidEncoding { .. }
+    ));
+    assert!(matches!(
+        parse("bitcoin:bc1qar0srrr7xfkvy5l643lydnw9re59gtzzwf5mdq?amount=1&amount=2"),
+        PaymentUriError::DuplicateParameter { .. }
+    ));
+    assert!(matches!(
+        parse(
+            "bitcoin:bc1qar0srrr7xfkvy5l643lydnw9re59gtzzwf5mdq?req-somethingyoudontunderstand=50"
+        ),
+        PaymentUriError::UnsupportedRequiredParameter { .. }
+    ));
+}
@@ -1,10 +1,17 @@
 use crate::bitcoin::{Network, NetworkKind};
-use crate::descriptor::Descriptor;
-use crate::error::DescriptorError;
+use crate::descriptor::{Descriptor, MultisigDescriptorBuilder};
+use crate::error::{DescriptorError, DescriptorKeyError};
 use crate::keys::{DerivationPath, DescriptorSecretKey, Mnemonic};
-use crate::types::KeychainKind;
+use crate::signer::SignersContainer;
+use crate::store::Persister;
+use crate::types::{Bip48ScriptType, HashFunction, KeychainKind, PolicyItem, WildcardType};
+use crate::wallet::Wallet;
 
 use assert_matches::assert_matches;
+use bdk_wallet::bitcoin::key::Secp256k1;
+use bdk_wallet::bitcoin::PrivateKey as BdkPrivateKey;
+
+use std::sync::Arc;
 
 fn get_descriptor_secret_key() -> DescriptorSecretKey {
     let mnemonic = Mnemonic::from_string("chaos fabric time speed sponsor all flat solution wisdom trophy crack object robot pave observe combine where aware bench orient secret primary cable detect".to_string()).unwrap();
@@ -184,3 +191,403 @@ fn test_descriptor_derive_address_multipath_error() {
 
     assert_matches!(error, DescriptorError::MultiPath);
 }
+
+fn account_key(path: &str, secret: bool) -> String {
+    let account = get_descriptor_secret_key()
+        .derive(&DerivationPath::new(path.to_string()).unwrap())
+        .unwrap();
+    match secret {
+        true => format!("{}/0/*", account),
+        false => format!("{}/0/*", account.as_public()),
+    }
+}
+
+#[test]
+fn test_inheritance_and_decaying_multisig_templates() {
+    let primary = account_key("m/48h/1h/0h/2h", true);
+    let recovery = account_key("m/48h/1h/1h/2h", false);
+
+    let wsh = Descriptor::new_wsh_inheritance(
+        primary.clone(),
+        recovery.clone(),
+        52_560,
+        NetworkKind::Test,
+    )
+    .unwrap();
+    assert!(wsh.to_string().starts_with("wsh(or_d(pk(["));
+    assert!(wsh.to_string().contains("older(52560)"));
+    assert!(wsh.to_string_with_secret().contains("tprv"));
+    let tr =
+        Descriptor::new_tr_inheritance(primary.clone(), recovery.clone(), 144, NetworkKind::Test)
+            .unwrap();
+    assert!(tr.to_string().starts_with("tr(["));
+    // The templates can be used as wallet descriptors right away.
+    let wallet = Wallet::create_single(
+        Arc::new(tr),
+        Network::Testnet,
+        Arc::new(Persister::new_in_memory().unwrap()),
+        25,
+    )
+    .unwrap();
+    assert_eq!(
+        wallet
+            .spending_paths(KeychainKind::External, 0)
+            .unwrap()
+            .len(),
+        2
+    );
+
+    let keys = vec![
+        account_key("m/48h/1h/0h/2h", false),
+        account_key("m/48h/1h/1h/2h", false),
+        account_key("m/48h/1h/2h/2h", false),
+    ];
+    let decaying = Descriptor::new_wsh_decaying_multisig(
+        3,
+        keys.clone(),
+        vec![4_320, 8_640],
+        NetworkKind::Test,
+    )
+    .unwrap();
+    assert!(decaying.to_string().starts_with("wsh(thresh(3,pk(["));
+    assert!(decaying.to_string().contains("sln:older(8640)"));
+    let decaying_tr =
+        Descriptor::new_tr_decaying_multisig(2, keys.clone(), vec![4_320], NetworkKind::Test)
+            .unwrap();
+    assert!(decaying_tr.to_string().starts_with(
+        "tr(50929b74c1a04954b78b4b6035e97a5e078a5a0f28ec96d547bfee9ace803ac0,thresh(2,"
+    ));
+
+    assert_matches!(
+        Descriptor::new_wsh_decaying_multisig(0, keys.clone(), vec![], NetworkKind::Test),
+        Err(DescriptorError::Policy { .. })
+    );
+    assert_matches!(
+        Descriptor::new_wsh_decaying_multisig(2, keys.clone(), vec![10, 20], NetworkKind::Test),
+        Err(DescriptorError::Policy { .. })
+    );
+    // Reusing a key is rejected by the miniscript sanity checks.
+    assert_matches!(
+        Descriptor::new_wsh_inheritance(recovery.clone(), recovery, 144, NetworkKind::Test),
+        Err(DescriptorError::Miniscript { .. })
+    );
+}
+
+#[test]
+fn test_templates_reject_key_expressions_and_invalid_delays() {
+    let primary = account_key("m/48h/1h/0h/2h", false);
+    let recovery = account_key("m/48h/1h/1h/2h", false);
+
+    // A "key" closing the fragment would otherwise add a spending condition.
+    let injected = format!("{primary}),pk({recovery}");
+    assert_matches!(
+        Descriptor::new_wsh_inheritance(injected.clone(), recovery.clone(), 144, NetworkKind::Test),
+        Err(DescriptorError::Key { .. })
+    );
+    assert_matches!(
+        Descriptor::new_tr_inheritance(primary.clone(), injected.clone(), 144, NetworkKind::Test),
+        Err(DescriptorError::Key { .. })
+    );
+    assert_matches!(
+        Descriptor::new_tr_decaying_multisig(
+            1,
+            vec![primary.clone(), injected],
+            vec![],
+            NetworkKind::Test
+        ),
+        Err(DescriptorError::Key { .. })
+    );
+
+    for delay in [0, 65_536] {
+        assert_matches!(
+            Descriptor::new_wsh_inheritance(
+                primary.clone(),
+                recovery.clone(),
+                delay,
+                NetworkKind::Test
+            ),
+            Err(DescriptorError::InvalidRelativeTimelock { delay: d }) if d == delay
+        );
+        assert_matches!(
+            Descriptor::new_wsh_decaying_multisig(
+                2,
+                vec![primary.clone(), recovery.clone()],
+                vec![delay],
+                NetworkKind::Test
+            ),
+            Err(DescriptorError::InvalidRelativeTimelock { .. })
+        );
+    }
+    assert!(Descriptor::new_tr_inheritance(primary, recovery, 65_535, NetworkKind::Test).is_ok());
+}
+
+#[test]
+fn test_policy_compiler() {
+    let primary = account_key("m/48h/1h/0h/2h", true);
+    let recovery = account_key("m/48h/1h/1h/2h", false);
+    let policy = format!("or(99@pk({primary}),1@and(pk({recovery}),older(1000)))");
+
+    let wsh = Descriptor::compile_wsh_policy(policy.clone(), NetworkKind::Test).unwrap();
+    assert!(wsh.to_string().starts_with("wsh("));
+    assert!(wsh.to_string().contains("older(1000)"));
+    assert!(wsh.to_string_with_secret().contains("tprv"));
+    let wallet = Wallet::create_single(
+        Arc::new(wsh),
+        Network::Testnet,
+        Arc::new(Persister::new_in_memory().unwrap()),
+        25,
+    )
+    .unwrap();
+    assert_eq!(
+        wallet
+            .spending_paths(KeychainKind::External, 0)
+            .unwrap()
+            .len(),
+        2
+    );
+
+    // The most likely key becomes the taproot internal key.
+    let tr = Descriptor::compile_tr_policy(policy, NetworkKind::Test, None).unwrap();
+    assert!(tr.to_string().starts_with("tr([d1d04177/48'/1'/0'/2']"));
+    let multisig = format!("and(pk({primary}),pk({recovery}))");
+    let tr = Descriptor::compile_tr_policy(multisig, NetworkKind::Test, None).unwrap();
+    assert!(tr
+        .to_string()
+        .starts_with("tr(50929b74c1a04954b78b4b6035e97a5e078a5a0f28ec96d547bfee9ace803ac0,"));
+
+    assert_matches!(
+        Descriptor::compile_wsh_policy("or(pk(".to_string(), NetworkKind::Test),
+        Err(DescriptorError::Policy { .. })
+    );
+    // A policy spendable without any signature cannot be compiled safely.
+    assert_matches!(
+        Descriptor::compile_wsh_policy("older(1000)".to_string(), NetworkKind::Test),
+        Err(DescriptorError::Miniscript { error_message }) if error_message.starts_with("compiler error")
+    );
+}
+
+#[test]
+fn test_descriptor_analysis() {
+    let primary = account_key("m/48h/1h/0h/2h", false);
+    let recovery = account_key("m/48h/1h/1h/2h", false);
+    let inheritance =
+        Descriptor::new_wsh_inheritance(primary, recovery, 144, NetworkKind::Test).unwrap();
+    let analysis = inheritance.analyze().unwrap();
+
+    assert_eq!(analysis.keys.len(), 2);
+    let key = &analysis.keys[0];
+    assert_eq!(key.master_fingerprint, "d1d04177");
+    assert_eq!(
+        key.origin_path.as_ref().unwrap().to_string(),
+        "48'/1'/0'/2'"
+    );
+    assert_eq!(key.derivation_paths[0].to_string(), "0");
+    assert!(key.wildcard == Some(WildcardType::Unhardened));
+    assert_eq!(analysis.relative_timelocks, vec![144]);
+    assert!(analysis.absolute_timelocks.is_empty());
+    assert!(analysis.taproot_internal_key.is_none());
+
+    // thresh(1, pk(primary), thresh(2, pk(recovery), older(144)))
+    assert_eq!(analysis.policy.len(), 2);
+    assert_eq!(analysis.policy[0].threshold, 1);
+    assert_matches!(analysis.policy[0].items[0], PolicyItem::Key { .. });
+    assert_matches!(
+        analysis.policy[0].items[1],
+        PolicyItem::Threshold { index: 1 }
+    );
+    assert_eq!(analysis.policy[1].threshold, 2);
+    assert_matches!(
+        analysis.policy[1].items[1],
+        PolicyItem::RelativeTimelock { sequence: 144 }
+    );
+
+    let spending_paths = analysis.spending_paths.unwrap();
+    assert_eq!(spending_paths.len(), 2);
+    let primary_path = &spending_paths[0];
+    let recovery_path = &spending_paths[1];
+    assert!(primary_path.relative_timelock.is_none());
+    assert_eq!(recovery_path.relative_timelock, Some(144));
+    assert!(primary_path.satisfaction_weight < recovery_path.satisfaction_weight);
+
+    let keys = vec![
+        account_key("m/48h/1h/0h/2h", false),
+        account_key("m/48h/1h/1h/2h", false),
+        account_key("m/48h/1h/2h/2h", false),
+    ];
+    let decaying =
+        Descriptor::new_tr_decaying_multisig(2, keys, vec![4_320], NetworkKind::Test).unwrap();
+    let analysis = decaying.analyze().unwrap();
+    assert_eq!(
+        analysis.taproot_internal_key.as_deref(),
+        Some("50929b74c1a04954b78b4b6035e97a5e078a5a0f28ec96d547bfee9ace803ac0")
+    );
+    assert_eq!(analysis.taproot_leaves.len(), 1);
+    assert_eq!(analysis.taproot_leaves[0].depth, 0);
+    // Any two of the three keys and the timelock.
+    assert_eq!(analysis.spending_paths.unwrap().len(), 6);
+
+    let hashlocked = Descriptor::new(
+        format!(
+            "wsh(and_v(v:pk({}),sha256({})))",
+            account_key("m/48h/1h/0h/2h", false),
+            "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855"
+        ),
+        NetworkKind::Test,
+    )
+    .unwrap();
+    let analysis = hashlocked.analyze().unwrap();
+    assert_eq!(analysis.hashlocks.len(), 1);
+    assert_matches!(analysis.hashlocks[0].function, HashFunction::Sha256);
+    let spending_paths = analysis.spending_paths.unwrap();
+    assert_eq!(spending_paths.len(), 1);
+    assert_eq!(spending_paths[0].hashlocks.len(), 1);
+    assert_eq!(spending_paths[0].keys.len(), 1);
+
+    // Keys behind a hardened wildcard cannot be derived, so only the spending paths are left out.
+    let account = get_descriptor_secret_key()
+        .derive(&DerivationPath::new("m/84h/1h/0h".to_string()).unwrap())
+        .unwrap();
+    let hardened = Descriptor::new(format!("wpkh({account}/0/*h)"), NetworkKind::Test).unwrap();
+    let analysis = hardened.analyze().unwrap();
+    assert_eq!(analysis.keys.len(), 1);
+    assert!(analysis.spending_paths.is_none());
+}
+
+#[test]
+fn test_bip48_multisig_builder() {
+    let master = get_descriptor_secret_key();
+    let account = master
+        .derive_bip48_account(Bip48ScriptType::P2wsh, 0, NetworkKind::Test)
+        .unwrap();
+    let own = account.as_public().to_cosigner().unwrap();
+    assert_eq!(own.master_fingerprint, "d1d04177");
+    assert_eq!(own.derivation_path.to_string(), "48'/1'/0'/2'");
+    assert!(own.xpub.starts_with("tpub"));
+
+    let cosigners = [[1u8; 16], [2u8; 16]].map(|entropy| {
+        DescriptorSecretKey::new(
+            NetworkKind::Test,
+            &Mnemonic::from_entropy(entropy.to_vec()).unwrap(),
+            None,
+        )
+        .derive_bip48_account(Bip48ScriptType::P2wsh, 0, NetworkKind::Test)
+        .unwrap()
+        .as_public()
+        .to_cosigner()
+        .unwrap()
+    });
+    let builder = MultisigDescriptorBuilder::new(2, Bip48ScriptType::P2wsh)
+        .add_signer(&account)
+        .unwrap()
+        .add_cosigner(cosigners[0].clone())
+        .add_cosigner(cosigners[1].clone());
+
+    let external = builder
+        .build(KeychainKind::External, NetworkKind::Test)
+        .unwrap();
+    let internal = builder
+        .build(KeychainKind::Internal, NetworkKind::Test)
+        .unwrap();
+    assert!(external.to_string().starts_with("wsh(sortedmulti(2,"));
+    assert!(external.to_string().contains("[d1d04177/48'/1'/0'/2']tpub"));
+    assert!(internal.to_string().contains("/1/*"));
+    assert_eq!(external.to_string_with_secret().matches("tprv").count(), 1);
+    let wallet = Wallet::new(
+        external,
+        internal,
+        Network::Testnet,
+        Arc::new(Persister::new_in_memory().unwrap()),
+        25,
+    )
+    .unwrap();
+    assert!(wallet
+        .reveal_next_address(KeychainKind::External)
+        .address
+        .to_string()
+        .starts_with("tb1q"));
+
+    let nested = MultisigDescriptorBuilder::new(2, Bip48ScriptType::P2shP2wsh)
+        .add_cosigner(cosigners[0].clone())
+        .add_cosigner(cosigners[1].clone())
+        .build(KeychainKind::External, NetworkKind::Test);
+    assert_matches!(nested, Err(DescriptorError::InvalidHdKeyPath));
+    assert_matches!(
+        account
+            .extend(&DerivationPath::new("m/0".to_string()).unwrap())
+            .unwrap()
+            .as_public()
+            .to_cosigner(),
+        Err(DescriptorKeyError::InvalidKeyType)
+    );
+}
+
+#[test]
+fn test_descriptor_wipe_and_redaction() {
+    let descriptor = Arc::new(Descriptor::new_bip84(
+        &get_descriptor_secret_key(),
+        KeychainKind::External,
+        NetworkKind::Test,
+    ));
+    assert!(descriptor.to_string_with_secret().contains("tprv"));
+    assert!(!format!("{descriptor:?}").contains("tprv"));
+    let signers = SignersContainer::from_descriptor(Arc::clone(&descriptor));
+    assert_eq!(signers.len(), 1);
+    assert!(!format!("{signers:?}").contains("tprv"));
+
+    let public = descriptor.to_string();
+    descriptor.wipe();
+    assert_eq!(descriptor.to_string_with_secret(), public);
+    assert!(SignersContainer::from_descriptor(descriptor).is_empty());
+}
+
+#[test]
+fn test_too_many_spending_paths() {
+    let secp = Secp256k1::new();
+    let keys = (1..=20u8)
+        .map(|i| {
+            BdkPrivateKey::from_slice(&[i; 32], NetworkKind::Test)
+                .unwrap()
+                .public_key(&secp)
+                .to_string()
+        })
+        .collect::<Vec<_>>();
+    // 10 of 20 keys can be chosen in 184756 ways.
+    let thresh = |k: usize| {
+        let items = keys
+            .iter()
+            .enumerate()
+            .map(|(i, key)| {
+                if i == 0 {
+                    format!("pk({key})")
+                } else {
+                    format!("s:pk({key})")
+                }
+            })
+            .collect::<Vec<_>>();
+        Arc::new(
+            Descriptor::new(
+                format!("wsh(thresh({k},{}))", items.join(",")),
+                NetworkKind::Test,
+            )
+            .unwrap(),
+        )
+    };
+    let external = thresh(10);
+    assert_matches!(
+        external.analyze().err(),
+        Some(DescriptorError::TooManySpendingPaths { max: 1000 })
+    );
+
+    let wallet = Wallet::new(
+        external,
+        thresh(11),
+        Network::Testnet,
+        Arc::new(Persister::new_in_memory().unwrap()),
+        25,
+    )
+    .unwrap();
+    assert_matches!(
+        wallet.spending_paths(KeychainKind::External, 0),
+        Err(DescriptorError::TooManySpendingPaths { max: 1000 })
+    );
+}
@@ -0,0 +1,137 @@
+use crate::electrum::ElectrumPool;
+use crate::error::ElectrumError;
+use crate::tests::wallet::wallet_with_confirmed_utxos;
+
+use assert_matches::assert_matches;
+use bdk_wallet::serde_json;
+
+use std::io::{BufRead, BufReader, Write};
+use std::net::TcpListener;
+use std::thread;
+
+// Nothing listens on these ports, so connecting fails right away.
+const UNREACHABLE_URLS: [&str; 2] = ["tcp://127.0.0.1:1", "tcp://127.0.0.1:2"];
+
+fn unreachable_pool() -> ElectrumPool {
+    ElectrumPool::new(
+        UNREACHABLE_URLS.iter().map(|url| url.to_string()).collect(),
+        None,
+        Some(1),
+        None,
+        true,
+        false,
+    )
+    .unwrap()
+}
+
+#[test]
+fn test_electrum_pool_requires_servers() {
+    assert_matches!(
+        ElectrumPool::new(Vec::new(), None, None, None, true, false).err(),
+        Some(ElectrumError::NoServers)
+    );
+}
+
+#[test]
+fn test_electrum_pool_fails_over_every_server() {
+    let pool = unreachable_pool();
+    assert_eq!(pool.current_url(), UNREACHABLE_URLS[0]);
+
+    let error = pool.estimate_fee(6).unwrap_err();
+    assert_matches!(&error, ElectrumError::AllServersFailed { error_message }
+        if UNREACHABLE_URLS.iter().all(|url| error_message.contains(url)));
+
+    let statuses = pool.health_check();
+    assert_eq!(statuses.len(), 2);
+    for (status, url) in statuses.iter().zip(UNREACHABLE_URLS.iter()) {
+        assert_eq!(status.url, *url);
+        assert!(status.features.is_none());
+        assert!(status.error_message.is_some());
+    }
+}
+
+#[test]
+fn test_electrum_pool_returns_server_errors() {
+    // A server answering every request but the handshake with an error, on a single connection.
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("tcp://{}", listener.local_addr().unwrap());
+    thread::spawn(move || {
+        let (stream, _) = listener.accept().unwrap();
+        let mut writer = stream.try_clone().unwrap();
+        for line in BufReader::new(stream).lines() {
+            let request: serde_json::Value = serde_json::from_str(&line.unwrap()).unwrap();
+            let response = match request["method"].as_str() {
+                Some("server.version") => serde_json::json!({
+                    "jsonrpc": "2.0",
+                    "id": request["id"],
+                    "result": ["mock", "1.4"],
+                }),
+                _ => serde_json::json!({
+                    "jsonrpc": "2.0",
+                    "id": request["id"],
+                    "error": { "code": -1, "message": "unsupported" },
+                }),
+            };
+            writeln!(writer, "{response}").unwrap();
+        }
+    });
+    let pool = ElectrumPool::new(
+        vec![url.clone(), UNREACHABLE_URLS[0].to_string()],
+        None,
+        Some(1),
+        None,
+        true,
+        false,
+    )
+    .unwrap();
+
+    // The error is returned without failing over, and the connection is kept for the next request.
+    for _ in 0..2 {
+        assert_matches!(
+            pool.estimate_fee(6).err(),
+            Some(ElectrumError::Protocol { .. })
+        );
+        assert_eq!(pool.current_url(), url);
+    }
+}
+
+#[test]
+fn test_electrum_pool_retries_interrupted_scan_on_next_server() {
+    // A server answering the handshake and pings, but dropping the connection when scanning.
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("tcp://{}", listener.local_addr().unwrap());
+    thread::spawn(move || {
+        let (stream, _) = listener.accept().unwrap();
+        let mut writer = stream.try_clone().unwrap();
+        for line in BufReader::new(stream).lines() {
+            let request: serde_json::Value = serde_json::from_str(&line.unwrap()).unwrap();
+            let result = match request["method"].as_str() {
+                Some("server.version") => serde_json::json!(["mock", "1.4"]),
+                Some("server.ping") => serde_json::Value::Null,
+                _ => break,
+            };
+            let response = serde_json::json!({
+                "jsonrpc": "2.0",
+                "id": request["id"],
+                "result": result,
+            });
+            writeln!(writer, "{response}").unwrap();
+        }
+    });
+    let pool = ElectrumPool::new(
+        vec![url.clone(), UNREACHABLE_URLS[0].to_string()],
+        None,
+        Some(1),
+        None,
+        true,
+        false,
+    )
+    .unwrap();
+    let wallet = wallet_with_confirmed_utxos(&[10_000]);
+
+    // The scan is started again on the other server rather than failing with the dropped one.
+    assert_matches!(pool.full_scan(&wallet, 20, 10, false).err(),
+        Some(ElectrumError::AllServersFailed { error_message })
+            if error_message.contains(&url) && error_message.contains(UNREACHABLE_URLS[0]));
+    assert_eq!(pool.current_url(), UNREACHABLE_URLS[0]);
+}
@@ -276,7 +276,22 @@ fn test_error_electrum_client() {
                 error_message: "message".to_string(),
             },
             "message",
-        )
+        ),
+        (ElectrumError::NoServers, "no electrum servers were given"),
+        (
+            ElectrumError::AllServersFailed {
+                error_message: "message".to_string(),
+            },
+            "all electrum servers failed: message",
+        ),
+        (
+            ElectrumError::HeaderMismatch {
+                height: 800000,
+                url: "tcp://a:50001".to_string(),
+                other_url: "tcp://b:50001".to_string(),
+            },
+            "tcp://a:50001 and tcp://b:50001 disagree on the block at height 800000",
+        ),
     ];
 
     for (error, expected_message) in cases {
@@ -1,8 +1,13 @@
-use crate::bitcoin::NetworkKind;
-use crate::error::DescriptorKeyError;
-use crate::keys::{DerivationPath, DescriptorPublicKey, DescriptorSecretKey, Mnemonic};
-use crate::types::WildcardType;
+use crate::bitcoin::{Network, NetworkKind};
+use crate::descriptor::Descriptor;
+use crate::error::{Bip39Error, Bip85Error, DescriptorError, DescriptorKeyError, Slip39Error};
+use crate::keys::{
+    DerivationPath, DescriptorPublicKey, DescriptorSecretKey, Mnemonic, Slip132Key, Wordlist,
+};
+use crate::slip39::{self, Slip39Group};
+use crate::types::{KeychainKind, Slip132ScriptType, WildcardType};
 use bdk_wallet::bitcoin::PrivateKey as BdkPrivateKey;
+use bdk_wallet::keys::bip39::{Language, WordCount};
 use std::sync::Arc;
 
 fn get_inner() -> DescriptorSecretKey {
@@ -215,3 +220,420 @@ fn test_add_wildcard() {
         Err(DescriptorKeyError::CannotChangeWildcardType)
     ));
 }
+
+#[test]
+fn test_slip132_keys() {
+    // BIP-84 test vector.
+    let zpub = "zpub6rFR7y4Q2AijBEqTUquhVz398htDFrtymD9xYYfG1m4wAcvPhXNfE3EfH1r1ADqtfSdVCToUG868RvUUkgDKf31mGDtKsAYz2oz2AGutZYs";
+    let mnemonic = Mnemonic::from_string("abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about".to_string()).unwrap();
+    let master = DescriptorSecretKey::new(NetworkKind::Main, &mnemonic, None);
+    let account = derive_dsk(&master, "m/84h/0h/0h").unwrap().as_public();
+    assert_eq!(account.to_slip132(Slip132ScriptType::P2wpkh).unwrap(), zpub);
+
+    let key = Slip132Key::new(zpub.to_string()).unwrap();
+    assert_eq!(key.script_type(), Slip132ScriptType::P2wpkh);
+    assert_eq!(key.network_kind(), NetworkKind::Main);
+    let xpub = key.descriptor_public_key().to_string();
+    assert!(xpub.starts_with("xpub"));
+    assert!(account.to_string().ends_with(&xpub));
+    assert_eq!(
+        key.descriptor_public_key()
+            .to_slip132(Slip132ScriptType::P2wpkh)
+            .unwrap(),
+        zpub
+    );
+
+    let descriptor = key.descriptor(KeychainKind::External).unwrap();
+    assert!(descriptor.to_string().starts_with("wpkh(xpub"));
+    assert_eq!(
+        descriptor
+            .derive_address(0, Network::Bitcoin)
+            .unwrap()
+            .to_string(),
+        "bc1qcr8te4kr609gcawutmrza0j4xv80jy8z306fyu"
+    );
+
+    // Multisig versions convert but do not make a single-sig descriptor.
+    let testnet_account = derive_dsk(&get_inner(), "m/48h/1h/0h/2h")
+        .unwrap()
+        .as_public();
+    let vpub_multisig = testnet_account
+        .to_slip132(Slip132ScriptType::P2wsh)
+        .unwrap();
+    assert!(vpub_multisig.starts_with("Vpub"));
+    let key = Slip132Key::new(vpub_multisig).unwrap();
+    assert_eq!(key.script_type(), Slip132ScriptType::P2wsh);
+    assert_eq!(key.network_kind(), NetworkKind::Test);
+    assert!(matches!(
+        key.descriptor(KeychainKind::External),
+        Err(DescriptorError::Key { .. })
+    ));
+
+    assert!(matches!(
+        Slip132Key::new("not a key".to_string()),
+        Err(DescriptorKeyError::Parse { .. })
+    ));
+}
+
+#[test]
+fn test_mnemonic_languages() {
+    let spanish = Mnemonic::from_entropy_in(vec![0; 16], Language::Spanish).unwrap();
+    assert_eq!(spanish.language(), Language::Spanish);
+    let phrase = spanish.to_string();
+    assert!(phrase.starts_with(&Wordlist::new(Language::Spanish).words()[0]));
+    assert_eq!(
+        Mnemonic::from_string(phrase.clone()).unwrap().language(),
+        Language::Spanish
+    );
+    assert!(matches!(
+        Mnemonic::from_string_in(phrase, Language::English),
+        Err(Bip39Error::UnknownWord { index: 0 })
+    ));
+
+    let japanese = Mnemonic::new_in(WordCount::Words24, Language::Japanese);
+    assert_eq!(japanese.language(), Language::Japanese);
+    assert_eq!(
+        Mnemonic::from_string(japanese.to_string())
+            .unwrap()
+            .to_string(),
+        japanese.to_string()
+    );
+}
+
+#[test]
+fn test_wordlist_assistance() {
+    let english = Wordlist::new(Language::English);
+    assert_eq!(english.words().len(), 2048);
+    assert_eq!(english.suggestions("aban".to_string()), vec!["abandon"]);
+    assert!(english
+        .suggestions("ab".to_string())
+        .contains(&"about".to_string()));
+    assert!(english.suggestions("xyz".to_string()).is_empty());
+    assert!(english.is_valid_word(" Abandon".to_string()));
+    assert!(!english.is_valid_word("abandonx".to_string()));
+    // Input composed differently than the wordlist is still recognized.
+    assert!(Wordlist::new(Language::Spanish).is_valid_word("\u{e1}baco".to_string()));
+
+    let final_words = english
+        .final_words(vec!["abandon".to_string(); 11])
+        .unwrap();
+    // 12 words carry 4 checksum bits, leaving 2^7 candidates for the last word.
+    assert_eq!(final_words.len(), 128);
+    assert!(final_words.contains(&"about".to_string()));
+    let final_words = english
+        .final_words(vec!["abandon".to_string(); 23])
+        .unwrap();
+    assert_eq!(final_words.len(), 8);
+    assert!(final_words.contains(&"art".to_string()));
+
+    assert!(matches!(
+        english.final_words(vec!["abandon".to_string(); 10]),
+        Err(Bip39Error::BadWordCount { word_count: 11 })
+    ));
+    let mut words = vec!["abandon".to_string(); 11];
+    words[3] = "notaword".to_string();
+    assert!(matches!(
+        english.final_words(words),
+        Err(Bip39Error::UnknownWord { index: 3 })
+    ));
+}
+
+#[test]
+fn test_slip39_vector() {
+    // First test vector of SLIP-39, a single share with the passphrase "TREZOR".
+    let share = "duckling enlarge academic academic agency result length solution fridge kidney coal piece deal husband erode duke ajar critical decision keyboard";
+    let recovered = DescriptorSecretKey::from_slip39_shares(
+        NetworkKind::Main,
+        vec![share.to_string()],
+        Some("TREZOR".to_string()),
+    )
+    .unwrap();
+    assert_eq!(
+        recovered.to_string(),
+        "xprv9s21ZrQH143K4QViKpwKCpS2zVbz8GrZgpEchMDg6KME9HZtjfL7iThE9w5muQA4YPHKN1u5VM1w8D4pvnjxa2BmpGMfXr7hnRrRHZ93awZ"
+    );
+
+    // Master secrets that are not valid BIP-39 entropy lengths are valid seeds.
+    let groups = vec![Slip39Group {
+        member_threshold: 1,
+        member_count: 1,
+    }];
+    let shares = slip39::split(&[3u8; 18], 1, &groups, "").unwrap();
+    assert!(
+        DescriptorSecretKey::from_slip39_shares(NetworkKind::Test, shares[0].clone(), None)
+            .unwrap()
+            .to_string()
+            .starts_with("tprv")
+    );
+
+    // Standard shares do not hold a mnemonic.
+    assert!(matches!(
+        Mnemonic::from_slip39_shares(vec![share.to_string()], Some("TREZOR".to_string())),
+        Err(Slip39Error::NotMnemonicShares)
+    ));
+
+    let corrupted = share.replace("keyboard", "kidney");
+    assert!(matches!(
+        Mnemonic::from_slip39_shares(vec![corrupted], Some("TREZOR".to_string())),
+        Err(Slip39Error::InvalidChecksum { index: 0 })
+    ));
+    assert!(matches!(
+        Mnemonic::from_slip39_shares(vec![share.replace("duckling", "duck")], None),
+        Err(Slip39Error::InvalidShare { index: 0, .. })
+    ));
+}
+
+#[test]
+fn test_slip39_shares() {
+    let mnemonic = Mnemonic::new(WordCount::Words24);
+    let groups = vec![
+        Slip39Group {
+            member_threshold: 1,
+            member_count: 1,
+        },
+        Slip39Group {
+            member_threshold: 2,
+            member_count: 3,
+        },
+        Slip39Group {
+            member_threshold: 3,
+            member_count: 5,
+        },
+    ];
+    let passphrase = Some("correct horse".to_string());
+    let shares = mnemonic
+        .to_slip39_shares(2, groups.clone(), passphrase.clone())
+        .unwrap();
+    assert_eq!(shares.len(), 3);
+    assert_eq!(shares[2].len(), 5);
+    // 256 bits of entropy are encoded in 35 words, unlike the 33 words of a standard 256-bit seed.
+    assert_eq!(shares[1][0].split(' ').count(), 35);
+
+    let recover = |shares: Vec<&String>| {
+        Mnemonic::from_slip39_shares(shares.into_iter().cloned().collect(), passphrase.clone())
+    };
+    let recovered = recover(vec![
+        &shares[0][0],
+        &shares[2][4],
+        &shares[2][0],
+        &shares[2][2],
+    ]);
+    assert_eq!(recovered.unwrap().to_string(), mnemonic.to_string());
+    let recovered = recover(vec![
+        &shares[1][2],
+        &shares[1][1],
+        &shares[2][1],
+        &shares[2][3],
+        &shares[2][4],
+    ]);
+    assert_eq!(recovered.unwrap().to_string(), mnemonic.to_string());
+
+    // A wrong passphrase does not recover the mnemonic.
+    let recovered = Mnemonic::from_slip39_shares(
+        vec![
+            shares[0][0].clone(),
+            shares[1][0].clone(),
+            shares[1][1].clone(),
+        ],
+        None,
+    );
+    assert!(!matches!(recovered, Ok(recovered) if recovered.to_string() == mnemonic.to_string()));
+    // Nor is the secret taken for a standard SLIP-39 seed.
+    assert!(matches!(
+        DescriptorSecretKey::from_slip39_shares(
+            NetworkKind::Test,
+            vec![
+                shares[0][0].clone(),
+                shares[1][0].clone(),
+                shares[1][1].clone(),
+            ],
+            passphrase.clone()
+        ),
+        Err(Slip39Error::MnemonicShares)
+    ));
+
+    assert!(matches!(
+        recover(vec![&shares[1][0], &shares[1][1]]),
+        Err(Slip39Error::InsufficientGroups {
+            required: 2,
+            provided: 1
+        })
+    ));
+    assert!(matches!(
+        recover(vec![&shares[0][0], &shares[2][0], &shares[2][1]]),
+        Err(Slip39Error::InsufficientShares {
+            group_index: 2,
+            required: 3,
+            provided: 2
+        })
+    ));
+    assert!(matches!(recover(vec![]), Err(Slip39Error::NoShares)));
+    let other_shares = mnemonic
+        .to_slip39_shares(2, groups, passphrase.clone())
+        .unwrap();
+    assert!(matches!(
+        recover(vec![
+            &shares[0][0],
+            &other_shares[1][0],
+            &other_shares[1][1]
+        ]),
+        Err(Slip39Error::MismatchedShares)
+    ));
+
+    let single = vec![Slip39Group {
+        member_threshold: 1,
+        member_count: 2,
+    }];
+    assert!(matches!(
+        mnemonic.to_slip39_shares(1, single.clone(), None),
+        Err(Slip39Error::InvalidGroups { .. })
+    ));
+    assert!(matches!(
+        mnemonic.to_slip39_shares(2, single, None),
+        Err(Slip39Error::InvalidGroups { .. })
+    ));
+    assert!(matches!(
+        mnemonic.to_slip39_shares(1, vec![], Some("caf\u{e9}".to_string())),
+        Err(Slip39Error::InvalidPassphrase)
+    ));
+}
+
+#[test]
+fn test_slip39_shares_keep_mnemonic_language() {
+    let groups = vec![Slip39Group {
+        member_threshold: 2,
+        member_count: 3,
+    }];
+    for language in [Language::Japanese, Language::Spanish] {
+        let mnemonic = Mnemonic::new_in(WordCount::Words12, language);
+        let shares = mnemonic.to_slip39_shares(1, groups.clone(), None).unwrap();
+        assert_eq!(shares[0][0].split(' ').count(), 22);
+
+        let recovered = Mnemonic::from_slip39_shares(shares[0][1..].to_vec(), None).unwrap();
+        assert_eq!(recovered.language(), language);
+        assert_eq!(recovered.to_string(), mnemonic.to_string());
+    }
+}
+
+#[test]
+fn test_bip85_derivation() {
+    // Test vectors of BIP-85.
+    let master = DescriptorSecretKey::from_string("xprv9s21ZrQH143K2LBWUUQRFXhucrQqBpKdRRxNVq2zBqsx8HVqFk2uYo8kmbaLLHRdqtQpUm98uKfu3vca1LqdGhUtyoFnCNkfmXRyPXLjbKb".to_string()).unwrap();
+    assert_eq!(
+        master
+            .bip85_mnemonic(WordCount::Words12, Language::English, 0)
+            .unwrap()
+            .to_string(),
+        "girl mad pet galaxy egg matter matrix prison refuse sense ordinary nose"
+    );
+    assert_eq!(
+        master
+            .bip85_mnemonic(WordCount::Words18, Language::English, 0)
+            .unwrap()
+            .to_string(),
+        "near account window bike charge season chef number sketch tomorrow excuse sniff circle vital hockey outdoor supply token"
+    );
+    assert_eq!(
+        master
+            .bip85_mnemonic(WordCount::Words24, Language::English, 0)
+            .unwrap()
+            .to_string(),
+        "puppy ocean match cereal symbol another shed magic wrap hammer bulb intact gadget divorce twin tonight reason outdoor destroy simple truth cigar social volcano"
+    );
+    assert_eq!(
+        master.bip85_wif(0).unwrap(),
+        "Kzyv4uF39d4Jrw2W7UryTHwZr1zQVNk4dAFyqE6BuMrMh1Za7uhp"
+    );
+    assert_eq!(
+        master.bip85_xprv(0).unwrap().to_string(),
+        "xprv9s21ZrQH143K2srSbCSg4m4kLvPMzcWydgmKEnMmoZUurYuBuYG46c6P71UGXMzmriLzCCBvKQWBUv3vPB3m1SATMhp3uEjXHJ42jFg7myX"
+    );
+    assert_eq!(
+        master.bip85_hex(64, 0).unwrap(),
+        "492db4698cf3b73a5a24998aa3e9d7fa96275d85724a91e71aa2d645442f878555d078fd1f1f67e368976f04137b1f7a0d19232136ca50c44614af72b5582a5c"
+    );
+
+    let other = master
+        .bip85_mnemonic(WordCount::Words12, Language::Japanese, 1)
+        .unwrap();
+    assert_eq!(other.language(), Language::Japanese);
+    assert!(matches!(
+        master.bip85_mnemonic(WordCount::Words12, Language::Portuguese, 0),
+        Err(Bip85Error::UnsupportedLanguage { .. })
+    ));
+    assert!(matches!(
+        master.bip85_hex(15, 0),
+        Err(Bip85Error::InvalidEntropyLength { length: 15 })
+    ));
+    assert!(matches!(
+        master.bip85_wif(1 << 31),
+        Err(Bip85Error::Bip32 { .. })
+    ));
+    let single = DescriptorSecretKey::from_string(
+        "L2wTu6hQrnDMiFNWA5na6jB12ErGQqtXwqpSL7aWquJaZG8Ai3ch".to_string(),
+    )
+    .unwrap();
+    assert!(matches!(
+        single.bip85_wif(0),
+        Err(Bip85Error::InvalidKeyType)
+    ));
+
+    // Only the master key itself can be used, not a key derived from it.
+    let account = master
+        .derive(&DerivationPath::new("m/84h/0h/0h".to_string()).unwrap())
+        .unwrap();
+    assert!(matches!(
+        account.bip85_xprv(0),
+        Err(Bip85Error::NotMasterKey)
+    ));
+    let with_path = DescriptorSecretKey::from_string(format!("{master}/0")).unwrap();
+    assert!(matches!(
+        with_path.bip85_hex(16, 0),
+        Err(Bip85Error::NotMasterKey)
+    ));
+}
+
+#[test]
+fn test_secret_wipe_and_redaction() {
+    let secret_key = get_inner();
+    let public_key = secret_key.as_public().to_string();
+    let descriptor = Descriptor::new_bip84(&secret_key, KeychainKind::External, NetworkKind::Test);
+    assert!(secret_key.to_string().starts_with("tprv"));
+    assert_eq!(format!("{secret_key:?}"), "DescriptorSecretKey(<redacted>)");
+    secret_key.wipe();
+    assert_eq!(secret_key.to_string(), "<wiped>");
+    assert!(secret_key.secret_bytes().is_empty());
+    assert!(matches!(
+        derive_dsk(&secret_key, "m/0"),
+        Err(DescriptorKeyError::Wiped)
+    ));
+    assert!(matches!(
+        secret_key.bip85_hex(16, 0),
+        Err(Bip85Error::Wiped)
+    ));
+    // The public key and the public templates remain available.
+    assert_eq!(secret_key.as_public().to_string(), public_key);
+    let wiped_descriptor =
+        Descriptor::new_bip84(&secret_key, KeychainKind::External, NetworkKind::Test);
+    assert_eq!(wiped_descriptor.to_string(), descriptor.to_string());
+    assert_eq!(
+        wiped_descriptor.to_string_with_secret(),
+        descriptor.to_string()
+    );
+
+    let mnemonic = Mnemonic::new_in(WordCount::Words12, Language::Spanish);
+    assert_eq!(mnemonic.to_string().split(' ').count(), 12);
+    mnemonic.wipe();
+    assert_eq!(mnemonic.to_string(), "<wiped>");
+    assert_eq!(mnemonic.language(), Language::Spanish);
+    let groups = vec![Slip39Group {
+        member_threshold: 1,
+        member_count: 1,
+    }];
+    assert!(matches!(
+        mnemonic.to_slip39_shares(1, groups, None),
+        Err(Slip39Error::Wiped)
+    ));
+    let wiped_key = DescriptorSecretKey::new(NetworkKind::Test, &mnemonic, None);
+    assert_eq!(wiped_key.to_string(), "<wiped>");
+}
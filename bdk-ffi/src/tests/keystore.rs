@@ -0,0 +1,101 @@
+use crate::bitcoin::NetworkKind;
+use crate::descriptor::Descriptor;
+use crate::error::KeystoreError;
+use crate::keys::{DescriptorSecretKey, Mnemonic};
+use crate::keystore::{Keystore, KeystoreContent};
+
+const MNEMONIC: &str = "chaos fabric time speed sponsor all flat solution wisdom trophy crack object robot pave observe combine where aware bench orient secret primary cable detect";
+
+#[test]
+fn test_keystore_mnemonic() {
+    let mnemonic = Mnemonic::from_string(MNEMONIC.to_string()).unwrap();
+    let keystore = Keystore::encrypt_mnemonic(&mnemonic, "hunter2".to_string()).unwrap();
+    assert_eq!(keystore.content(), KeystoreContent::Mnemonic);
+    let saved = keystore.to_string();
+    assert!(!saved.contains("chaos"));
+
+    let keystore = Keystore::from_string(saved.clone()).unwrap();
+    let decrypted = keystore.decrypt_mnemonic("hunter2".to_string()).unwrap();
+    assert_eq!(decrypted.to_string(), MNEMONIC);
+    assert!(matches!(
+        keystore.decrypt_mnemonic("hunter3".to_string()),
+        Err(KeystoreError::WrongPassword)
+    ));
+    assert!(matches!(
+        keystore.decrypt_secret_key("hunter2".to_string()),
+        Err(KeystoreError::WrongContent { .. })
+    ));
+
+    // The content type is authenticated, relabeling the keystore is detected.
+    let relabeled = Keystore::from_string(saved.replace("\"mnemonic\"", "\"secret_key\"")).unwrap();
+    assert!(matches!(
+        relabeled.decrypt_secret_key("hunter2".to_string()),
+        Err(KeystoreError::WrongPassword)
+    ));
+    assert!(matches!(
+        Keystore::from_string(saved.replace("\"keystore_version\":1", "\"keystore_version\":2")),
+        Err(KeystoreError::UnsupportedVersion { version: 2 })
+    ));
+    assert!(matches!(
+        Keystore::from_string("{}".to_string()),
+        Err(KeystoreError::InvalidKeystore { .. })
+    ));
+}
+
+#[test]
+fn test_keystore_secret_key_and_descriptor() {
+    let mnemonic = Mnemonic::from_string(MNEMONIC.to_string()).unwrap();
+    let secret_key = DescriptorSecretKey::new(NetworkKind::Test, &mnemonic, None);
+    let keystore = Keystore::encrypt_secret_key(&secret_key, "correct horse".to_string()).unwrap();
+    let decrypted = Keystore::from_string(keystore.to_string())
+        .unwrap()
+        .decrypt_secret_key("correct horse".to_string())
+        .unwrap();
+    assert_eq!(decrypted.to_string(), secret_key.to_string());
+
+    let descriptor = Descriptor::new(
+        format!("wpkh({secret_key}/84'/1'/0'/0/*)"),
+        NetworkKind::Test,
+    )
+    .unwrap();
+    let keystore = Keystore::encrypt_descriptor(&descriptor, "correct horse".to_string()).unwrap();
+    assert_eq!(keystore.content(), KeystoreContent::Descriptor);
+    let decrypted = keystore
+        .decrypt_descriptor("correct horse".to_string(), NetworkKind::Test)
+        .unwrap();
+    assert_eq!(
+        decrypted.to_string_with_secret(),
+        descriptor.to_string_with_secret()
+    );
+    assert!(matches!(
+        keystore.decrypt_descriptor("Correct horse".to_string(), NetworkKind::Test),
+        Err(KeystoreError::WrongPassword)
+    ));
+    secret_key.wipe();
+    assert!(matches!(
+        Keystore::encrypt_secret_key(&secret_key, "correct horse".to_string()),
+        Err(KeystoreError::Wiped)
+    ));
+}
+
+#[test]
+fn test_keystore_rejects_descriptor_without_secret_keys() {
+    let mnemonic = Mnemonic::from_string(MNEMONIC.to_string()).unwrap();
+    let secret_key = DescriptorSecretKey::new(NetworkKind::Test, &mnemonic, None);
+    let descriptor = Descriptor::new(
+        format!("wpkh({secret_key}/84'/1'/0'/0/*)"),
+        NetworkKind::Test,
+    )
+    .unwrap();
+    let public = Descriptor::new(descriptor.to_string(), NetworkKind::Test).unwrap();
+    assert!(matches!(
+        Keystore::encrypt_descriptor(&public, "correct horse".to_string()),
+        Err(KeystoreError::NoSecretKeys)
+    ));
+
+    descriptor.wipe();
+    assert!(matches!(
+        Keystore::encrypt_descriptor(&descriptor, "correct horse".to_string()),
+        Err(KeystoreError::NoSecretKeys)
+    ));
+}
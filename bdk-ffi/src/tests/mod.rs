@@ -1,6 +1,11 @@
 mod bitcoin;
 mod descriptor;
+mod electrum;
 mod error;
 mod keys;
+mod keystore;
+mod outbox;
+mod privacy;
+mod sweep;
 mod tx_builder;
 mod wallet;
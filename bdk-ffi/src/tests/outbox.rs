@@ -0,0 +1,132 @@
+use crate::bitcoin::{Amount, Transaction};
+use crate::error::RebroadcastError;
+use crate::esplora::EsploraClient;
+use crate::outbox::{OutboxTxStatus, TxOutbox};
+use crate::tests::wallet::funded_wallet;
+use crate::tx_builder::TxBuilder;
+use crate::types::{EvictedTx, UnconfirmedTx};
+use crate::wallet::Wallet;
+
+use bdk_wallet::bitcoin::Transaction as BdkTransaction;
+use bdk_wallet::KeychainKind;
+
+use std::sync::Arc;
+
+fn signed_tx(wallet: &Arc<Wallet>, amount: u64) -> Arc<Transaction> {
+    let recipient_script = wallet
+        .next_unused_address(KeychainKind::External)
+        .address
+        .script_pubkey();
+    let psbt = TxBuilder::new()
+        .add_recipient(&recipient_script, Arc::new(Amount::from_sat(amount)))
+        .finish(wallet)
+        .unwrap();
+    assert!(wallet.sign(Arc::clone(&psbt), None).unwrap());
+    psbt.extract_tx().unwrap()
+}
+
+fn seen(wallet: &Wallet, tx: &Arc<Transaction>, last_seen: u64) {
+    wallet.apply_unconfirmed_txs(vec![UnconfirmedTx {
+        tx: Arc::clone(tx),
+        last_seen,
+    }]);
+}
+
+#[test]
+fn test_outbox_tracks_added_transaction() {
+    let wallet = Arc::new(funded_wallet());
+    let outbox = TxOutbox::new(Arc::clone(&wallet));
+    assert!(outbox.list().is_empty());
+
+    let tx = signed_tx(&wallet, 10_000);
+    assert!(outbox.add(&tx));
+
+    let entries = outbox.list();
+    assert_eq!(entries.len(), 1);
+    assert_eq!(entries[0].txid.0, tx.compute_txid().0);
+    assert_eq!(entries[0].status, OutboxTxStatus::Unbroadcast);
+    assert_eq!(entries[0].last_seen, None);
+
+    seen(&wallet, &tx, 2);
+    let entries = outbox.list();
+    assert_eq!(entries[0].status, OutboxTxStatus::Pending);
+    assert_eq!(entries[0].last_seen, Some(2));
+
+    // Adding it again does not change it.
+    assert!(outbox.add(&tx));
+    assert_eq!(outbox.list()[0].last_seen, Some(2));
+
+    // A payment to the wallet does not spend from it.
+    let mut incoming = BdkTransaction::from(tx.as_ref());
+    for (vout, input) in incoming.input.iter_mut().enumerate() {
+        input.previous_output.txid = tx.compute_txid().0;
+        input.previous_output.vout = 100 + vout as u32;
+    }
+    assert!(!outbox.add(&incoming.into()));
+    assert_eq!(outbox.list().len(), 1);
+}
+
+#[test]
+fn test_outbox_reports_dropped_and_abandoned_transactions() {
+    let wallet = Arc::new(funded_wallet());
+    let outbox = TxOutbox::new(Arc::clone(&wallet));
+    let tx = signed_tx(&wallet, 10_000);
+    assert!(outbox.add(&tx));
+    seen(&wallet, &tx, 2);
+
+    wallet.apply_evicted_txs(vec![EvictedTx {
+        txid: tx.compute_txid(),
+        evicted_at: 3,
+    }]);
+    let entries = outbox.list();
+    assert_eq!(entries.len(), 1);
+    assert_eq!(entries[0].status, OutboxTxStatus::Dropped);
+    assert_eq!(wallet.balance().total.to_sat(), 76_000);
+
+    assert!(outbox.abandon(tx.compute_txid()));
+    assert!(outbox.list().is_empty());
+    assert!(!outbox.abandon(tx.compute_txid()));
+
+    // An abandoned transaction cannot be added back.
+    assert!(!outbox.add(&tx));
+    assert!(outbox.list().is_empty());
+}
+
+#[test]
+fn test_outbox_keeps_transaction_unbroadcast_when_broadcast_fails() {
+    let wallet = Arc::new(funded_wallet());
+    let outbox = TxOutbox::new(Arc::clone(&wallet));
+    let tx = signed_tx(&wallet, 10_000);
+    assert!(outbox.add(&tx));
+
+    let client = EsploraClient::new("http://127.0.0.1:1".to_string(), None);
+    let results = outbox.rebroadcast_esplora(&client, 2);
+    assert_eq!(results.len(), 1);
+    assert_eq!(results[0].txid.0, tx.compute_txid().0);
+    assert!(matches!(
+        results[0].error,
+        Some(RebroadcastError::Esplora { .. })
+    ));
+
+    let entries = outbox.list();
+    assert_eq!(entries.len(), 1);
+    assert_eq!(entries[0].status, OutboxTxStatus::Unbroadcast);
+    assert_eq!(entries[0].last_seen, None);
+}
+
+#[test]
+fn test_outbox_drops_replaced_transaction() {
+    let wallet = Arc::new(funded_wallet());
+    let outbox = TxOutbox::new(Arc::clone(&wallet));
+    let original = signed_tx(&wallet, 10_000);
+    let replacement = signed_tx(&wallet, 20_000);
+    outbox.add(&original);
+    outbox.add(&replacement);
+    seen(&wallet, &original, 2);
+    seen(&wallet, &replacement, 3);
+
+    let entries = outbox.list();
+    assert_eq!(entries.len(), 1);
+    assert_eq!(entries[0].txid.0, replacement.compute_txid().0);
+    assert!(!outbox.abandon(original.compute_txid()));
+}
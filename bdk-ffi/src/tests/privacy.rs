@@ -0,0 +1,193 @@
+use crate::bitcoin::{Amount, Network, NetworkKind, OutPoint, Script, Transaction, Txid};
+use crate::descriptor::Descriptor;
+use crate::privacy::ScriptType;
+use crate::store::Persister;
+use crate::tests::wallet::wallet_with_confirmed_utxos;
+use crate::tx_builder::TxBuilder;
+use crate::wallet::Wallet;
+
+use bdk_wallet::bitcoin::hashes::Hash;
+use bdk_wallet::bitcoin::Transaction as BdkTransaction;
+use bdk_wallet::bitcoin::{
+    absolute, transaction, Amount as BdkAmount, PubkeyHash, ScriptBuf, TxOut as BdkTxOut,
+    WPubkeyHash,
+};
+use bdk_wallet::KeychainKind;
+
+use std::sync::Arc;
+
+const TAPROOT_INTERNAL_DESCRIPTOR: &str = "tr(tprv8ZgxMBicQKsPf2qfrEygW6fdYseJDDrVnDv26PH5BHdvSuG6ecCbHqLVof9yZcMoM31z9ur3tTYbSnr1WBqbGX97CbXcmp5H6qeMpyvx35B/86h/1h/0h/1/*)";
+const EXTERNAL_DESCRIPTOR: &str = "wpkh(tprv8ZgxMBicQKsPf2qfrEygW6fdYseJDDrVnDv26PH5BHdvSuG6ecCbHqLVof9yZcMoM31z9ur3tTYbSnr1WBqbGX97CbXcmp5H6qeMpyvx35B/84h/1h/1h/0/*)";
+
+/// Receive `value` to `script` in an unconfirmed transaction seen at `last_seen`.
+fn receive(wallet: &Wallet, script: &Script, value: u64, last_seen: u64) -> Txid {
+    let tx = BdkTransaction {
+        version: transaction::Version::TWO,
+        lock_time: absolute::LockTime::from_consensus(last_seen as u32),
+        input: vec![],
+        output: vec![BdkTxOut {
+            value: BdkAmount::from_sat(value),
+            script_pubkey: script.0.clone(),
+        }],
+    };
+    let txid = Txid(tx.compute_txid());
+    wallet
+        .get_wallet_mut()
+        .apply_unconfirmed_txs([(Arc::new(tx), last_seen)]);
+    txid
+}
+
+/// Pay `amount` to `script`, spending exactly `outpoints`, and mark the payment as seen.
+fn pay(
+    wallet: &Arc<Wallet>,
+    script: &Script,
+    amount: u64,
+    outpoints: Vec<OutPoint>,
+) -> Arc<Transaction> {
+    let psbt = TxBuilder::new()
+        .add_recipient(script, Arc::new(Amount::from_sat(amount)))
+        .add_utxos(outpoints)
+        .manually_selected_only()
+        .finish(wallet)
+        .unwrap();
+    assert!(wallet.sign(Arc::clone(&psbt), None).unwrap());
+    let tx = psbt.extract_tx().unwrap();
+    wallet
+        .get_wallet_mut()
+        .apply_unconfirmed_txs([(Arc::new(BdkTransaction::from(tx.as_ref())), 10)]);
+    tx
+}
+
+fn unspent(wallet: &Wallet) -> Vec<OutPoint> {
+    wallet
+        .list_unspent()
+        .into_iter()
+        .map(|utxo| utxo.outpoint)
+        .collect()
+}
+
+fn change_vout(tx: &Transaction, payment: u64) -> u32 {
+    tx.output()
+        .iter()
+        .position(|txout| txout.value.to_sat() != payment)
+        .unwrap() as u32
+}
+
+fn wpkh_script() -> Script {
+    Script(ScriptBuf::new_p2wpkh(&WPubkeyHash::all_zeros()))
+}
+
+#[test]
+fn test_privacy_report_is_empty_for_unused_wallet() {
+    let wallet = wallet_with_confirmed_utxos(&[30_000, 40_000]);
+    let report = wallet.privacy_report();
+    assert!(report.reused_addresses.is_empty());
+    assert!(report.linked_addresses.is_empty());
+    assert!(report.round_number_change.is_empty());
+    assert!(report.script_type_change.is_empty());
+    assert!(report.mixed_script_types.is_empty());
+}
+
+#[test]
+fn test_privacy_report_finds_reused_address() {
+    let wallet = wallet_with_confirmed_utxos(&[30_000, 40_000]);
+    let first_script = wallet
+        .peek_address(KeychainKind::External, 0)
+        .address
+        .script_pubkey();
+    receive(&wallet, &first_script, 5_000, 2);
+
+    let report = wallet.privacy_report();
+    assert_eq!(report.reused_addresses.len(), 1);
+    assert_eq!(report.reused_addresses[0].keychain, KeychainKind::External);
+    assert_eq!(report.reused_addresses[0].index, 0);
+    assert_eq!(report.reused_addresses[0].tx_count, 2);
+}
+
+#[test]
+fn test_privacy_report_finds_utxos_linked_by_common_inputs() {
+    let wallet = Arc::new(wallet_with_confirmed_utxos(&[30_000, 40_000]));
+    let spent = unspent(&wallet);
+    let first_address = wallet.peek_address(KeychainKind::External, 0).address;
+    let remaining_txid = receive(&wallet, &first_address.script_pubkey(), 5_000, 2);
+
+    let payment_tx = pay(&wallet, &wpkh_script(), 50_000, spent);
+
+    let report = wallet.privacy_report();
+    assert_eq!(report.linked_addresses.len(), 1);
+    let linked = &report.linked_addresses[0];
+    assert_eq!(linked.addresses.len(), 2);
+    assert!(linked
+        .addresses
+        .iter()
+        .any(|address| address.0 == first_address.0));
+    assert_eq!(linked.txids.len(), 1);
+    assert_eq!(linked.txids[0].0, payment_tx.compute_txid().0);
+    // The output received again on a linked address is tied to the other one.
+    assert_eq!(linked.utxos.len(), 1);
+    assert_eq!(linked.utxos[0].txid.0, remaining_txid.0);
+}
+
+#[test]
+fn test_privacy_report_finds_round_number_change() {
+    let wallet = Arc::new(wallet_with_confirmed_utxos(&[30_000]));
+    let payment_tx = pay(&wallet, &wpkh_script(), 20_000, unspent(&wallet));
+
+    let report = wallet.privacy_report();
+    assert!(report.script_type_change.is_empty());
+    assert_eq!(report.round_number_change.len(), 1);
+    assert_eq!(
+        report.round_number_change[0].txid.0,
+        payment_tx.compute_txid().0
+    );
+    assert_eq!(
+        report.round_number_change[0].vout,
+        change_vout(&payment_tx, 20_000)
+    );
+}
+
+#[test]
+fn test_privacy_report_finds_script_type_change() {
+    let wallet = Arc::new(wallet_with_confirmed_utxos(&[30_000]));
+    let legacy_script = Script(ScriptBuf::new_p2pkh(&PubkeyHash::all_zeros()));
+    let payment_tx = pay(&wallet, &legacy_script, 12_345, unspent(&wallet));
+
+    let report = wallet.privacy_report();
+    assert!(report.round_number_change.is_empty());
+    assert_eq!(report.script_type_change.len(), 1);
+    assert_eq!(
+        report.script_type_change[0].txid.0,
+        payment_tx.compute_txid().0
+    );
+    assert_eq!(
+        report.script_type_change[0].vout,
+        change_vout(&payment_tx, 12_345)
+    );
+}
+
+#[test]
+fn test_privacy_report_finds_mixed_script_types() {
+    let wallet = Wallet::new(
+        Arc::new(Descriptor::new(EXTERNAL_DESCRIPTOR.to_string(), NetworkKind::Test).unwrap()),
+        Arc::new(
+            Descriptor::new(TAPROOT_INTERNAL_DESCRIPTOR.to_string(), NetworkKind::Test).unwrap(),
+        ),
+        Network::Regtest,
+        Arc::new(Persister::new_in_memory().unwrap()),
+        25,
+    )
+    .unwrap();
+    for (keychain, last_seen) in [(KeychainKind::External, 2), (KeychainKind::Internal, 3)] {
+        let script = wallet.reveal_next_address(keychain).address.script_pubkey();
+        receive(&wallet, &script, 10_000, last_seen);
+    }
+
+    let report = wallet.privacy_report();
+    assert_eq!(report.mixed_script_types.len(), 2);
+    assert_eq!(report.mixed_script_types[0].script_type, ScriptType::P2wpkh);
+    assert_eq!(report.mixed_script_types[1].script_type, ScriptType::P2tr);
+    assert!(report
+        .mixed_script_types
+        .iter()
+        .all(|group| group.utxos.len() == 1));
+}
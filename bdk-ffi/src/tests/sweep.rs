@@ -0,0 +1,185 @@
+use crate::bitcoin::{FeeRate, NetworkKind, Transaction};
+use crate::error::SweepError;
+use crate::sweep::{SweepKey, SweepUtxo};
+use crate::tests::wallet::funded_wallet;
+
+use bdk_wallet::bitcoin::{
+    absolute, transaction, Amount as BdkAmount, PrivateKey as BdkPrivateKey,
+    Transaction as BdkTransaction, TxOut as BdkTxOut,
+};
+
+use std::sync::Arc;
+
+fn sweep_key() -> SweepKey {
+    let wif = BdkPrivateKey::from_slice(&[7u8; 32], NetworkKind::Test)
+        .unwrap()
+        .to_wif();
+    SweepKey::from_wif(wif).unwrap()
+}
+
+fn utxos_for(key: &SweepKey, amount: u64) -> Vec<SweepUtxo> {
+    let funding_tx = BdkTransaction {
+        version: transaction::Version::ONE,
+        lock_time: absolute::LockTime::ZERO,
+        input: vec![],
+        output: key
+            .scripts()
+            .iter()
+            .map(|script| BdkTxOut {
+                value: BdkAmount::from_sat(amount),
+                script_pubkey: script.0.clone(),
+            })
+            .collect(),
+    };
+    let tx = Transaction::from(funding_tx);
+    (0..tx.output().len() as u32)
+        .map(|vout| SweepUtxo::new(tx.clone(), vout).unwrap())
+        .collect()
+}
+
+#[test]
+fn test_bip38_decryption() {
+    let cases = vec![
+        (
+            "6PRVWUbkzzsbcVac2qwfssoUJAN1Xhrg6bNk8J7Nzm5H7kxEbn2Nh2ZoGg",
+            "TestingOneTwoThree",
+            "5KN7MzqK5wt2TP1fQCYyHBtDrXdJuXbUzm4A9rKAteGu3Qi5CVR",
+        ),
+        (
+            "6PYNKZ1EAgYgmQfmNVamxyXVWHzK5s6DGhwP4J5o44cvXdoY7sRzhtpUeo",
+            "TestingOneTwoThree",
+            "L44B5gGEpqEDRS9vVPz7QT35jcBG2r3CZwSwQ4fCewXAhAhqGVpP",
+        ),
+        (
+            "6PfQu77ygVyJLZjfvMLyhLMQbYnu5uguoJJ4kMCLqWwPEdfpwANVS76gTX",
+            "TestingOneTwoThree",
+            "5K4caxezwjGCGfnoPTZ8tMcJBLB7Jvyjv4xxeacadhq8nLisLR2",
+        ),
+        (
+            "6PgNBNNzDkKdhkT6uJntUXwwzQV8Rr2tZcbkDcuC9DZRsS6AtHts4Ypo1j",
+            "MOLON LABE",
+            "5JLdxTtcTHcfYcmJsNVy1v2PMDx432JPoYcBTVVRHpPaxUrdtf8",
+        ),
+    ];
+
+    for (encrypted, passphrase, wif) in cases {
+        let expected = SweepKey::from_wif(wif.to_string()).unwrap();
+        let decrypted = SweepKey::from_bip38(
+            encrypted.to_string(),
+            passphrase.to_string(),
+            NetworkKind::Main,
+        )
+        .unwrap();
+        let scripts = |key: &SweepKey| -> Vec<Vec<u8>> {
+            key.scripts()
+                .iter()
+                .map(|script| script.to_bytes())
+                .collect()
+        };
+        assert_eq!(scripts(&decrypted), scripts(&expected), "{encrypted}");
+    }
+}
+
+#[test]
+fn test_bip38_wrong_passphrase() {
+    let result = SweepKey::from_bip38(
+        "6PYNKZ1EAgYgmQfmNVamxyXVWHzK5s6DGhwP4J5o44cvXdoY7sRzhtpUeo".to_string(),
+        "TestingOneTwoFour".to_string(),
+        NetworkKind::Main,
+    );
+    assert!(matches!(result, Err(SweepError::WrongPassphrase)));
+
+    let result = SweepKey::from_bip38(
+        "not a key".to_string(),
+        "TestingOneTwoThree".to_string(),
+        NetworkKind::Main,
+    );
+    assert!(matches!(result, Err(SweepError::InvalidBip38 { .. })));
+}
+
+#[test]
+fn test_sweep_key_scripts() {
+    assert_eq!(sweep_key().scripts().len(), 3);
+
+    let uncompressed = BdkPrivateKey::from_slice(&[7u8; 32], NetworkKind::Test)
+        .map(|mut key| {
+            key.compressed = false;
+            key.to_wif()
+        })
+        .unwrap();
+    assert_eq!(SweepKey::from_wif(uncompressed).unwrap().scripts().len(), 1);
+
+    let key = sweep_key();
+    let utxos = utxos_for(&key, 20_000);
+    key.wipe();
+    assert!(key.scripts().is_empty());
+    let result = key.sweep(
+        &Arc::new(funded_wallet()),
+        utxos,
+        Arc::new(FeeRate::from_sat_per_vb(2).unwrap()),
+    );
+    assert!(matches!(result, Err(SweepError::Wiped)));
+}
+
+#[test]
+fn test_sweep_into_wallet() {
+    let wallet = Arc::new(funded_wallet());
+    let key = sweep_key();
+    let utxos = utxos_for(&key, 20_000);
+    let expected_inputs = utxos.len();
+
+    let psbt = key
+        .sweep(
+            &wallet,
+            utxos,
+            Arc::new(FeeRate::from_sat_per_vb(2).unwrap()),
+        )
+        .unwrap();
+    let tx = psbt.extract_tx().unwrap();
+
+    assert_eq!(tx.input().len(), expected_inputs);
+    assert_eq!(tx.output().len(), 1);
+    assert!(wallet.is_mine(tx.output()[0].script_pubkey.clone()));
+    assert!(tx
+        .input()
+        .iter()
+        .all(|input| !input.script_sig.0.is_empty() || !input.witness.is_empty()));
+    assert!(tx.output()[0].value.to_sat() < 60_000);
+}
+
+#[test]
+fn test_sweep_rejects_unknown_script() {
+    let wallet = Arc::new(funded_wallet());
+    let utxos = utxos_for(&sweep_key(), 20_000);
+    let other_key = SweepKey::from_wif(
+        BdkPrivateKey::from_slice(&[8u8; 32], NetworkKind::Test)
+            .unwrap()
+            .to_wif(),
+    )
+    .unwrap();
+
+    let result = other_key.sweep(
+        &wallet,
+        utxos,
+        Arc::new(FeeRate::from_sat_per_vb(2).unwrap()),
+    );
+    assert!(matches!(result, Err(SweepError::UnknownScript { .. })));
+
+    let result = other_key.sweep(
+        &wallet,
+        vec![],
+        Arc::new(FeeRate::from_sat_per_vb(2).unwrap()),
+    );
+    assert!(matches!(result, Err(SweepError::NoUtxos)));
+}
+
+#[test]
+fn test_sweep_utxo_rejects_missing_output() {
+    let key = sweep_key();
+    let tx = utxos_for(&key, 10_000)[0].tx.as_ref().clone();
+    let vout = tx.output().len() as u32;
+    assert!(matches!(
+        SweepUtxo::new(tx, vout),
+        Err(SweepError::InvalidUtxo { .. })
+    ));
+}
@@ -1,19 +1,32 @@
-use crate::bitcoin::{Amount, Input, Network, NetworkKind, OutPoint, Script, Transaction, TxOut};
+use crate::bitcoin::{
+    Address, Amount, FeeRate, Input, Network, NetworkKind, OutPoint, PaymentUri, Psbt, Script,
+    Transaction, TxOut,
+};
 use crate::descriptor::Descriptor;
-use crate::error::{AddForeignUtxoError, SighashParseError};
+use crate::error::{
+    AddForeignUtxoError, CoinSelectionError, CreateTxError, PaymentUriError, SighashParseError,
+    TxDraftError,
+};
 use crate::esplora::EsploraClient;
 use crate::store::Persister;
-use crate::tx_builder::TxBuilder;
-use crate::types::FullScanScriptInspector;
+use crate::tests::wallet::wallet_with_confirmed_utxos;
+use crate::tx_builder::{
+    BumpFeeTxBuilder, CoinSelectionAlgorithm, CoinSelectionCandidate, CoinSelector, TxBuilder,
+    TxOrdering,
+};
+use crate::types::{AddressAmount, FullScanScriptInspector, LockTime};
 use crate::wallet::Wallet;
 
 use bdk_wallet::bitcoin::hashes::hex::FromHex;
+use bdk_wallet::bitcoin::hashes::Hash;
 use bdk_wallet::bitcoin::{
-    absolute, consensus::serialize, transaction, Amount as BdkAmount, ScriptBuf as BdkScriptBuf,
-    Transaction as BdkTransaction, TxIn as BdkTxIn, TxOut as BdkTxOut,
+    absolute, consensus::serialize, psbt::Input as BdkInput, transaction, Amount as BdkAmount,
+    OutPoint as BdkOutPoint, PubkeyHash, ScriptBuf as BdkScriptBuf, Transaction as BdkTransaction,
+    TxIn as BdkTxIn, TxOut as BdkTxOut, Txid as BitcoinTxid,
 };
+use bdk_wallet::KeychainKind;
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 
 struct FullScanInspector;
@@ -672,3 +685,382 @@ fn test_combined_only_witness_utxo_and_foreign_utxo_with_finish() {
         );
     }
 }
+
+struct SmallestFirst;
+
+impl CoinSelector for SmallestFirst {
+    fn select(
+        &self,
+        _: Vec<CoinSelectionCandidate>,
+        mut optional_utxos: Vec<CoinSelectionCandidate>,
+        _: Arc<FeeRate>,
+        target_amount: Arc<Amount>,
+        _: Arc<Script>,
+    ) -> Result<Vec<OutPoint>, CoinSelectionError> {
+        optional_utxos.sort_by_key(|candidate| candidate.effective_value);
+        let mut selected = Vec::new();
+        let mut selected_value = 0;
+        for candidate in optional_utxos {
+            if selected_value >= target_amount.to_sat() as i64 {
+                break;
+            }
+            selected_value += candidate.effective_value;
+            selected.push(candidate.outpoint);
+        }
+        Ok(selected)
+    }
+}
+
+struct Refuse;
+
+impl CoinSelector for Refuse {
+    fn select(
+        &self,
+        _: Vec<CoinSelectionCandidate>,
+        _: Vec<CoinSelectionCandidate>,
+        _: Arc<FeeRate>,
+        _: Arc<Amount>,
+        _: Arc<Script>,
+    ) -> Result<Vec<OutPoint>, CoinSelectionError> {
+        Err(CoinSelectionError::Reason {
+            error_message: "policy violation".to_string(),
+        })
+    }
+}
+
+#[test]
+fn test_custom_coin_selection() {
+    let wallet = Arc::new(wallet_with_confirmed_utxos(&[10_000, 20_000, 30_000]));
+    let recipient = wallet
+        .next_unused_address(bdk_wallet::KeychainKind::External)
+        .address
+        .script_pubkey();
+
+    let psbt = TxBuilder::new()
+        .add_recipient(&recipient, Arc::new(Amount::from_sat(25_000)))
+        .fee_rate(&FeeRate::from_sat_per_vb(2).unwrap())
+        .custom_coin_selection(Arc::new(SmallestFirst))
+        .finish(&wallet)
+        .unwrap();
+    let tx = psbt.extract_tx().unwrap();
+    let mut spent = tx
+        .input()
+        .iter()
+        .map(|input| {
+            wallet
+                .get_utxo(input.previous_output.clone())
+                .unwrap()
+                .txout
+                .value
+                .to_sat()
+        })
+        .collect::<Vec<_>>();
+    spent.sort();
+    assert_eq!(spent, vec![10_000, 20_000]);
+
+    let result = TxBuilder::new()
+        .add_recipient(&recipient, Arc::new(Amount::from_sat(25_000)))
+        .custom_coin_selection(Arc::new(Refuse))
+        .finish(&wallet);
+    assert!(matches!(
+        result,
+        Err(CreateTxError::CoinSelector {
+            error: CoinSelectionError::Reason { error_message }
+        }) if error_message == "policy violation"
+    ));
+}
+
+#[test]
+fn test_preview_matches_finish_without_revealing_addresses() {
+    let wallet = Arc::new(wallet_with_confirmed_utxos(&[10_000, 20_000, 30_000]));
+    let recipient = Script(BdkScriptBuf::new_p2pkh(&PubkeyHash::all_zeros()));
+    let tx_builder = TxBuilder::new()
+        .add_recipient(&recipient, Arc::new(Amount::from_sat(45_000)))
+        .fee_rate(&FeeRate::from_sat_per_vb(2).unwrap())
+        .coin_selection(CoinSelectionAlgorithm::LargestFirst);
+
+    let preview = tx_builder.preview(&wallet, None).unwrap();
+    assert_eq!(wallet.derivation_index(KeychainKind::Internal), None);
+
+    let psbt = tx_builder.finish(&wallet).unwrap();
+    let tx = psbt.extract_tx().unwrap();
+    assert_eq!(
+        preview.utxos.into_iter().collect::<HashSet<_>>(),
+        tx.input()
+            .into_iter()
+            .map(|input| input.previous_output)
+            .collect::<HashSet<_>>()
+    );
+    assert_eq!(preview.fee.to_sat(), psbt.fee().unwrap());
+    assert_eq!(preview.fee_rate.to_sat_per_vb_floor(), 2);
+    let change = tx
+        .output()
+        .into_iter()
+        .find(|output| output.script_pubkey.to_bytes() != recipient.to_bytes())
+        .unwrap();
+    assert_eq!(preview.change.unwrap().to_sat(), change.value.to_sat());
+    // At the long-term fee rate only the cost of the change output is wasted, and spending two
+    // inputs now costs more if fee rates are expected to drop.
+    assert!(preview.waste > 0);
+    let low_fee_rate = Arc::new(FeeRate::from_sat_per_vb(1).unwrap());
+    assert!(
+        tx_builder
+            .preview(&wallet, Some(low_fee_rate))
+            .unwrap()
+            .waste
+            > preview.waste
+    );
+
+    let drain_psbt = TxBuilder::new()
+        .drain_wallet()
+        .drain_to(&recipient)
+        .fee_rate(&FeeRate::from_sat_per_vb(2).unwrap())
+        .finish(&wallet)
+        .unwrap();
+    assert_eq!(
+        preview.max_sendable.to_sat(),
+        drain_psbt.extract_tx().unwrap().output()[0].value.to_sat()
+    );
+}
+
+#[test]
+fn test_subtract_fee_from_recipients() {
+    let wallet = Arc::new(wallet_with_confirmed_utxos(&[50_000]));
+    let first = Script(BdkScriptBuf::new_p2pkh(&PubkeyHash::all_zeros()));
+    let second = Script(BdkScriptBuf::new_p2pkh(&PubkeyHash::from_byte_array(
+        [1; 20],
+    )));
+    let fee_rate = FeeRate::from_sat_per_vb(2).unwrap();
+    let sign = |psbt: &Arc<Psbt>| {
+        assert!(wallet.sign(Arc::clone(psbt), None).unwrap());
+        psbt.extract_tx().unwrap()
+    };
+    let output_value = |tx: &Transaction, script: &Script| {
+        tx.output()
+            .into_iter()
+            .find(|output| output.script_pubkey.to_bytes() == script.to_bytes())
+            .map(|output| output.value.to_sat())
+    };
+
+    let psbt = TxBuilder::new()
+        .add_recipient(&first, Arc::new(Amount::from_sat(20_000)))
+        .add_recipient(&second, Arc::new(Amount::from_sat(20_000)))
+        .fee_rate(&fee_rate)
+        .subtract_fee_from_recipients(vec![0, 1])
+        .finish(&wallet)
+        .unwrap();
+    let fee = psbt.fee().unwrap();
+    let tx = sign(&psbt);
+    assert_eq!(tx.output().len(), 3);
+    let first_value = output_value(&tx, &first).unwrap();
+    let second_value = output_value(&tx, &second).unwrap();
+    assert_eq!(first_value + second_value + fee, 40_000);
+    assert_eq!(first_value, second_value - fee % 2);
+    assert!(fee * 4 >= 2 * tx.weight());
+
+    // Sending the whole balance to a single recipient.
+    let psbt = TxBuilder::new()
+        .add_recipient(&first, Arc::new(Amount::from_sat(50_000)))
+        .fee_rate(&fee_rate)
+        .subtract_fee_from_recipients(vec![0])
+        .finish(&wallet)
+        .unwrap();
+    let fee = psbt.fee().unwrap();
+    let tx = sign(&psbt);
+    assert_eq!(tx.output().len(), 1);
+    assert_eq!(output_value(&tx, &first), Some(50_000 - fee));
+    assert!(fee * 4 >= 2 * tx.weight());
+
+    let result = TxBuilder::new()
+        .add_recipient(&first, Arc::new(Amount::from_sat(20_000)))
+        .subtract_fee_from_recipients(vec![1])
+        .finish(&wallet);
+    assert!(matches!(
+        result,
+        Err(CreateTxError::InvalidRecipientIndex { index: 1 })
+    ));
+
+    let change_index = wallet.next_unused_address(KeychainKind::Internal).index;
+    let result = TxBuilder::new()
+        .add_recipient(&first, Arc::new(Amount::from_sat(20_000)))
+        .add_recipient(&second, Arc::new(Amount::from_sat(600)))
+        .fee_rate(&fee_rate)
+        .subtract_fee_from_recipients(vec![0, 1])
+        .finish(&wallet);
+    assert!(matches!(
+        result,
+        Err(CreateTxError::RecipientCannotPayFee { index: 1, .. })
+    ));
+    // The failed build leaves the change address unused.
+    assert_eq!(
+        wallet.next_unused_address(KeychainKind::Internal).index,
+        change_index
+    );
+}
+
+#[test]
+fn test_address_and_payment_uri_recipients() {
+    let wallet = Arc::new(wallet_with_confirmed_utxos(&[50_000]));
+    let address = wallet.peek_address(KeychainKind::External, 5).address;
+    let uri =
+        PaymentUri::new(format!("bitcoin:{address}?amount=0.0001"), Network::Regtest).unwrap();
+
+    let psbt = TxBuilder::new()
+        .add_recipient_address(&address, Arc::new(Amount::from_sat(20_000)))
+        .add_payment_uri(&uri, None)
+        .unwrap()
+        .finish(&wallet)
+        .unwrap();
+    let mut values = psbt
+        .extract_tx()
+        .unwrap()
+        .output()
+        .into_iter()
+        .filter(|output| output.script_pubkey.to_bytes() == address.script_pubkey().to_bytes())
+        .map(|output| output.value.to_sat())
+        .collect::<Vec<_>>();
+    values.sort();
+    assert_eq!(values, vec![10_000, 20_000]);
+
+    let amountless_uri = PaymentUri::new(format!("bitcoin:{address}"), Network::Regtest).unwrap();
+    assert!(matches!(
+        TxBuilder::new().add_payment_uri(&amountless_uri, None),
+        Err(PaymentUriError::MissingAmount)
+    ));
+
+    let mainnet_address = Address::new(
+        "bc1qar0srrr7xfkvy5l643lydnw9re59gtzzwf5mdq".to_string(),
+        Network::Bitcoin,
+    )
+    .unwrap();
+    let result = TxBuilder::new()
+        .add_recipient_address(&mainnet_address, Arc::new(Amount::from_sat(20_000)))
+        .finish(&wallet);
+    assert!(matches!(
+        result,
+        Err(CreateTxError::AddressNetworkMismatch { .. })
+    ));
+
+    let psbt = TxBuilder::new()
+        .add_recipient_address(&mainnet_address, Arc::new(Amount::from_sat(20_000)))
+        .set_recipient_addresses(vec![AddressAmount {
+            address: Arc::clone(&address),
+            amount: Arc::new(Amount::from_sat(15_000)),
+        }])
+        .finish(&wallet)
+        .unwrap();
+    assert!(psbt
+        .extract_tx()
+        .unwrap()
+        .output()
+        .iter()
+        .any(
+            |output| output.script_pubkey.to_bytes() == address.script_pubkey().to_bytes()
+                && output.value.to_sat() == 15_000
+        ));
+    let result = TxBuilder::new()
+        .set_recipient_addresses(vec![AddressAmount {
+            address: Arc::new(mainnet_address),
+            amount: Arc::new(Amount::from_sat(15_000)),
+        }])
+        .finish(&wallet);
+    assert!(matches!(
+        result,
+        Err(CreateTxError::AddressNetworkMismatch { .. })
+    ));
+}
+
+#[test]
+fn test_tx_builder_drafts() {
+    let wallet = Arc::new(wallet_with_confirmed_utxos(&[30_000, 40_000]));
+    let recipient = Script(BdkScriptBuf::new_p2pkh(&PubkeyHash::all_zeros()));
+    let address = wallet.peek_address(KeychainKind::External, 5).address;
+    let utxo = wallet.list_unspent()[0].outpoint.clone();
+    let tx_builder = TxBuilder::new()
+        .add_recipient(&recipient, Arc::new(Amount::from_sat(10_000)))
+        .add_recipient_address(&address, Arc::new(Amount::from_sat(5_000)))
+        .add_utxo(utxo.clone())
+        .manually_selected_only()
+        .fee_rate(&FeeRate::from_sat_per_vb(3).unwrap())
+        .add_data(vec![1, 2, 3])
+        .nlocktime(LockTime::Blocks { height: 100 })
+        .sighash("SIGHASH_ALL".to_string())
+        .unwrap()
+        .ordering(TxOrdering::Untouched)
+        .coin_selection(CoinSelectionAlgorithm::LargestFirst)
+        .subtract_fee_from_recipients(vec![0]);
+
+    let draft = tx_builder.to_draft().unwrap();
+    let restored = TxBuilder::from_draft(draft.clone()).unwrap();
+    assert_eq!(restored.to_draft().unwrap(), draft);
+
+    let tx = restored.finish(&wallet).unwrap().extract_tx().unwrap();
+    assert_eq!(tx.input().len(), 1);
+    assert_eq!(tx.input()[0].previous_output, utxo);
+    assert_eq!(tx.lock_time(), 100);
+    assert_eq!(
+        tx.output()[1].script_pubkey.to_bytes(),
+        address.script_pubkey().to_bytes()
+    );
+    assert_eq!(tx.output()[1].value.to_sat(), 5_000);
+    assert!(tx.output()[0].value.to_sat() < 10_000);
+
+    // Foreign utxos are saved as hex encoded PSBTs, and enums by name.
+    let foreign_outpoint = BdkOutPoint {
+        txid: BitcoinTxid::from_byte_array([7; 32]),
+        vout: 1,
+    };
+    let foreign_input = BdkInput {
+        witness_utxo: Some(BdkTxOut {
+            value: BdkAmount::from_sat(20_000),
+            script_pubkey: recipient.0.clone(),
+        }),
+        ..Default::default()
+    };
+    let foreign_draft = TxBuilder::new()
+        .only_spend_change()
+        .add_foreign_utxo(foreign_outpoint.into(), (&foreign_input).into(), 68)
+        .unwrap()
+        .to_draft()
+        .unwrap();
+    assert!(foreign_draft.contains("\"psbt_input\":\"70736274ff"));
+    assert!(foreign_draft.contains("\"change_policy\":\"OnlyChange\""));
+    assert_eq!(
+        TxBuilder::from_draft(foreign_draft.clone())
+            .unwrap()
+            .to_draft()
+            .unwrap(),
+        foreign_draft
+    );
+
+    let unsupported = draft.replace("\"draft_version\":1", "\"draft_version\":99");
+    assert!(matches!(
+        TxBuilder::from_draft(unsupported),
+        Err(TxDraftError::UnsupportedVersion { version: 99 })
+    ));
+    assert!(matches!(
+        TxBuilder::from_draft("{}".to_string()),
+        Err(TxDraftError::InvalidDraft { .. })
+    ));
+    assert!(matches!(
+        TxBuilder::new()
+            .custom_coin_selection(Arc::new(Refuse))
+            .to_draft(),
+        Err(TxDraftError::CustomCoinSelection)
+    ));
+
+    let bump_fee = BumpFeeTxBuilder::new(
+        tx.compute_txid(),
+        Arc::new(FeeRate::from_sat_per_vb(5).unwrap()),
+    )
+    .set_exact_sequence(0xfffffffd)
+    .allow_dust(true);
+    let draft = bump_fee.to_draft().unwrap();
+    assert_eq!(
+        BumpFeeTxBuilder::from_draft(draft.clone())
+            .unwrap()
+            .to_draft()
+            .unwrap(),
+        draft
+    );
+}
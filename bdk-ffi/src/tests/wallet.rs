@@ -1,22 +1,26 @@
-use crate::bitcoin::{Amount, BlockHash, FeeRate, Network, NetworkKind};
+use crate::bitcoin::{Amount, BlockHash, FeeRate, Network, NetworkKind, OutPoint, Txid};
 use crate::descriptor::Descriptor;
-use crate::error::{CreateTxError, LoadWithPersistError};
+use crate::error::{ConsolidationError, CreateTxError, LoadWithPersistError, PersistenceError};
 use crate::signer::SignersContainer;
 use crate::store::Persister;
-use crate::tx_builder::{BumpFeeTxBuilder, TxBuilder};
+use crate::tx_builder::{BumpFeeTxBuilder, CpfpTxBuilder, TxBuilder};
 use crate::types::{UnconfirmedTx, Update};
 use crate::wallet::{CreateParams, LoadParams, Wallet};
 
 use bdk_wallet::bitcoin::Amount as BdkAmount;
 use bdk_wallet::bitcoin::Transaction as BdkTransaction;
 use bdk_wallet::bitcoin::{absolute, transaction, TxOut as BdkTxOut};
-use bdk_wallet::KeychainKind;
+use bdk_wallet::rusqlite::Connection as BdkConnection;
+use bdk_wallet::{KeychainKind, WalletPersister};
 
+use std::ops::DerefMut;
 use std::sync::Arc;
 
 const EXTERNAL_DESCRIPTOR: &str = "wpkh(tprv8ZgxMBicQKsPf2qfrEygW6fdYseJDDrVnDv26PH5BHdvSuG6ecCbHqLVof9yZcMoM31z9ur3tTYbSnr1WBqbGX97CbXcmp5H6qeMpyvx35B/84h/1h/1h/0/*)";
 const INTERNAL_DESCRIPTOR: &str = "wpkh(tprv8ZgxMBicQKsPf2qfrEygW6fdYseJDDrVnDv26PH5BHdvSuG6ecCbHqLVof9yZcMoM31z9ur3tTYbSnr1WBqbGX97CbXcmp5H6qeMpyvx35B/84h/1h/1h/1/*)";
 const TWO_PATH_DESCRIPTOR: &str = "wpkh([9a6a2580/84'/1'/0']tpubDDnGNapGEY6AZAdQbfRJgMg9fvz8pUBrLwvyvUqEgcUfgzM6zc2eVK4vY9x9L5FJWdX8WumXuLEDV5zDZnTfbn87vLe9XceCFwTu9so9Kks/<0;1>/*)";
+const TIMELOCKED_EXTERNAL_DESCRIPTOR: &str = "wsh(or_d(pk(tprv8ZgxMBicQKsPf2qfrEygW6fdYseJDDrVnDv26PH5BHdvSuG6ecCbHqLVof9yZcMoM31z9ur3tTYbSnr1WBqbGX97CbXcmp5H6qeMpyvx35B/84h/1h/1h/0/*),and_v(v:pk(tprv8ZgxMBicQKsPf2qfrEygW6fdYseJDDrVnDv26PH5BHdvSuG6ecCbHqLVof9yZcMoM31z9ur3tTYbSnr1WBqbGX97CbXcmp5H6qeMpyvx35B/84h/1h/1h/2/*),older(144))))";
+const TIMELOCKED_INTERNAL_DESCRIPTOR: &str = "wsh(or_d(pk(tprv8ZgxMBicQKsPf2qfrEygW6fdYseJDDrVnDv26PH5BHdvSuG6ecCbHqLVof9yZcMoM31z9ur3tTYbSnr1WBqbGX97CbXcmp5H6qeMpyvx35B/84h/1h/1h/1/*),and_v(v:pk(tprv8ZgxMBicQKsPf2qfrEygW6fdYseJDDrVnDv26PH5BHdvSuG6ecCbHqLVof9yZcMoM31z9ur3tTYbSnr1WBqbGX97CbXcmp5H6qeMpyvx35B/84h/1h/1h/3/*),older(144))))";
 const EXPECTED_FIRST_ADDRESS: &str = "tb1qhjys9wxlfykmte7ftryptx975uqgd6kcm6a7z4";
 
 fn external_descriptor() -> Arc<Descriptor> {
@@ -51,7 +55,7 @@ fn build_wallet() -> Wallet {
     .unwrap()
 }
 
-fn funded_wallet() -> Wallet {
+pub(super) fn funded_wallet() -> Wallet {
     let wallet = Wallet::new(
         external_descriptor(),
         internal_descriptor(),
@@ -499,3 +503,575 @@ fn test_load_from_two_path_descriptor_with_params() {
         error => panic!("expected InvalidChangeSet error, got {:?}", error),
     }
 }
+
+#[test]
+fn test_insert_tx_does_not_spend_inputs() {
+    let wallet = Arc::new(funded_wallet());
+    let recipient_script = wallet
+        .next_unused_address(KeychainKind::External)
+        .address
+        .script_pubkey();
+    let tx = TxBuilder::new()
+        .add_recipient(&recipient_script, Arc::new(Amount::from_sat(10_000)))
+        .finish(&wallet)
+        .unwrap()
+        .extract_tx()
+        .unwrap();
+
+    assert!(wallet.insert_tx(&tx));
+    assert!(!wallet.insert_tx(&tx));
+    assert!(wallet.get_tx(tx.compute_txid()).unwrap().is_none());
+    assert_eq!(wallet.balance().total.to_sat(), 76_000);
+    assert_eq!(wallet.list_unspent().len(), 1);
+}
+
+#[test]
+fn test_cancel_tx_releases_inputs_and_change_address() {
+    let wallet = Arc::new(funded_wallet());
+    let recipient_script = wallet
+        .next_unused_address(KeychainKind::External)
+        .address
+        .script_pubkey();
+    let change_index = wallet.next_unused_address(KeychainKind::Internal).index;
+    let tx = TxBuilder::new()
+        .add_recipient(&recipient_script, Arc::new(Amount::from_sat(10_000)))
+        .finish(&wallet)
+        .unwrap()
+        .extract_tx()
+        .unwrap();
+
+    // The change address is reserved by the builder until the transaction is cancelled.
+    assert_ne!(
+        wallet.next_unused_address(KeychainKind::Internal).index,
+        change_index
+    );
+    wallet.cancel_tx(&tx);
+    assert_eq!(
+        wallet.next_unused_address(KeychainKind::Internal).index,
+        change_index
+    );
+
+    wallet.apply_unconfirmed_txs(vec![UnconfirmedTx {
+        tx: Arc::clone(&tx),
+        last_seen: 2,
+    }]);
+    assert!(wallet
+        .list_unspent()
+        .iter()
+        .all(|utxo| utxo.outpoint.txid.0 == tx.compute_txid().0));
+
+    wallet.cancel_tx(&tx);
+    assert!(wallet.get_tx(tx.compute_txid()).unwrap().is_none());
+    assert_eq!(wallet.balance().total.to_sat(), 76_000);
+
+    // Seeing the transaction again does not make it canonical.
+    wallet.apply_unconfirmed_txs(vec![UnconfirmedTx { tx, last_seen: 3 }]);
+    assert_eq!(wallet.balance().total.to_sat(), 76_000);
+}
+
+#[test]
+fn test_cancel_tx_releases_change_address_of_inserted_tx() {
+    let wallet = Arc::new(funded_wallet());
+    let recipient_script = wallet
+        .next_unused_address(KeychainKind::External)
+        .address
+        .script_pubkey();
+    let build = |amount| {
+        TxBuilder::new()
+            .add_recipient(&recipient_script, Arc::new(Amount::from_sat(amount)))
+            .finish(&wallet)
+            .unwrap()
+            .extract_tx()
+            .unwrap()
+    };
+    let change_address = wallet.next_unused_address(KeychainKind::Internal);
+    let change_script = change_address.address.script_pubkey();
+    let tx = build(10_000);
+    assert!(wallet.insert_tx(&tx));
+    assert_ne!(
+        wallet.next_unused_address(KeychainKind::Internal).index,
+        change_address.index
+    );
+
+    wallet.cancel_tx(&tx);
+    assert_eq!(
+        wallet.next_unused_address(KeychainKind::Internal).index,
+        change_address.index
+    );
+    let replacement = build(20_000);
+    assert!(replacement
+        .output()
+        .iter()
+        .any(|output| output.script_pubkey.0 == change_script.0));
+
+    // Once another transaction pays to it, the address is used again.
+    wallet.apply_unconfirmed_txs(vec![UnconfirmedTx {
+        tx: replacement,
+        last_seen: 2,
+    }]);
+    assert_ne!(
+        wallet.next_unused_address(KeychainKind::Internal).index,
+        change_address.index
+    );
+}
+
+#[test]
+fn test_wallet_allows_concurrent_readers() {
+    let wallet = Arc::new(funded_wallet());
+    let _reader = wallet.get_wallet();
+
+    let handle = {
+        let wallet = Arc::clone(&wallet);
+        std::thread::spawn(move || (wallet.balance().total.to_sat(), wallet.list_unspent().len()))
+    };
+
+    assert_eq!(handle.join().unwrap(), (76_000, 1));
+}
+
+#[test]
+fn test_cpfp_tx_builder_reaches_package_fee_rate() {
+    let wallet = Arc::new(funded_wallet());
+    let recipient_script = wallet
+        .next_unused_address(KeychainKind::External)
+        .address
+        .script_pubkey();
+    let parent_psbt = TxBuilder::new()
+        .add_recipient(&recipient_script, Arc::new(Amount::from_sat(30_000)))
+        .fee_rate(&FeeRate::from_sat_per_vb(1).unwrap())
+        .finish(&wallet)
+        .unwrap();
+    wallet.sign(Arc::clone(&parent_psbt), None).unwrap();
+    let parent = parent_psbt.extract_tx().unwrap();
+    wallet.apply_unconfirmed_txs(vec![UnconfirmedTx {
+        tx: Arc::clone(&parent),
+        last_seen: 2,
+    }]);
+
+    let vout = parent
+        .output()
+        .iter()
+        .position(|txout| txout.script_pubkey.to_bytes() == recipient_script.to_bytes())
+        .unwrap() as u32;
+    let outpoint = OutPoint {
+        txid: parent.compute_txid(),
+        vout,
+    };
+    // A failed build leaves the change address unused.
+    let change_index = wallet.next_unused_address(KeychainKind::Internal).index;
+    let result = CpfpTxBuilder::new(
+        outpoint.clone(),
+        Arc::new(FeeRate::from_sat_per_vb(1_000).unwrap()),
+    )
+    .finish(&wallet);
+    assert!(result.is_err());
+    assert_eq!(
+        wallet.next_unused_address(KeychainKind::Internal).index,
+        change_index
+    );
+
+    let child_psbt = CpfpTxBuilder::new(outpoint, Arc::new(FeeRate::from_sat_per_vb(10).unwrap()))
+        .finish(&wallet)
+        .unwrap();
+    wallet.sign(Arc::clone(&child_psbt), None).unwrap();
+    let child = child_psbt.extract_tx().unwrap();
+
+    assert_eq!(child.input().len(), 1);
+    assert_eq!(child.output().len(), 1);
+    let parent_fee = wallet.calculate_fee(&parent).unwrap().to_sat();
+    let child_fee = wallet.calculate_fee(&child).unwrap().to_sat();
+    let package_vsize = parent.vsize() + child.vsize();
+    assert!(parent_fee + child_fee >= 10 * package_vsize);
+    assert!(parent_fee + child_fee <= 10 * package_vsize + 10);
+}
+
+#[test]
+fn test_cpfp_tx_builder_accelerates_incoming_transaction() {
+    let wallet = Arc::new(funded_wallet());
+    let script_pubkey = wallet
+        .next_unused_address(KeychainKind::External)
+        .address
+        .script_pubkey();
+    let parent = BdkTransaction {
+        version: transaction::Version::TWO,
+        lock_time: absolute::LockTime::ZERO,
+        input: vec![bdk_wallet::bitcoin::TxIn {
+            previous_output: bdk_wallet::bitcoin::OutPoint {
+                txid: bdk_wallet::bitcoin::hashes::Hash::all_zeros(),
+                vout: 7,
+            },
+            ..Default::default()
+        }],
+        output: vec![BdkTxOut {
+            value: BdkAmount::from_sat(50_000),
+            script_pubkey: script_pubkey.0.clone(),
+        }],
+    };
+    let parent: Arc<crate::bitcoin::Transaction> = Arc::new(parent.into());
+    wallet.apply_unconfirmed_txs(vec![UnconfirmedTx {
+        tx: Arc::clone(&parent),
+        last_seen: 2,
+    }]);
+    let outpoint = OutPoint {
+        txid: parent.compute_txid(),
+        vout: 0,
+    };
+    let fee_rate = Arc::new(FeeRate::from_sat_per_vb(10).unwrap());
+
+    // The wallet cannot compute the fee of a transaction spending outputs it does not know.
+    let result = CpfpTxBuilder::new(outpoint.clone(), Arc::clone(&fee_rate)).finish(&wallet);
+    assert!(matches!(
+        result,
+        Err(CreateTxError::ParentFeeUnknown { .. })
+    ));
+
+    let parent_fee = 200;
+    let child_psbt = CpfpTxBuilder::new(outpoint, fee_rate)
+        .parent_fee(Arc::new(Amount::from_sat(parent_fee)))
+        .finish(&wallet)
+        .unwrap();
+    wallet.sign(Arc::clone(&child_psbt), None).unwrap();
+    let child = child_psbt.extract_tx().unwrap();
+
+    let child_fee = wallet.calculate_fee(&child).unwrap().to_sat();
+    let package_vsize = parent.vsize() + child.vsize();
+    assert!(parent_fee + child_fee >= 10 * package_vsize);
+    assert!(parent_fee + child_fee <= 10 * package_vsize + 10);
+}
+
+#[test]
+fn test_cpfp_tx_builder_rejects_unknown_output() {
+    let wallet = Arc::new(funded_wallet());
+    let unknown = OutPoint {
+        txid: Arc::new(
+            Txid::from_string(
+                "0000000000000000000000000000000000000000000000000000000000000001".to_string(),
+            )
+            .unwrap(),
+        ),
+        vout: 0,
+    };
+
+    let result = CpfpTxBuilder::new(unknown, Arc::new(FeeRate::from_sat_per_vb(10).unwrap()))
+        .finish(&wallet);
+
+    assert!(matches!(result, Err(CreateTxError::UnknownUtxo { .. })));
+}
+
+pub(super) fn wallet_with_confirmed_utxos(values: &[u64]) -> Wallet {
+    let wallet = Wallet::new(
+        external_descriptor(),
+        internal_descriptor(),
+        Network::Regtest,
+        Arc::new(Persister::new_in_memory().unwrap()),
+        25,
+    )
+    .unwrap();
+    confirm_utxos(&wallet, values);
+
+    wallet
+}
+
+/// Receive one output per value to the wallet's external keychain, confirmed at height 1.
+fn confirm_utxos(wallet: &Wallet, values: &[u64]) {
+    let output = values
+        .iter()
+        .map(|value| BdkTxOut {
+            value: BdkAmount::from_sat(*value),
+            script_pubkey: wallet
+                .reveal_next_address(KeychainKind::External)
+                .address
+                .script_pubkey()
+                .0
+                .clone(),
+        })
+        .collect();
+    let funding_tx = BdkTransaction {
+        version: transaction::Version::ONE,
+        lock_time: absolute::LockTime::ZERO,
+        input: vec![],
+        output,
+    };
+    let txid = funding_tx.compute_txid();
+    let block_id = bdk_wallet::chain::BlockId {
+        height: 1,
+        hash: bdk_wallet::bitcoin::hashes::Hash::all_zeros(),
+    };
+    let mut update = bdk_wallet::Update::default();
+    update
+        .last_active_indices
+        .insert(KeychainKind::External, values.len() as u32 - 1);
+    update.tx_update.txs.push(Arc::new(funding_tx));
+    update.tx_update.anchors.insert((
+        bdk_wallet::chain::ConfirmationBlockTime {
+            block_id,
+            confirmation_time: 1,
+        },
+        txid,
+    ));
+    update.chain = Some(wallet.get_wallet().latest_checkpoint().insert(block_id));
+    wallet.apply_update(Arc::new(Update(update))).unwrap();
+}
+
+#[test]
+fn test_plan_consolidation() {
+    let wallet = Arc::new(wallet_with_confirmed_utxos(&[
+        10_000, 20_000, 30_000, 40_000, 50_000,
+    ]));
+    let low = Arc::new(FeeRate::from_sat_per_vb(2).unwrap());
+    let high = Arc::new(FeeRate::from_sat_per_vb(20).unwrap());
+
+    let proposals = wallet
+        .plan_consolidation(Arc::clone(&low), Arc::clone(&high), None, 500)
+        .unwrap();
+    assert_eq!(proposals.len(), 1);
+    assert_eq!(proposals[0].utxos.len(), 5);
+    assert!(proposals[0].savings.to_sat() > 0);
+
+    let psbt = proposals[0].tx_builder.finish(&wallet).unwrap();
+    wallet.sign(Arc::clone(&psbt), None).unwrap();
+    let tx = psbt.extract_tx().unwrap();
+    assert_eq!(tx.input().len(), 5);
+    assert_eq!(tx.output().len(), 1);
+    assert!(tx.weight() <= proposals[0].weight);
+    assert!(wallet.calculate_fee(&tx).unwrap().to_sat() <= proposals[0].fee.to_sat() + 5);
+
+    let proposals = wallet
+        .plan_consolidation(Arc::clone(&low), Arc::clone(&high), None, 2)
+        .unwrap();
+    assert_eq!(proposals.len(), 2);
+    assert_eq!(proposals[0].utxos[0].txout.value.to_sat(), 10_000);
+    let drain_scripts = proposals
+        .iter()
+        .map(|proposal| {
+            let psbt = proposal.tx_builder.finish(&wallet).unwrap();
+            psbt.extract_tx().unwrap().output()[0]
+                .script_pubkey
+                .to_bytes()
+        })
+        .collect::<Vec<_>>();
+    assert_ne!(drain_scripts[0], drain_scripts[1]);
+    assert!(matches!(
+        wallet.plan_consolidation(Arc::clone(&low), Arc::clone(&high), None, 1),
+        Err(ConsolidationError::InvalidMaxInputs { max_inputs: 1 })
+    ));
+
+    let proposals = wallet
+        .plan_consolidation(
+            Arc::clone(&low),
+            Arc::clone(&high),
+            Some(Arc::new(Amount::from_sat(25_000))),
+            500,
+        )
+        .unwrap();
+    assert_eq!(proposals.len(), 1);
+    assert_eq!(proposals[0].utxos.len(), 2);
+
+    assert!(wallet
+        .plan_consolidation(high, low, None, 500)
+        .unwrap()
+        .is_empty());
+}
+
+#[test]
+fn test_spending_paths() {
+    let wallet = Wallet::new(
+        Arc::new(
+            Descriptor::new(
+                TIMELOCKED_EXTERNAL_DESCRIPTOR.to_string(),
+                NetworkKind::Test,
+            )
+            .unwrap(),
+        ),
+        Arc::new(
+            Descriptor::new(
+                TIMELOCKED_INTERNAL_DESCRIPTOR.to_string(),
+                NetworkKind::Test,
+            )
+            .unwrap(),
+        ),
+        Network::Regtest,
+        Arc::new(Persister::new_in_memory().unwrap()),
+        25,
+    )
+    .unwrap();
+    confirm_utxos(&wallet, &[50_000]);
+    let wallet = Arc::new(wallet);
+
+    let paths = wallet.spending_paths(KeychainKind::External, 0).unwrap();
+    assert_eq!(paths.len(), 2);
+    let immediate = paths
+        .iter()
+        .find(|path| path.relative_timelock.is_none())
+        .unwrap();
+    let recovery = paths
+        .iter()
+        .find(|path| path.relative_timelock == Some(144))
+        .unwrap();
+    assert!(immediate.is_spendable_now);
+    assert!(!recovery.is_spendable_now);
+    assert_eq!(immediate.signers.len(), 1);
+    assert_eq!(recovery.signers.len(), 1);
+    assert_eq!(immediate.description, "signature from 9122d9e0");
+    assert!(recovery
+        .description
+        .ends_with("144 blocks after confirmation"));
+
+    // The change keychain needs a path of its own.
+    let internal_paths = wallet.spending_paths(KeychainKind::Internal, 0).unwrap();
+    let internal_immediate = internal_paths
+        .iter()
+        .find(|path| path.relative_timelock.is_none())
+        .unwrap();
+    let internal_recovery = internal_paths
+        .iter()
+        .find(|path| path.relative_timelock == Some(144))
+        .unwrap();
+
+    let recipient = wallet.peek_address(KeychainKind::External, 5).address;
+    let builder = TxBuilder::new().add_recipient(
+        &recipient.script_pubkey(),
+        Arc::new(Amount::from_sat(10_000)),
+    );
+    assert!(matches!(
+        builder.finish(&wallet),
+        Err(CreateTxError::SpendingPolicyRequired { .. })
+    ));
+
+    let psbt = builder
+        .spending_path(immediate.clone())
+        .spending_path(internal_immediate.clone())
+        .finish(&wallet)
+        .unwrap();
+    let tx = psbt.0.lock().unwrap().unsigned_tx.clone();
+    assert!(tx.input.iter().all(|input| input.sequence.0 != 144));
+
+    let psbt = builder
+        .spending_path(recovery.clone())
+        .spending_path(internal_recovery.clone())
+        .finish(&wallet)
+        .unwrap();
+    let tx = psbt.0.lock().unwrap().unsigned_tx.clone();
+    assert!(tx.input.iter().all(|input| input.sequence.0 == 144));
+}
+
+#[test]
+fn test_encrypted_sqlite_persister() {
+    let path =
+        std::env::temp_dir().join(format!("bdk-ffi-encrypted-{}.sqlite", std::process::id()));
+    let path_string = path.to_str().unwrap().to_string();
+    let _ = std::fs::remove_file(&path);
+    let key = vec![7u8; 32];
+    let new_key = vec![9u8; 32];
+
+    let persister =
+        Arc::new(Persister::new_encrypted_sqlite(path_string.clone(), key.clone()).unwrap());
+    let wallet = Wallet::new(
+        external_descriptor(),
+        internal_descriptor(),
+        Network::Signet,
+        Arc::clone(&persister),
+        25,
+    )
+    .unwrap();
+    wallet.reveal_next_address(KeychainKind::External);
+    wallet.reveal_next_address(KeychainKind::External);
+    wallet.persist(Arc::clone(&persister)).unwrap();
+    drop(wallet);
+    drop(persister);
+
+    // The descriptors are not stored in plaintext.
+    let contents = std::fs::read(&path).unwrap();
+    assert!(!contents.windows(4).any(|window| window == b"wpkh"));
+
+    assert!(matches!(
+        Persister::new_encrypted_sqlite(path_string.clone(), new_key.clone()),
+        Err(PersistenceError::WrongKey)
+    ));
+    assert!(matches!(
+        Persister::new_encrypted_sqlite(path_string.clone(), vec![7u8; 16]),
+        Err(PersistenceError::InvalidKeyLength { length: 16 })
+    ));
+
+    let persister =
+        Arc::new(Persister::new_encrypted_sqlite(path_string.clone(), key.clone()).unwrap());
+    let wallet = Wallet::load(
+        external_descriptor(),
+        internal_descriptor(),
+        Arc::clone(&persister),
+        25,
+    )
+    .unwrap();
+    assert_eq!(wallet.derivation_index(KeychainKind::External), Some(1));
+    persister.rotate_key(new_key.clone()).unwrap();
+    drop(wallet);
+    drop(persister);
+
+    assert!(matches!(
+        Persister::new_encrypted_sqlite(path_string.clone(), key),
+        Err(PersistenceError::WrongKey)
+    ));
+    let persister = Arc::new(Persister::new_encrypted_sqlite(path_string, new_key).unwrap());
+    let wallet = Wallet::load(external_descriptor(), internal_descriptor(), persister, 25).unwrap();
+    assert_eq!(wallet.derivation_index(KeychainKind::External), Some(1));
+    assert!(matches!(
+        Persister::new_in_memory()
+            .unwrap()
+            .rotate_key(vec![0u8; 32]),
+        Err(PersistenceError::NotEncrypted)
+    ));
+    std::fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn test_encrypted_sqlite_detects_tampering() {
+    let path = std::env::temp_dir().join(format!(
+        "bdk-ffi-encrypted-tampering-{}.sqlite",
+        std::process::id()
+    ));
+    let path_string = path.to_str().unwrap().to_string();
+    let key = vec![7u8; 32];
+    let tampered = |statement: &str| {
+        let _ = std::fs::remove_file(&path);
+        let persister =
+            Arc::new(Persister::new_encrypted_sqlite(path_string.clone(), key.clone()).unwrap());
+        let wallet = Wallet::new(
+            external_descriptor(),
+            internal_descriptor(),
+            Network::Signet,
+            Arc::clone(&persister),
+            25,
+        )
+        .unwrap();
+        wallet.reveal_next_address(KeychainKind::External);
+        wallet.persist(Arc::clone(&persister)).unwrap();
+        drop(wallet);
+        drop(persister);
+
+        BdkConnection::open(&path)
+            .unwrap()
+            .execute_batch(statement)
+            .unwrap();
+        let persister = Persister::new_encrypted_sqlite(path_string.clone(), key.clone())?;
+        let mut inner = persister.inner.lock().unwrap();
+        WalletPersister::initialize(inner.deref_mut()).map(|_| ())
+    };
+
+    assert!(tampered("").is_ok());
+    assert!(matches!(
+        tampered("DELETE FROM bdk_encrypted_changeset WHERE id = 2"),
+        Err(PersistenceError::Tampered)
+    ));
+    assert!(matches!(
+        tampered("DELETE FROM bdk_encrypted_changeset WHERE id = 1"),
+        Err(PersistenceError::Tampered)
+    ));
+    assert!(matches!(
+        tampered(
+            "UPDATE bdk_encrypted_changeset SET id = 3 WHERE id = 1;
+            UPDATE bdk_encrypted_changeset SET id = 1 WHERE id = 2;
+            UPDATE bdk_encrypted_changeset SET id = 2 WHERE id = 3;"
+        ),
+        Err(PersistenceError::Tampered)
+    ));
+    std::fs::remove_file(path).unwrap();
+}
@@ -1,29 +1,55 @@
-use crate::bitcoin::{Amount, FeeRate, Input, OutPoint, Psbt, Script, Txid};
-use crate::error::{AddForeignUtxoError, CreateTxError, SighashParseError};
-use crate::types::{KeychainKind, LockTime, ScriptAmount};
-use crate::wallet::Wallet;
+use crate::bitcoin::{
+    Address, Amount, FeeRate, Input, OutPoint, PaymentUri, Psbt, Script, TxOut, Txid,
+};
+use crate::error::{
+    AddForeignUtxoError, CoinSelectionError, CreateTxError, PaymentUriError, SighashParseError,
+    TxDraftError,
+};
+use crate::spending_path::SpendingPath;
+use crate::types::{AddressAmount, ChainPosition, KeychainKind, LockTime, ScriptAmount, TxPreview};
+use crate::wallet::{released_index, Wallet};
 
 use bdk_wallet::bitcoin::absolute::{Height as BdkHeight, LockTime as BdkLockTime};
+use bdk_wallet::bitcoin::address::{Address as BdkAddress, NetworkUnchecked};
 use bdk_wallet::bitcoin::amount::Amount as BdkAmount;
+use bdk_wallet::bitcoin::hex::{DisplayHex, FromHex};
 use bdk_wallet::bitcoin::psbt::Input as BdkInput;
 use bdk_wallet::bitcoin::psbt::PsbtSighashType as BdkPsbtSighashType;
 use bdk_wallet::bitcoin::script::PushBytesBuf;
+use bdk_wallet::bitcoin::secp256k1::rand::RngCore;
 use bdk_wallet::bitcoin::Psbt as BdkPsbt;
 use bdk_wallet::bitcoin::ScriptBuf as BdkScriptBuf;
-use bdk_wallet::bitcoin::{OutPoint as BdkOutPoint, Sequence, Weight as BdkWeight};
+use bdk_wallet::bitcoin::{
+    transaction::Version as BdkVersion, Transaction as BdkTransaction, TxIn as BdkTxIn,
+    TxOut as BdkTxOut,
+};
+use bdk_wallet::bitcoin::{
+    FeeRate as BdkFeeRate, Script as BdkScript, SignedAmount as BdkSignedAmount,
+};
+use bdk_wallet::bitcoin::{
+    OutPoint as BdkOutPoint, Sequence, Txid as BitcoinTxid, Weight as BdkWeight,
+};
 use bdk_wallet::coin_selection::{
-    CoinSelectionAlgorithm as BdkCoinSelectionAlgorithm,
+    decide_change as bdk_decide_change, CoinSelectionAlgorithm as BdkCoinSelectionAlgorithm,
+    CoinSelectionResult as BdkCoinSelectionResult, InsufficientFunds as BdkInsufficientFunds,
     LargestFirstCoinSelection as BdkLargestFirstCoinSelection,
     OldestFirstCoinSelection as BdkOldestFirstCoinSelection,
     SingleRandomDraw as BdkSingleRandomDraw,
 };
-use bdk_wallet::TxOrdering as BdkTxOrdering;
+use bdk_wallet::serde::de::DeserializeOwned;
+use bdk_wallet::serde::{Deserialize, Serialize};
+use bdk_wallet::serde_json;
+use bdk_wallet::{
+    TxOrdering as BdkTxOrdering, Utxo as BdkUtxo, Wallet as BdkWallet,
+    WeightedUtxo as BdkWeightedUtxo,
+};
 
 use std::collections::BTreeMap;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::convert::{TryFrom, TryInto};
+use std::fmt;
 use std::str::FromStr;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 
 type ChangeSpendPolicy = bdk_wallet::ChangeSpendPolicy;
 
@@ -33,6 +59,7 @@ type ChangeSpendPolicy = bdk_wallet::ChangeSpendPolicy;
 pub struct TxBuilder {
     add_global_xpubs: bool,
     recipients: Vec<(BdkScriptBuf, BdkAmount)>,
+    recipient_addresses: Vec<BdkAddress>,
     utxos: Vec<BdkOutPoint>,
     unspendable: Vec<BdkOutPoint>,
     internal_policy_path: Option<BTreeMap<String, Vec<usize>>>,
@@ -56,6 +83,8 @@ pub struct TxBuilder {
     only_witness_utxo: bool,
     foreign_utxos: Vec<(BdkOutPoint, BdkInput, BdkWeight, Option<u32>)>,
     coin_selection: Option<CoinSelectionAlgorithm>,
+    coin_selector: Option<Arc<dyn CoinSelector>>,
+    fee_paying_recipients: Vec<u64>,
 }
 
 #[allow(clippy::new_without_default)]
@@ -66,6 +95,7 @@ impl TxBuilder {
         TxBuilder {
             add_global_xpubs: false,
             recipients: Vec::new(),
+            recipient_addresses: Vec::new(),
             utxos: Vec::new(),
             unspendable: Vec::new(),
             internal_policy_path: None,
@@ -89,6 +119,8 @@ impl TxBuilder {
             only_witness_utxo: false,
             foreign_utxos: Vec::new(),
             coin_selection: None,
+            coin_selector: None,
+            fee_paying_recipients: Vec::new(),
         }
     }
 
@@ -143,7 +175,38 @@ impl TxBuilder {
         })
     }
 
-    /// Replace the recipients already added with a new list of recipients.
+    /// Add an address to the internal list of recipients. `finish` fails if the address is not
+    /// valid for the network of the wallet.
+    pub fn add_recipient_address(&self, address: &Address, amount: Arc<Amount>) -> Arc<Self> {
+        let mut recipients = self.recipients.clone();
+        recipients.push((address.0.script_pubkey(), amount.0));
+        let mut recipient_addresses = self.recipient_addresses.clone();
+        recipient_addresses.push(address.0.clone());
+
+        Arc::new(TxBuilder {
+            recipients,
+            recipient_addresses,
+            ..self.clone()
+        })
+    }
+
+    /// Add the recipient of a BIP-21 payment URI to the internal list of recipients, paying the
+    /// given `amount` or else the amount requested by the URI. `finish` fails if the address is
+    /// not valid for the network of the wallet.
+    #[uniffi::method(default(amount = None))]
+    pub fn add_payment_uri(
+        &self,
+        uri: &PaymentUri,
+        amount: Option<Arc<Amount>>,
+    ) -> Result<Arc<Self>, PaymentUriError> {
+        let amount = amount
+            .or_else(|| uri.amount())
+            .ok_or(PaymentUriError::MissingAmount)?;
+        Ok(self.add_recipient_address(&uri.address(), amount))
+    }
+
+    /// Replace the recipients already added, including those added as addresses, with a new list
+    /// of recipients.
     pub fn set_recipients(&self, recipients: Vec<ScriptAmount>) -> Arc<Self> {
         let recipients = recipients
             .iter()
@@ -151,6 +214,30 @@ impl TxBuilder {
             .collect();
         Arc::new(TxBuilder {
             recipients,
+            recipient_addresses: Vec::new(),
+            ..self.clone()
+        })
+    }
+
+    /// Replace the recipients already added with a new list of addresses. `finish` fails if an
+    /// address is not valid for the network of the wallet.
+    pub fn set_recipient_addresses(&self, recipients: Vec<AddressAmount>) -> Arc<Self> {
+        let recipient_addresses = recipients
+            .iter()
+            .map(|address_amount| address_amount.address.0.clone())
+            .collect();
+        let recipients = recipients
+            .iter()
+            .map(|address_amount| {
+                (
+                    address_amount.address.0.script_pubkey(),
+                    address_amount.amount.0,
+                )
+            })
+            .collect();
+        Arc::new(TxBuilder {
+            recipients,
+            recipient_addresses,
             ..self.clone()
         })
     }
@@ -223,6 +310,12 @@ impl TxBuilder {
         Arc::new(updated_self)
     }
 
+    /// Spend through one of the paths returned by `Wallet::spending_paths`, a readable alternative
+    /// to `TxBuilder::policy_path`.
+    pub fn spending_path(&self, path: SpendingPath) -> Arc<Self> {
+        self.policy_path(path.policy_path, path.keychain)
+    }
+
     /// Set a specific `ChangeSpendPolicy`. See `TxBuilder::do_not_spend_change` and `TxBuilder::only_spend_change` for
     /// some shortcuts. This method assumes the presence of an internal keychain, otherwise it has no effect.
     pub fn change_policy(&self, change_policy: ChangeSpendPolicy) -> Arc<Self> {
@@ -365,6 +458,23 @@ impl TxBuilder {
         })
     }
 
+    /// Deduct the fee from the amounts of the recipients at the given indices, counted in the order
+    /// the recipients were added, instead of paying it on top of them. The fee is shared equally
+    /// between these recipients, the first of them paying what cannot be split evenly.
+    ///
+    /// Setting the amount of such a recipient to the spendable balance sends everything to it,
+    /// without change. To send everything left after paying the other recipients their exact
+    /// amounts, use [`TxBuilder::drain_wallet`] and [`TxBuilder::drain_to`] instead.
+    ///
+    /// `finish` fails if a recipient's amount cannot cover its share of the fee and stay above the
+    /// dust limit.
+    pub fn subtract_fee_from_recipients(&self, indices: Vec<u64>) -> Arc<Self> {
+        Arc::new(TxBuilder {
+            fee_paying_recipients: indices,
+            ..self.clone()
+        })
+    }
+
     /// Set whether or not the dust limit is checked.
     ///
     /// Note: by avoiding a dust limit check you may end up with a transaction that is non-standard.
@@ -414,6 +524,18 @@ impl TxBuilder {
     pub fn coin_selection(&self, coin_selection: CoinSelectionAlgorithm) -> Arc<Self> {
         Arc::new(TxBuilder {
             coin_selection: Some(coin_selection),
+            coin_selector: None,
+            ..self.clone()
+        })
+    }
+
+    /// Use a custom coin selection algorithm, replacing any algorithm chosen with
+    /// [`TxBuilder::coin_selection`]. Errors returned by the selector are reported as
+    /// [`CreateTxError::CoinSelector`]. See [`CoinSelector`] for what the selector must not do.
+    pub fn custom_coin_selection(&self, coin_selector: Arc<dyn CoinSelector>) -> Arc<Self> {
+        Arc::new(TxBuilder {
+            coin_selection: None,
+            coin_selector: Some(coin_selector),
             ..self.clone()
         })
     }
@@ -557,6 +679,163 @@ impl TxBuilder {
         }))
     }
 
+    /// Restore a transaction builder saved with [`TxBuilder::to_draft`].
+    #[uniffi::constructor]
+    pub fn from_draft(draft: String) -> Result<Self, TxDraftError> {
+        let draft: TxBuilderDraft = parse_draft(&draft)?;
+        let outpoints = |outpoints: Vec<String>| {
+            outpoints
+                .iter()
+                .map(|outpoint| parse_field(outpoint, BdkOutPoint::from_str))
+                .collect::<Result<Vec<_>, _>>()
+        };
+        Ok(TxBuilder {
+            add_global_xpubs: draft.add_global_xpubs,
+            recipients: draft
+                .recipients
+                .into_iter()
+                .map(|recipient| {
+                    Ok((
+                        parse_field(&recipient.script, BdkScriptBuf::from_hex)?,
+                        BdkAmount::from_sat(recipient.amount),
+                    ))
+                })
+                .collect::<Result<_, TxDraftError>>()?,
+            recipient_addresses: draft
+                .recipient_addresses
+                .iter()
+                .map(|address| {
+                    parse_field(address, BdkAddress::<NetworkUnchecked>::from_str)
+                        .map(BdkAddress::assume_checked)
+                })
+                .collect::<Result<_, _>>()?,
+            utxos: outpoints(draft.utxos)?,
+            unspendable: outpoints(draft.unspendable)?,
+            internal_policy_path: draft.internal_policy_path,
+            external_policy_path: draft.external_policy_path,
+            change_policy: parse_change_policy(&draft.change_policy)?,
+            manually_selected_only: draft.manually_selected_only,
+            fee_rate: draft
+                .fee_rate
+                .map(|rate| FeeRate(BdkFeeRate::from_sat_per_kwu(rate))),
+            fee_absolute: draft
+                .fee_absolute
+                .map(|fee| Arc::new(Amount::from_sat(fee))),
+            drain_wallet: draft.drain_wallet,
+            drain_to: draft
+                .drain_to
+                .map(|script| parse_field(&script, BdkScriptBuf::from_hex))
+                .transpose()?,
+            sequence: draft.sequence,
+            data: parse_field(&draft.data, Vec::<u8>::from_hex)?,
+            current_height: draft.current_height,
+            locktime: draft
+                .locktime
+                .map(|locktime| BdkLockTime::from_consensus(locktime).into()),
+            allow_dust: draft.allow_dust,
+            version: draft.version,
+            sighash: draft
+                .sighash
+                .map(|sighash| parse_field(&sighash, BdkPsbtSighashType::from_str))
+                .transpose()?,
+            ordering: parse_ordering(&draft.ordering)?,
+            exclude_unconfirmed: draft.exclude_unconfirmed,
+            exclude_below_confirmations: draft.exclude_below_confirmations,
+            only_witness_utxo: draft.only_witness_utxo,
+            foreign_utxos: draft
+                .foreign_utxos
+                .into_iter()
+                .map(|utxo| {
+                    Ok((
+                        parse_field(&utxo.outpoint, BdkOutPoint::from_str)?,
+                        parse_psbt_input(&utxo.psbt_input)?,
+                        BdkWeight::from_wu(utxo.satisfaction_weight),
+                        utxo.sequence,
+                    ))
+                })
+                .collect::<Result<_, TxDraftError>>()?,
+            coin_selection: draft
+                .coin_selection
+                .map(|algorithm| parse_coin_selection(&algorithm))
+                .transpose()?,
+            coin_selector: None,
+            fee_paying_recipients: draft.fee_paying_recipients,
+        })
+    }
+
+    /// Save the options of this transaction builder as a JSON draft, to be restored later with
+    /// [`TxBuilder::from_draft`]. The format is versioned, and a draft saved with another version
+    /// of the format fails to restore with `TxDraftError::UnsupportedVersion` rather than being
+    /// misread.
+    ///
+    /// Fails if a custom coin selection algorithm was set, since it cannot be saved.
+    pub fn to_draft(&self) -> Result<String, TxDraftError> {
+        if self.coin_selector.is_some() {
+            return Err(TxDraftError::CustomCoinSelection);
+        }
+        let outpoints = |outpoints: &[BdkOutPoint]| {
+            outpoints
+                .iter()
+                .map(|outpoint| outpoint.to_string())
+                .collect()
+        };
+        let draft = TxBuilderDraft {
+            draft_version: DRAFT_VERSION,
+            add_global_xpubs: self.add_global_xpubs,
+            recipients: self
+                .recipients
+                .iter()
+                .map(|(script, amount)| RecipientDraft {
+                    script: script.to_hex_string(),
+                    amount: amount.to_sat(),
+                })
+                .collect(),
+            recipient_addresses: self
+                .recipient_addresses
+                .iter()
+                .map(|address| address.to_string())
+                .collect(),
+            utxos: outpoints(&self.utxos),
+            unspendable: outpoints(&self.unspendable),
+            internal_policy_path: self.internal_policy_path.clone(),
+            external_policy_path: self.external_policy_path.clone(),
+            change_policy: change_policy_name(self.change_policy).to_string(),
+            manually_selected_only: self.manually_selected_only,
+            fee_rate: self.fee_rate.as_ref().map(|rate| rate.0.to_sat_per_kwu()),
+            fee_absolute: self.fee_absolute.as_ref().map(|fee| fee.0.to_sat()),
+            drain_wallet: self.drain_wallet,
+            drain_to: self.drain_to.as_ref().map(|script| script.to_hex_string()),
+            sequence: self.sequence,
+            data: self.data.to_lower_hex_string(),
+            current_height: self.current_height,
+            locktime: self.locktime.as_ref().map(locktime_to_draft).transpose()?,
+            allow_dust: self.allow_dust,
+            version: self.version,
+            sighash: self.sighash.map(|sighash| sighash.to_string()),
+            ordering: ordering_name(self.ordering).to_string(),
+            exclude_unconfirmed: self.exclude_unconfirmed,
+            exclude_below_confirmations: self.exclude_below_confirmations,
+            only_witness_utxo: self.only_witness_utxo,
+            foreign_utxos: self
+                .foreign_utxos
+                .iter()
+                .map(
+                    |(outpoint, psbt_input, satisfaction_weight, sequence)| ForeignUtxoDraft {
+                        outpoint: outpoint.to_string(),
+                        psbt_input: psbt_input_to_hex(outpoint, psbt_input),
+                        satisfaction_weight: satisfaction_weight.to_wu(),
+                        sequence: *sequence,
+                    },
+                )
+                .collect(),
+            coin_selection: self
+                .coin_selection
+                .map(|algorithm| coin_selection_name(algorithm).to_string()),
+            fee_paying_recipients: self.fee_paying_recipients.clone(),
+        };
+        write_draft(&draft)
+    }
+
     /// Finish building the transaction.
     ///
     /// Uses the thread-local random number generator (rng).
@@ -567,8 +846,313 @@ impl TxBuilder {
     /// method before closing the wallet. See `Wallet::reveal_next_address`.
     pub fn finish(&self, wallet: &Arc<Wallet>) -> Result<Arc<Psbt>, CreateTxError> {
         // TODO: I had to change the wallet here to be mutable. Why is that now required with the 1.0 API?
-        let mut wallet = wallet.get_wallet();
+        let mut wallet = wallet.get_wallet_mut();
+        let psbt = self.create_psbt(&mut wallet)?;
+        Ok(Arc::new(psbt.into()))
+    }
+
+    /// Preview the transaction that `finish` would create, without revealing a change address or
+    /// otherwise changing the wallet. This is cheap enough to be called every time the amount or
+    /// fee rate of the transaction changes.
+    ///
+    /// The `waste` of the preview is computed against `long_term_fee_rate`, the fee rate expected to
+    /// be paid for spending outputs in the future. It defaults to the fee rate of the transaction.
+    ///
+    /// Coin selection may be random, so `finish` is not guaranteed to select the same outputs.
+    #[uniffi::method(default(long_term_fee_rate = None))]
+    pub fn preview(
+        &self,
+        wallet: &Arc<Wallet>,
+        long_term_fee_rate: Option<Arc<FeeRate>>,
+    ) -> Result<TxPreview, CreateTxError> {
+        let mut wallet = wallet.get_wallet_mut();
+        let change_keychain = change_keychain(&wallet);
+        // Passing the change script explicitly keeps coin selection from revealing one.
+        let change_script = match &self.drain_to {
+            Some(script) => script.clone(),
+            None => match released_index(&wallet, change_keychain) {
+                Some(index) => wallet.peek_address(change_keychain, index),
+                None => wallet
+                    .list_unused_addresses(change_keychain)
+                    .next()
+                    .unwrap_or_else(|| {
+                        wallet.peek_address(
+                            change_keychain,
+                            wallet.next_derivation_index(change_keychain),
+                        )
+                    }),
+            }
+            .address
+            .script_pubkey(),
+        };
+
+        let psbt = TxBuilder {
+            drain_to: Some(change_script.clone()),
+            ..self.clone()
+        }
+        .create_psbt(&mut wallet)?;
+
+        // Find the largest amount the first recipient could receive by sending it everything
+        // left after paying the other recipients.
+        let (max_script, other_recipients) = match self.recipients.split_first() {
+            Some(((script, _), others)) => (script.clone(), others.to_vec()),
+            None => (change_script.clone(), Vec::new()),
+        };
+        let max_sendable = TxBuilder {
+            recipients: other_recipients,
+            drain_wallet: true,
+            drain_to: Some(max_script.clone()),
+            ..self.clone()
+        }
+        .create_psbt(&mut wallet)
+        .ok()
+        .and_then(|psbt| {
+            psbt.unsigned_tx
+                .output
+                .into_iter()
+                .find(|output| output.script_pubkey == max_script)
+        })
+        .map_or(BdkAmount::ZERO, |output| output.value);
+
+        let fee = psbt.fee().map_err(|error| CreateTxError::Psbt {
+            error_message: error.to_string(),
+        })?;
+        let fee_rate = self
+            .fee_rate
+            .as_ref()
+            .map_or(BdkFeeRate::BROADCAST_MIN, |fee_rate| fee_rate.0);
+        let long_term_fee_rate = long_term_fee_rate.map_or(fee_rate, |fee_rate| fee_rate.0);
+
+        let input_weights = self.input_weights(&wallet, &psbt)?;
+        let weight = signed_weight(&psbt, &input_weights);
+        let mut waste = BdkSignedAmount::ZERO;
+        for (satisfaction_weight, _) in &input_weights {
+            let input_weight = BdkTxIn::default().segwit_weight() + *satisfaction_weight;
+            waste +=
+                signed_fee(fee_rate, input_weight) - signed_fee(long_term_fee_rate, input_weight);
+        }
+        let utxos = psbt
+            .unsigned_tx
+            .input
+            .iter()
+            .map(|input| OutPoint::from(&input.previous_output))
+            .collect();
+
+        let change = psbt
+            .unsigned_tx
+            .output
+            .iter()
+            .find(|output| output.script_pubkey == change_script);
+        match change {
+            Some(change) => {
+                let (change_spend_weight, _) = satisfaction_weight(&wallet, change_keychain)?;
+                waste += signed_fee(fee_rate, change.weight())
+                    + signed_fee(
+                        long_term_fee_rate,
+                        BdkTxIn::default().segwit_weight() + change_spend_weight,
+                    );
+            }
+            None => {
+                // Without change, whatever is paid above the fee rate is wasted.
+                let needed = fee_rate
+                    .fee_vb(weight.to_vbytes_ceil())
+                    .unwrap_or(BdkAmount::MAX);
+                waste += fee
+                    .checked_sub(needed)
+                    .map_or(BdkSignedAmount::ZERO, |excess| {
+                        excess.to_signed().unwrap_or(BdkSignedAmount::MAX)
+                    });
+            }
+        }
+
+        Ok(TxPreview {
+            utxos,
+            fee: Arc::new(Amount(fee)),
+            fee_rate: Arc::new(FeeRate(BdkFeeRate::from_sat_per_kwu(
+                fee.to_sat() * 1000 / weight.to_wu().max(1),
+            ))),
+            weight: weight.to_wu(),
+            change: change.map(|change| Arc::new(Amount(change.value))),
+            waste: waste.to_sat(),
+            max_sendable: Arc::new(Amount(max_sendable)),
+        })
+    }
+}
+
+impl TxBuilder {
+    /// The satisfaction weight of each input of `psbt`, and whether it spends a segwit output. The
+    /// weight of local inputs comes from the wallet's descriptors, and the weight of foreign
+    /// inputs is the one they were added with.
+    fn input_weights(
+        &self,
+        wallet: &BdkWallet,
+        psbt: &BdkPsbt,
+    ) -> Result<Vec<(BdkWeight, bool)>, CreateTxError> {
+        psbt.unsigned_tx
+            .input
+            .iter()
+            .zip(&psbt.inputs)
+            .map(|(input, psbt_input)| {
+                let outpoint = input.previous_output;
+                match wallet.spk_index().txout(outpoint) {
+                    Some(((keychain, _), _)) => satisfaction_weight(wallet, keychain),
+                    None => {
+                        let weight = self
+                            .foreign_utxos
+                            .iter()
+                            .find(|(foreign, _, _, _)| *foreign == outpoint)
+                            .map_or(BdkWeight::ZERO, |(_, _, weight, _)| *weight);
+                        Ok((weight, psbt_input.witness_utxo.is_some()))
+                    }
+                }
+            })
+            .collect()
+    }
+
+    /// Build the PSBT, deducting the fee from the recipients chosen with
+    /// `subtract_fee_from_recipients` if any.
+    fn create_psbt(&self, wallet: &mut BdkWallet) -> Result<BdkPsbt, CreateTxError> {
+        let network = wallet.network();
+        if let Some(address) = self
+            .recipient_addresses
+            .iter()
+            .find(|address| !address.as_unchecked().is_valid_for_network(network))
+        {
+            return Err(CreateTxError::AddressNetworkMismatch {
+                address: address.to_string(),
+                network: network.to_string(),
+            });
+        }
+
+        // Reuse a change address released by `Wallet::cancel_tx`, which the wallet itself still
+        // considers used.
+        if self.drain_to.is_none() && !self.recipients.is_empty() {
+            let change_keychain = change_keychain(wallet);
+            if let Some(index) = released_index(wallet, change_keychain) {
+                return TxBuilder {
+                    drain_to: Some(wallet.peek_address(change_keychain, index).script_pubkey()),
+                    ..self.clone()
+                }
+                .create_psbt(wallet);
+            }
+        }
+
+        let mut fee_payers = self.fee_paying_recipients.clone();
+        fee_payers.sort_unstable();
+        fee_payers.dedup();
+        if fee_payers.is_empty() {
+            return self.select_and_create_psbt(wallet);
+        }
+        if let Some(index) = fee_payers
+            .iter()
+            .find(|index| **index >= self.recipients.len() as u64)
+        {
+            return Err(CreateTxError::InvalidRecipientIndex { index: *index });
+        }
+
+        // The transaction is built twice, so the change script is chosen once for both. It is only
+        // marked as used once the final transaction pays to it, so that a failure does not burn it.
+        let (drain_to, change) = match &self.drain_to {
+            Some(script) => (script.clone(), None),
+            None => {
+                let change = wallet.next_unused_address(change_keychain(wallet));
+                (change.address.script_pubkey(), Some(change))
+            }
+        };
+
+        // Select inputs covering only the amounts, the fee being taken out of them. Without a fee
+        // the transaction has the same inputs and outputs as the final one, so its weight gives
+        // the fee to deduct.
+        let zero_fee_psbt = TxBuilder {
+            drain_to: Some(drain_to.clone()),
+            fee_rate: None,
+            fee_absolute: Some(Arc::new(Amount(BdkAmount::ZERO))),
+            fee_paying_recipients: Vec::new(),
+            ..self.clone()
+        }
+        .select_and_create_psbt(wallet)?;
+        let fee = match &self.fee_absolute {
+            Some(fee) => fee.0,
+            None => {
+                let input_weights = self.input_weights(wallet, &zero_fee_psbt)?;
+                let weight = signed_weight(&zero_fee_psbt, &input_weights);
+                let fee_rate = self
+                    .fee_rate
+                    .as_ref()
+                    .map_or(BdkFeeRate::BROADCAST_MIN, |fee_rate| fee_rate.0);
+                fee_rate
+                    .fee_vb(weight.to_vbytes_ceil())
+                    .ok_or(CreateTxError::FeeRateTooLow {
+                        required: fee_rate.to_string(),
+                    })?
+            }
+        };
+
+        let payer_count = fee_payers.len() as u64;
+        let mut recipients = self.recipients.clone();
+        for (position, index) in fee_payers.iter().enumerate() {
+            let mut share = fee.to_sat() / payer_count;
+            if position == 0 {
+                share += fee.to_sat() % payer_count;
+            }
+            let (script, amount) = &mut recipients[*index as usize];
+            let minimum = if self.allow_dust {
+                BdkAmount::ZERO
+            } else {
+                script.minimal_non_dust()
+            };
+            *amount = amount
+                .checked_sub(BdkAmount::from_sat(share))
+                .filter(|amount| *amount >= minimum)
+                .ok_or(CreateTxError::RecipientCannotPayFee {
+                    index: *index,
+                    fee: share,
+                })?;
+        }
+
+        let utxos = zero_fee_psbt
+            .unsigned_tx
+            .input
+            .iter()
+            .map(|input| input.previous_output)
+            .filter(|outpoint| wallet.spk_index().txout(*outpoint).is_some())
+            .collect();
+        let psbt = TxBuilder {
+            recipients,
+            utxos,
+            manually_selected_only: true,
+            drain_to: Some(drain_to.clone()),
+            fee_rate: None,
+            fee_absolute: Some(Arc::new(Amount(fee))),
+            fee_paying_recipients: Vec::new(),
+            ..self.clone()
+        }
+        .select_and_create_psbt(wallet)?;
+        if let Some(change) = change {
+            if psbt
+                .unsigned_tx
+                .output
+                .iter()
+                .any(|txout| txout.script_pubkey == drain_to)
+            {
+                wallet.mark_used(change.keychain, change.index);
+            }
+        }
+        Ok(psbt)
+    }
+
+    /// Build the PSBT with the chosen coin selection algorithm.
+    fn select_and_create_psbt(&self, wallet: &mut BdkWallet) -> Result<BdkPsbt, CreateTxError> {
         let tx_builder = wallet.build_tx();
+        if let Some(coin_selector) = &self.coin_selector {
+            let coin_selection = ForeignCoinSelection::new(Arc::clone(coin_selector));
+            let error = Arc::clone(&coin_selection.error);
+            let result = self.finish_with_builder(tx_builder.coin_selection(coin_selection));
+            return match error.lock().unwrap().take() {
+                Some(error) => Err(CreateTxError::CoinSelector { error }),
+                None => result,
+            };
+        }
         match self.coin_selection {
             None | Some(CoinSelectionAlgorithm::BranchAndBound) => {
                 self.finish_with_builder(tx_builder)
@@ -584,13 +1168,11 @@ impl TxBuilder {
             }
         }
     }
-}
 
-impl TxBuilder {
     fn finish_with_builder<Cs>(
         &self,
         mut tx_builder: bdk_wallet::TxBuilder<'_, Cs>,
-    ) -> Result<Arc<Psbt>, CreateTxError>
+    ) -> Result<BdkPsbt, CreateTxError>
     where
         Cs: BdkCoinSelectionAlgorithm,
     {
@@ -680,10 +1262,61 @@ impl TxBuilder {
                     .map_err(AddForeignUtxoError::from)?,
             };
         }
-        let psbt = tx_builder.finish().map_err(CreateTxError::from)?;
+        tx_builder.finish().map_err(CreateTxError::from)
+    }
+}
 
-        Ok(Arc::new(psbt.into()))
+/// The keychain that change is sent to, which is the external one for single descriptor wallets.
+fn change_keychain(wallet: &BdkWallet) -> KeychainKind {
+    if wallet
+        .keychains()
+        .any(|(keychain, _)| keychain == KeychainKind::Internal)
+    {
+        KeychainKind::Internal
+    } else {
+        KeychainKind::External
+    }
+}
+
+/// The satisfaction weight of the descriptor of `keychain`, and whether it spends a segwit output.
+fn satisfaction_weight(
+    wallet: &BdkWallet,
+    keychain: KeychainKind,
+) -> Result<(BdkWeight, bool), CreateTxError> {
+    let descriptor = wallet.public_descriptor(keychain);
+    descriptor
+        .max_weight_to_satisfy()
+        .map(|weight| (weight, descriptor.desc_type().segwit_version().is_some()))
+        .map_err(|error| CreateTxError::Descriptor {
+            error_message: error.to_string(),
+        })
+}
+
+/// The estimated weight of `psbt` once signed, given the satisfaction weights of its inputs.
+fn signed_weight(psbt: &BdkPsbt, input_weights: &[(BdkWeight, bool)]) -> BdkWeight {
+    let mut weight = psbt.unsigned_tx.weight();
+    let mut has_witness = false;
+    for (satisfaction_weight, is_segwit) in input_weights {
+        weight += *satisfaction_weight;
+        if *is_segwit {
+            // The count of witness elements.
+            weight += BdkWeight::from_wu(1);
+            has_witness = true;
+        }
+    }
+    if has_witness {
+        // The segwit marker and flag.
+        weight += BdkWeight::from_wu(2);
     }
+    weight
+}
+
+/// A fee computed with `fee_rate` for `weight`, as a signed amount to account for waste.
+fn signed_fee(fee_rate: BdkFeeRate, weight: BdkWeight) -> BdkSignedAmount {
+    fee_rate
+        .fee_wu(weight)
+        .and_then(|fee| fee.to_signed().ok())
+        .unwrap_or(BdkSignedAmount::MAX)
 }
 
 /// A `BumpFeeTxBuilder` is created by calling `build_fee_bump` on a wallet. After assigning it, you set options on it
@@ -718,6 +1351,45 @@ impl BumpFeeTxBuilder {
         }
     }
 
+    /// Restore a fee bump builder saved with [`BumpFeeTxBuilder::to_draft`].
+    #[uniffi::constructor]
+    pub fn from_draft(draft: String) -> Result<Self, TxDraftError> {
+        let draft: BumpFeeTxBuilderDraft = parse_draft(&draft)?;
+        Ok(BumpFeeTxBuilder {
+            txid: Arc::new(Txid(parse_field(&draft.txid, BitcoinTxid::from_str)?)),
+            fee_rate: Arc::new(FeeRate(BdkFeeRate::from_sat_per_kwu(draft.fee_rate))),
+            sequence: draft.sequence,
+            current_height: draft.current_height,
+            locktime: draft
+                .locktime
+                .map(|locktime| BdkLockTime::from_consensus(locktime).into()),
+            allow_dust: draft.allow_dust,
+            version: draft.version,
+            sighash: draft
+                .sighash
+                .map(|sighash| parse_field(&sighash, BdkPsbtSighashType::from_str))
+                .transpose()?,
+            ordering: parse_ordering(&draft.ordering)?,
+        })
+    }
+
+    /// Save the options of this fee bump builder as a JSON draft, to be restored later with
+    /// [`BumpFeeTxBuilder::from_draft`].
+    pub fn to_draft(&self) -> Result<String, TxDraftError> {
+        write_draft(&BumpFeeTxBuilderDraft {
+            draft_version: DRAFT_VERSION,
+            txid: self.txid.0.to_string(),
+            fee_rate: self.fee_rate.0.to_sat_per_kwu(),
+            sequence: self.sequence,
+            current_height: self.current_height,
+            locktime: self.locktime.as_ref().map(locktime_to_draft).transpose()?,
+            allow_dust: self.allow_dust,
+            version: self.version,
+            sighash: self.sighash.map(|sighash| sighash.to_string()),
+            ordering: ordering_name(self.ordering).to_string(),
+        })
+    }
+
     /// Set an exact `nSequence` value.
     ///
     /// This can cause conflicts if the wallet’s descriptors contain an "older" (`OP_CSV`) operator and the given
@@ -812,7 +1484,7 @@ impl BumpFeeTxBuilder {
     /// WARNING: To avoid change address reuse you must persist the changes resulting from one or more calls to this
     /// method before closing the wallet. See `Wallet::reveal_next_address`.
     pub fn finish(&self, wallet: &Arc<Wallet>) -> Result<Arc<Psbt>, CreateTxError> {
-        let mut wallet = wallet.get_wallet();
+        let mut wallet = wallet.get_wallet_mut();
         let mut tx_builder = wallet
             .build_fee_bump(self.txid.0)
             .map_err(CreateTxError::from)?;
@@ -846,6 +1518,213 @@ impl BumpFeeTxBuilder {
     }
 }
 
+/// A `CpfpTxBuilder` creates a child transaction spending an unconfirmed output of the wallet so
+/// that the parent and child together pay a target fee rate (child-pays-for-parent). This can be
+/// used to accelerate transactions that cannot be replaced, such as incoming payments.
+///
+/// The whole output is spent to a single output of the wallet, paying the fee needed for the
+/// package formed by the parent and the child to reach the target fee rate. Unconfirmed ancestors
+/// of the parent are not taken into account.
+#[derive(Clone, uniffi::Object)]
+pub struct CpfpTxBuilder {
+    outpoint: OutPoint,
+    fee_rate: Arc<FeeRate>,
+    parent_fee: Option<Arc<Amount>>,
+    drain_to: Option<BdkScriptBuf>,
+    current_height: Option<u32>,
+    locktime: Option<LockTime>,
+    version: Option<i32>,
+}
+
+#[uniffi::export]
+impl CpfpTxBuilder {
+    /// Create a builder spending the unconfirmed wallet output at `outpoint`, targeting `fee_rate`
+    /// for the package formed by its parent transaction and the child.
+    #[uniffi::constructor]
+    pub fn new(outpoint: OutPoint, fee_rate: Arc<FeeRate>) -> Self {
+        CpfpTxBuilder {
+            outpoint,
+            fee_rate,
+            parent_fee: None,
+            drain_to: None,
+            current_height: None,
+            locktime: None,
+            version: None,
+        }
+    }
+
+    /// Set the fee paid by the parent transaction, instead of having the wallet compute it.
+    ///
+    /// This is needed when the wallet does not know the outputs spent by the parent, typically for
+    /// incoming payments. The fee can be looked up with a blockchain client, e.g.
+    /// `EsploraClient::get_tx_info`.
+    pub fn parent_fee(&self, fee: Arc<Amount>) -> Arc<Self> {
+        Arc::new(CpfpTxBuilder {
+            parent_fee: Some(fee),
+            ..self.clone()
+        })
+    }
+
+    /// Sets the address to send the output to. Defaults to the next unused change address of the
+    /// wallet.
+    pub fn drain_to(&self, script: &Script) -> Arc<Self> {
+        Arc::new(CpfpTxBuilder {
+            drain_to: Some(script.0.clone()),
+            ..self.clone()
+        })
+    }
+
+    /// Set the current blockchain height.
+    ///
+    /// This will be used to set the `nLockTime` for preventing fee sniping. If you don't provide a
+    /// current height, we use the last sync height.
+    pub fn current_height(&self, height: u32) -> Arc<Self> {
+        Arc::new(CpfpTxBuilder {
+            current_height: Some(height),
+            ..self.clone()
+        })
+    }
+
+    /// Use a specific nLockTime while creating the transaction.
+    ///
+    /// This can cause conflicts if the wallet’s descriptors contain an "after" (`OP_CLTV`) operator.
+    pub fn nlocktime(&self, locktime: LockTime) -> Arc<Self> {
+        Arc::new(CpfpTxBuilder {
+            locktime: Some(locktime),
+            ..self.clone()
+        })
+    }
+
+    /// Build a transaction with a specific version.
+    ///
+    /// The version should always be greater than 0 and greater than 1 if the wallet’s descriptors contain an "older"
+    /// (`OP_CSV`) operator.
+    pub fn version(&self, version: i32) -> Arc<Self> {
+        Arc::new(CpfpTxBuilder {
+            version: Some(version),
+            ..self.clone()
+        })
+    }
+
+    /// Finish building the child transaction.
+    ///
+    /// Unless it is set with `parent_fee`, the fee of the parent transaction must be known to the
+    /// wallet, so the outputs it spends need to be in the wallet's transaction graph. See
+    /// `Wallet::insert_txout`.
+    ///
+    /// Returns a new `Psbt` per BIP174.
+    ///
+    /// WARNING: To avoid change address reuse you must persist the changes resulting from one or more calls to this
+    /// method before closing the wallet. See `Wallet::reveal_next_address`.
+    pub fn finish(&self, wallet: &Arc<Wallet>) -> Result<Arc<Psbt>, CreateTxError> {
+        let outpoint: BdkOutPoint = self.outpoint.clone().into();
+        let mut wallet = wallet.get_wallet_mut();
+
+        let utxo = wallet
+            .get_utxo(outpoint)
+            .ok_or_else(|| CreateTxError::UnknownUtxo {
+                outpoint: outpoint.to_string(),
+            })?;
+        if utxo.chain_position.is_confirmed() {
+            return Err(CreateTxError::OutputAlreadyConfirmed {
+                outpoint: outpoint.to_string(),
+            });
+        }
+        let parent =
+            wallet
+                .tx_graph()
+                .get_tx(outpoint.txid)
+                .ok_or_else(|| CreateTxError::UnknownUtxo {
+                    outpoint: outpoint.to_string(),
+                })?;
+        let parent_fee =
+            match &self.parent_fee {
+                Some(fee) => fee.0,
+                None => wallet.calculate_fee(&parent).map_err(|error| {
+                    CreateTxError::ParentFeeUnknown {
+                        error_message: error.to_string(),
+                    }
+                })?,
+            };
+
+        // The change address is only marked as used once the child is built, so that a failure
+        // does not burn it.
+        let (drain_script, change) = match &self.drain_to {
+            Some(script) => (script.clone(), None),
+            None => {
+                let change = wallet.next_unused_address(bdk_wallet::KeychainKind::Internal);
+                (change.address.script_pubkey(), Some(change))
+            }
+        };
+
+        // The child has a single input spending the output and a single output, so its weight
+        // can be known before building it.
+        let descriptor = wallet.public_descriptor(utxo.keychain);
+        let satisfaction_weight =
+            descriptor
+                .max_weight_to_satisfy()
+                .map_err(|error| CreateTxError::Descriptor {
+                    error_message: error.to_string(),
+                })?;
+        let is_segwit = descriptor.desc_type().segwit_version().is_some();
+        let skeleton = BdkTransaction {
+            version: BdkVersion::TWO,
+            lock_time: BdkLockTime::ZERO,
+            input: vec![BdkTxIn::default()],
+            output: vec![BdkTxOut {
+                value: BdkAmount::ZERO,
+                script_pubkey: drain_script.clone(),
+            }],
+        };
+        let mut child_weight = skeleton.weight() + satisfaction_weight;
+        if is_segwit {
+            // The segwit marker and flag, and the count of witness elements of the input.
+            child_weight += BdkWeight::from_wu(3);
+        }
+        let fee_for = |weight: BdkWeight| {
+            self.fee_rate
+                .0
+                .fee_vb(weight.to_vbytes_ceil())
+                .ok_or(CreateTxError::FeeRateTooLow {
+                    required: self.fee_rate.0.to_string(),
+                })
+        };
+        let child_fee = fee_for(child_weight)?;
+        let package_fee = fee_for(parent.weight())? + child_fee;
+        let child_fee = package_fee
+            .checked_sub(parent_fee)
+            .unwrap_or(BdkAmount::ZERO)
+            .max(child_fee);
+
+        let mut tx_builder = wallet.build_tx();
+        tx_builder
+            .add_utxo(outpoint)
+            .map_err(CreateTxError::from)?
+            .manually_selected_only()
+            .drain_to(drain_script)
+            .fee_absolute(child_fee);
+        if let Some(height) = self.current_height {
+            let height = BdkHeight::from_consensus(height)
+                .map_err(|_| CreateTxError::LockTimeConversionError)?;
+            tx_builder.current_height(height.to_consensus_u32());
+        }
+        if let Some(locktime) = &self.locktime {
+            let bdk_locktime: BdkLockTime = locktime.try_into()?;
+            tx_builder.nlocktime(bdk_locktime);
+        }
+        if let Some(version) = self.version {
+            tx_builder.version(version);
+        }
+
+        let psbt: BdkPsbt = tx_builder.finish()?;
+        if let Some(change) = change {
+            wallet.mark_used(change.keychain, change.index);
+        }
+
+        Ok(Arc::new(psbt.into()))
+    }
+}
+
 /// Policy regarding the use of change outputs when creating a transaction.
 #[uniffi::remote(Enum)]
 pub enum ChangeSpendPolicy {
@@ -872,6 +1751,176 @@ pub enum CoinSelectionAlgorithm {
     SingleRandomDraw,
 }
 
+/// A coin selection algorithm implemented outside of this library. Set it on a transaction with
+/// [`TxBuilder::custom_coin_selection`].
+///
+/// The selector is called while the wallet is locked for building the transaction, so it must not
+/// call any method of the `Wallet` (or of builders using it) from `select`: doing so waits for the
+/// lock forever. Everything the selection needs is passed to `select`; read anything else from the
+/// wallet before calling `TxBuilder::finish`.
+#[uniffi::export(with_foreign)]
+pub trait CoinSelector: Send + Sync {
+    /// Choose which of the `optional_utxos` to spend, returning their outpoints. The
+    /// `required_utxos` are always spent, whether they are returned or not.
+    ///
+    /// `target_amount` is the value to send plus the fee for the transaction without its inputs.
+    /// The selection is valid if the effective values of the required and selected outputs add up
+    /// to at least `target_amount`. Any excess goes to a change output paying to `drain_script` if
+    /// it is not dust, and to fees otherwise.
+    fn select(
+        &self,
+        required_utxos: Vec<CoinSelectionCandidate>,
+        optional_utxos: Vec<CoinSelectionCandidate>,
+        fee_rate: Arc<FeeRate>,
+        target_amount: Arc<Amount>,
+        drain_script: Arc<Script>,
+    ) -> Result<Vec<OutPoint>, CoinSelectionError>;
+}
+
+/// An output that a [`CoinSelector`] can spend.
+#[derive(Debug, Clone, uniffi::Record)]
+pub struct CoinSelectionCandidate {
+    /// The location of the output.
+    pub outpoint: OutPoint,
+    /// The output.
+    pub txout: TxOut,
+    /// The keychain of the output, or `None` for a foreign UTXO.
+    pub keychain: Option<KeychainKind>,
+    /// The position of the transaction holding the output, or `None` for a foreign UTXO.
+    pub chain_position: Option<ChainPosition>,
+    /// The weight of the witness data and `scriptSig` needed to spend the output, in weight units.
+    pub satisfaction_weight: u64,
+    /// The value of the output minus the fee for spending it at the transaction's fee rate, in
+    /// satoshis. Negative when spending the output costs more than it is worth.
+    pub effective_value: i64,
+}
+
+/// Runs a [`CoinSelector`] as the coin selection algorithm of a `bdk_wallet::TxBuilder`.
+///
+/// Only `InsufficientFunds` can be returned from coin selection, so errors of the selector are
+/// kept aside for `TxBuilder::finish` to report.
+struct ForeignCoinSelection {
+    selector: Arc<dyn CoinSelector>,
+    error: Arc<Mutex<Option<CoinSelectionError>>>,
+}
+
+impl ForeignCoinSelection {
+    fn new(selector: Arc<dyn CoinSelector>) -> Self {
+        ForeignCoinSelection {
+            selector,
+            error: Arc::new(Mutex::new(None)),
+        }
+    }
+}
+
+impl fmt::Debug for ForeignCoinSelection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ForeignCoinSelection")
+            .finish_non_exhaustive()
+    }
+}
+
+impl BdkCoinSelectionAlgorithm for ForeignCoinSelection {
+    fn coin_select<R: RngCore>(
+        &self,
+        required_utxos: Vec<BdkWeightedUtxo>,
+        optional_utxos: Vec<BdkWeightedUtxo>,
+        fee_rate: BdkFeeRate,
+        target_amount: BdkAmount,
+        drain_script: &BdkScript,
+        _: &mut R,
+    ) -> Result<BdkCoinSelectionResult, BdkInsufficientFunds> {
+        let input_fee = |utxo: &BdkWeightedUtxo| {
+            fee_rate * (BdkTxIn::default().segwit_weight() + utxo.satisfaction_weight)
+        };
+        let candidate = |utxo: &BdkWeightedUtxo| {
+            let (keychain, chain_position) = match &utxo.utxo {
+                BdkUtxo::Local(output) => (Some(output.keychain), Some(output.chain_position)),
+                BdkUtxo::Foreign { .. } => (None, None),
+            };
+            let effective_value = utxo
+                .utxo
+                .txout()
+                .value
+                .to_signed()
+                .unwrap_or(BdkSignedAmount::MAX)
+                - input_fee(utxo).to_signed().unwrap_or(BdkSignedAmount::MAX);
+            CoinSelectionCandidate {
+                outpoint: OutPoint::from(&utxo.utxo.outpoint()),
+                txout: utxo.utxo.txout().into(),
+                keychain,
+                chain_position: chain_position.map(ChainPosition::from),
+                satisfaction_weight: utxo.satisfaction_weight.to_wu(),
+                effective_value: effective_value.to_sat(),
+            }
+        };
+        let insufficient_funds = BdkInsufficientFunds {
+            needed: target_amount,
+            available: BdkAmount::ZERO,
+        };
+
+        let selection = self.selector.select(
+            required_utxos.iter().map(candidate).collect(),
+            optional_utxos.iter().map(candidate).collect(),
+            Arc::new(FeeRate(fee_rate)),
+            Arc::new(Amount(target_amount)),
+            Arc::new(Script(drain_script.to_owned())),
+        );
+        let selection = match selection {
+            Ok(selection) => selection
+                .into_iter()
+                .map(BdkOutPoint::from)
+                .collect::<HashSet<_>>(),
+            Err(error) => {
+                *self.error.lock().unwrap() = Some(error);
+                return Err(insufficient_funds);
+            }
+        };
+        let required_outpoints = required_utxos
+            .iter()
+            .map(|utxo| utxo.utxo.outpoint())
+            .collect::<HashSet<_>>();
+        if let Some(unknown) = selection.iter().find(|outpoint| {
+            !required_outpoints.contains(outpoint)
+                && !optional_utxos
+                    .iter()
+                    .any(|utxo| utxo.utxo.outpoint() == **outpoint)
+        }) {
+            *self.error.lock().unwrap() = Some(CoinSelectionError::Reason {
+                error_message: format!("selected outpoint {unknown} is not a candidate"),
+            });
+            return Err(insufficient_funds);
+        }
+
+        let selected = required_utxos
+            .into_iter()
+            .chain(
+                optional_utxos
+                    .into_iter()
+                    .filter(|utxo| selection.contains(&utxo.utxo.outpoint())),
+            )
+            .collect::<Vec<_>>();
+        let fee_amount = selected.iter().map(input_fee).sum::<BdkAmount>();
+        let selected_amount = selected
+            .iter()
+            .map(|utxo| utxo.utxo.txout().value)
+            .sum::<BdkAmount>();
+        let needed = target_amount + fee_amount;
+        if selected_amount < needed {
+            return Err(BdkInsufficientFunds {
+                needed,
+                available: selected_amount,
+            });
+        }
+
+        Ok(BdkCoinSelectionResult {
+            selected: selected.into_iter().map(|utxo| utxo.utxo).collect(),
+            fee_amount,
+            excess: bdk_decide_change(selected_amount - needed, fee_rate, drain_script),
+        })
+    }
+}
+
 /// Ordering of the transaction's inputs and outputs.
 #[derive(Clone, Copy, Debug, Default, uniffi::Enum)]
 pub enum TxOrdering {
@@ -897,6 +1946,214 @@ impl From<TxOrdering> for BdkTxOrdering {
     }
 }
 
+/// The version of the format of transaction builder drafts, increased on incompatible changes.
+const DRAFT_VERSION: u32 = 1;
+
+/// The saved state of a [`TxBuilder`]. Scripts and data are hex encoded, amounts are in
+/// satoshis and fee rates in satoshis per 1000 weight units.
+#[derive(Serialize, Deserialize)]
+#[serde(crate = "bdk_wallet::serde")]
+struct TxBuilderDraft {
+    draft_version: u32,
+    add_global_xpubs: bool,
+    recipients: Vec<RecipientDraft>,
+    recipient_addresses: Vec<String>,
+    utxos: Vec<String>,
+    unspendable: Vec<String>,
+    internal_policy_path: Option<BTreeMap<String, Vec<usize>>>,
+    external_policy_path: Option<BTreeMap<String, Vec<usize>>>,
+    change_policy: String,
+    manually_selected_only: bool,
+    fee_rate: Option<u64>,
+    fee_absolute: Option<u64>,
+    drain_wallet: bool,
+    drain_to: Option<String>,
+    sequence: Option<u32>,
+    data: String,
+    current_height: Option<u32>,
+    locktime: Option<u32>,
+    allow_dust: bool,
+    version: Option<i32>,
+    sighash: Option<String>,
+    ordering: String,
+    exclude_unconfirmed: bool,
+    exclude_below_confirmations: Option<u32>,
+    only_witness_utxo: bool,
+    foreign_utxos: Vec<ForeignUtxoDraft>,
+    coin_selection: Option<String>,
+    fee_paying_recipients: Vec<u64>,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(crate = "bdk_wallet::serde")]
+struct RecipientDraft {
+    script: String,
+    amount: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(crate = "bdk_wallet::serde")]
+struct ForeignUtxoDraft {
+    outpoint: String,
+    /// A PSBT with the input as its only input, hex encoded.
+    psbt_input: String,
+    satisfaction_weight: u64,
+    sequence: Option<u32>,
+}
+
+/// The saved state of a [`BumpFeeTxBuilder`].
+#[derive(Serialize, Deserialize)]
+#[serde(crate = "bdk_wallet::serde")]
+struct BumpFeeTxBuilderDraft {
+    draft_version: u32,
+    txid: String,
+    fee_rate: u64,
+    sequence: Option<u32>,
+    current_height: Option<u32>,
+    locktime: Option<u32>,
+    allow_dust: bool,
+    version: Option<i32>,
+    sighash: Option<String>,
+    ordering: String,
+}
+
+fn parse_draft<T: DeserializeOwned>(draft: &str) -> Result<T, TxDraftError> {
+    #[derive(Deserialize)]
+    #[serde(crate = "bdk_wallet::serde")]
+    struct Versioned {
+        draft_version: u32,
+    }
+
+    let invalid = |error: serde_json::Error| TxDraftError::InvalidDraft {
+        error_message: error.to_string(),
+    };
+    let Versioned { draft_version } = serde_json::from_str(draft).map_err(invalid)?;
+    if draft_version != DRAFT_VERSION {
+        return Err(TxDraftError::UnsupportedVersion {
+            version: draft_version,
+        });
+    }
+    serde_json::from_str(draft).map_err(invalid)
+}
+
+fn write_draft<T: Serialize>(draft: &T) -> Result<String, TxDraftError> {
+    serde_json::to_string(draft).map_err(|error| TxDraftError::InvalidDraft {
+        error_message: error.to_string(),
+    })
+}
+
+fn parse_field<T, E: fmt::Display>(
+    value: &str,
+    parse: impl FnOnce(&str) -> Result<T, E>,
+) -> Result<T, TxDraftError> {
+    parse(value).map_err(|error| TxDraftError::InvalidDraft {
+        error_message: format!("{value}: {error}"),
+    })
+}
+
+fn invalid_field(field: &str, value: &str) -> TxDraftError {
+    TxDraftError::InvalidDraft {
+        error_message: format!("unknown {field} {value}"),
+    }
+}
+
+// The names of enum values in drafts, used in both directions so that the format does not depend
+// on `Debug` output.
+
+fn change_policy_name(policy: ChangeSpendPolicy) -> &'static str {
+    match policy {
+        ChangeSpendPolicy::ChangeAllowed => "ChangeAllowed",
+        ChangeSpendPolicy::OnlyChange => "OnlyChange",
+        ChangeSpendPolicy::ChangeForbidden => "ChangeForbidden",
+    }
+}
+
+fn parse_change_policy(name: &str) -> Result<ChangeSpendPolicy, TxDraftError> {
+    [
+        ChangeSpendPolicy::ChangeAllowed,
+        ChangeSpendPolicy::OnlyChange,
+        ChangeSpendPolicy::ChangeForbidden,
+    ]
+    .iter()
+    .copied()
+    .find(|policy| change_policy_name(*policy) == name)
+    .ok_or_else(|| invalid_field("change policy", name))
+}
+
+fn ordering_name(ordering: TxOrdering) -> &'static str {
+    match ordering {
+        TxOrdering::Shuffle => "Shuffle",
+        TxOrdering::Untouched => "Untouched",
+    }
+}
+
+fn parse_ordering(name: &str) -> Result<TxOrdering, TxDraftError> {
+    [TxOrdering::Shuffle, TxOrdering::Untouched]
+        .iter()
+        .copied()
+        .find(|ordering| ordering_name(*ordering) == name)
+        .ok_or_else(|| invalid_field("ordering", name))
+}
+
+fn coin_selection_name(algorithm: CoinSelectionAlgorithm) -> &'static str {
+    match algorithm {
+        CoinSelectionAlgorithm::BranchAndBound => "BranchAndBound",
+        CoinSelectionAlgorithm::LargestFirst => "LargestFirst",
+        CoinSelectionAlgorithm::OldestFirst => "OldestFirst",
+        CoinSelectionAlgorithm::SingleRandomDraw => "SingleRandomDraw",
+    }
+}
+
+fn parse_coin_selection(name: &str) -> Result<CoinSelectionAlgorithm, TxDraftError> {
+    [
+        CoinSelectionAlgorithm::BranchAndBound,
+        CoinSelectionAlgorithm::LargestFirst,
+        CoinSelectionAlgorithm::OldestFirst,
+        CoinSelectionAlgorithm::SingleRandomDraw,
+    ]
+    .iter()
+    .copied()
+    .find(|algorithm| coin_selection_name(*algorithm) == name)
+    .ok_or_else(|| invalid_field("coin selection algorithm", name))
+}
+
+/// PSBT inputs have no encoding of their own, so an input is saved as a PSBT spending only its
+/// outpoint.
+fn psbt_input_to_hex(outpoint: &BdkOutPoint, psbt_input: &BdkInput) -> String {
+    let unsigned_tx = BdkTransaction {
+        version: BdkVersion::TWO,
+        lock_time: BdkLockTime::ZERO,
+        input: vec![BdkTxIn {
+            previous_output: *outpoint,
+            ..Default::default()
+        }],
+        output: Vec::new(),
+    };
+    let mut psbt = BdkPsbt::from_unsigned_tx(unsigned_tx).expect("unsigned transaction");
+    psbt.inputs[0] = psbt_input.clone();
+    psbt.serialize_hex()
+}
+
+fn parse_psbt_input(psbt_input: &str) -> Result<BdkInput, TxDraftError> {
+    let mut psbt = parse_field(psbt_input, |psbt| {
+        Vec::<u8>::from_hex(psbt)
+            .map_err(|error| error.to_string())
+            .and_then(|bytes| BdkPsbt::deserialize(&bytes).map_err(|error| error.to_string()))
+    })?;
+    if psbt.inputs.len() != 1 {
+        return Err(invalid_field("psbt input", psbt_input));
+    }
+    Ok(psbt.inputs.remove(0))
+}
+
+fn locktime_to_draft(locktime: &LockTime) -> Result<u32, TxDraftError> {
+    BdkLockTime::try_from(locktime)
+        .map(|locktime| locktime.to_consensus_u32())
+        .map_err(|error| TxDraftError::InvalidDraft {
+            error_message: error.to_string(),
+        })
+}
+
 fn parse_sighash_type(sighash: &str) -> Result<BdkPsbtSighashType, SighashParseError> {
     BdkPsbtSighashType::from_str(sighash).map_err(|error| SighashParseError::Invalid {
         error_message: error.to_string(),
@@ -1,9 +1,12 @@
+use crate::bitcoin::DescriptorType;
 use crate::bitcoin::{
     Address, Amount, BlockHash, DescriptorId, FeeRate, HashableOutPoint, OutPoint, Script,
     Transaction, TxOut, Txid,
 };
 use crate::descriptor::Descriptor;
 use crate::error::{CreateTxError, RequestBuilderError};
+use crate::keys::{DerivationPath, DescriptorPublicKey};
+use crate::tx_builder::TxBuilder;
 
 use bdk_wallet::bitcoin::absolute::LockTime as BdkLockTime;
 use bdk_wallet::chain::spk_client::SyncItem;
@@ -66,6 +69,22 @@ pub enum WordCount {
     Words24,
 }
 
+type Language = bdk_wallet::keys::bip39::Language;
+
+#[uniffi::remote(Enum)]
+pub enum Language {
+    English,
+    SimplifiedChinese,
+    TraditionalChinese,
+    Czech,
+    French,
+    Italian,
+    Japanese,
+    Korean,
+    Portuguese,
+    Spanish,
+}
+
 /// Represents the observed position of some chain data.
 #[derive(Debug, uniffi::Enum, Clone)]
 pub enum ChainPosition {
@@ -179,6 +198,46 @@ impl From<BdkCanonicalTx<'_, Arc<BdkTransaction>, BdkConfirmationBlockTime>> for
     }
 }
 
+/// A consolidation transaction proposed by `Wallet::plan_consolidation`.
+#[derive(uniffi::Record)]
+pub struct ConsolidationProposal {
+    /// The outputs spent by the consolidation transaction.
+    pub utxos: Vec<LocalOutput>,
+    /// The estimated weight of the signed transaction, in weight units.
+    pub weight: u64,
+    /// The estimated fee paid by the transaction at the current fee rate.
+    pub fee: Arc<Amount>,
+    /// How much less is paid in fees by consolidating now than by spending each output
+    /// separately at the future fee rate.
+    pub savings: Arc<Amount>,
+    /// A transaction builder spending exactly these outputs to a change address of the wallet at
+    /// the current fee rate. Call `finish` on it to get the PSBT.
+    pub tx_builder: Arc<TxBuilder>,
+}
+
+/// A preview of the transaction a `TxBuilder` would create, returned by `TxBuilder::preview`.
+#[derive(uniffi::Record)]
+pub struct TxPreview {
+    /// The outputs selected to be spent by the transaction.
+    pub utxos: Vec<OutPoint>,
+    /// The fee paid by the transaction.
+    pub fee: Arc<Amount>,
+    /// The fee rate of the transaction, based on its estimated weight once signed.
+    pub fee_rate: Arc<FeeRate>,
+    /// The estimated weight of the signed transaction, in weight units.
+    pub weight: u64,
+    /// The amount sent back to the wallet as change, if any.
+    pub change: Option<Arc<Amount>>,
+    /// The waste metric of the selection, in satoshis: the extra fee paid for spending the inputs
+    /// now rather than at the long-term fee rate, plus either the cost of creating and later
+    /// spending the change output, or the excess paid as fee when there is no change. Lower is
+    /// better, and negative when spending now is cheaper.
+    pub waste: i64,
+    /// The largest amount the first recipient could receive with the same options, by spending
+    /// every available output. Without recipients, the largest amount the wallet can send.
+    pub max_sendable: Arc<Amount>,
+}
+
 /// A bitcoin script and associated amount.
 #[derive(uniffi::Record)]
 pub struct ScriptAmount {
@@ -188,6 +247,15 @@ pub struct ScriptAmount {
     pub amount: Arc<Amount>,
 }
 
+/// An address and the amount to pay to it.
+#[derive(uniffi::Record)]
+pub struct AddressAmount {
+    /// The address paid.
+    pub address: Arc<Address>,
+    /// The amount paid to the address.
+    pub amount: Arc<Amount>,
+}
+
 /// A derived address and the index it was found at.
 #[derive(uniffi::Record)]
 pub struct AddressInfo {
@@ -1412,13 +1480,13 @@ impl From<bdk_wallet::ChangeSet> for ChangeSet {
         let descriptor = value.descriptor.map(|d| {
             Arc::new(Descriptor {
                 extended_descriptor: d,
-                key_map: BTreeMap::new(),
+                key_map: BTreeMap::new().into(),
             })
         });
         let change_descriptor = value.change_descriptor.map(|d| {
             Arc::new(Descriptor {
                 extended_descriptor: d,
-                key_map: BTreeMap::new(),
+                key_map: BTreeMap::new().into(),
             })
         });
         let network = value.network;
@@ -1497,3 +1565,164 @@ impl From<WildcardType> for Wildcard {
         }
     }
 }
+
+/// The contents of a descriptor, as returned by `Descriptor::analyze`.
+#[derive(uniffi::Record)]
+pub struct DescriptorAnalysis {
+    /// The type of the descriptor.
+    pub desc_type: DescriptorType,
+    /// Every key appearing in the descriptor.
+    pub keys: Vec<DescriptorKeyInfo>,
+    /// The absolute timelocks (`after`) appearing in the descriptor.
+    pub absolute_timelocks: Vec<LockTime>,
+    /// The relative timelocks (`older`), as nSequence consensus values.
+    pub relative_timelocks: Vec<u32>,
+    /// The hashlocks appearing in the descriptor.
+    pub hashlocks: Vec<Hashlock>,
+    /// The threshold structure of the spending policy. The first node is the root, nested
+    /// thresholds are referenced by their index in this list.
+    pub policy: Vec<ThresholdNode>,
+    /// The internal key of a taproot descriptor.
+    pub taproot_internal_key: Option<String>,
+    /// The script leaves of a taproot descriptor.
+    pub taproot_leaves: Vec<TaprootLeaf>,
+    /// The minimal sets of requirements to spend an output of the descriptor, or `None` if the
+    /// descriptor has hardened wildcards and its keys cannot be derived to plan the spends.
+    pub spending_paths: Option<Vec<SatisfactionPath>>,
+}
+
+/// A key of a descriptor.
+#[derive(uniffi::Record)]
+pub struct DescriptorKeyInfo {
+    /// The key, as it appears in the descriptor.
+    pub key: Arc<DescriptorPublicKey>,
+    /// The fingerprint of the master key, taken from the key origin when present.
+    pub master_fingerprint: String,
+    /// The derivation path from the master key to the key, if the origin is known.
+    pub origin_path: Option<Arc<DerivationPath>>,
+    /// The derivation paths appended to an extended key, more than one for multipath keys.
+    pub derivation_paths: Vec<Arc<DerivationPath>>,
+    /// The wildcard at the end of the derivation path of an extended key.
+    pub wildcard: Option<WildcardType>,
+}
+
+/// A hash function used in a hashlock.
+#[derive(Debug, Clone, uniffi::Enum)]
+pub enum HashFunction {
+    /// A single SHA256.
+    Sha256,
+    /// A double SHA256.
+    Hash256,
+    /// A single RIPEMD160.
+    Ripemd160,
+    /// A RIPEMD160 of a SHA256.
+    Hash160,
+}
+
+/// A hash whose preimage needs to be revealed to spend.
+#[derive(Debug, Clone, uniffi::Record)]
+pub struct Hashlock {
+    /// The hash function.
+    pub function: HashFunction,
+    /// The hex encoded hash.
+    pub hash: String,
+}
+
+/// A threshold of the spending policy of a descriptor: `threshold` of the `items` need to be
+/// satisfied.
+#[derive(uniffi::Record)]
+pub struct ThresholdNode {
+    /// The number of items that need to be satisfied.
+    pub threshold: u32,
+    /// The items of the threshold.
+    pub items: Vec<PolicyItem>,
+}
+
+/// A condition in the spending policy of a descriptor.
+#[derive(Debug, uniffi::Enum)]
+pub enum PolicyItem {
+    /// A signature from the key.
+    Key { key: String },
+    /// An absolute timelock.
+    AbsoluteTimelock { lock_time: LockTime },
+    /// A relative timelock, as an nSequence consensus value.
+    RelativeTimelock { sequence: u32 },
+    /// The preimage of a hash.
+    Hashlock { hashlock: Hashlock },
+    /// The nested threshold at this index of `DescriptorAnalysis::policy`.
+    Threshold { index: u32 },
+    /// A condition that is always satisfied.
+    Trivial,
+    /// A condition that can never be satisfied.
+    Unsatisfiable,
+}
+
+/// A leaf of a taproot script tree.
+#[derive(uniffi::Record)]
+pub struct TaprootLeaf {
+    /// The depth of the leaf in the tree.
+    pub depth: u8,
+    /// The miniscript of the leaf.
+    pub miniscript: String,
+}
+
+/// A minimal set of requirements to spend an output of a descriptor.
+#[derive(uniffi::Record)]
+pub struct SatisfactionPath {
+    /// The keys that need to sign.
+    pub keys: Vec<String>,
+    /// The absolute timelock the spending transaction must satisfy.
+    pub absolute_timelock: Option<LockTime>,
+    /// The relative timelock the spending input must satisfy, as an nSequence consensus value.
+    pub relative_timelock: Option<u32>,
+    /// The hashes whose preimages need to be revealed.
+    pub hashlocks: Vec<Hashlock>,
+    /// The maximum weight of the satisfaction of the input (witness and script sig) in weight
+    /// units.
+    pub satisfaction_weight: u64,
+}
+
+/// The script types of BIP-48 multisig accounts, the last hardened step of their derivation path.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, uniffi::Enum)]
+pub enum Bip48ScriptType {
+    /// Nested segwit (`sh(wsh(...))`), derived at `m/48'/coin'/account'/1'`.
+    P2shP2wsh,
+    /// Native segwit (`wsh(...)`), derived at `m/48'/coin'/account'/2'`.
+    P2wsh,
+}
+
+impl Bip48ScriptType {
+    pub(crate) fn index(&self) -> u32 {
+        match self {
+            Bip48ScriptType::P2shP2wsh => 1,
+            Bip48ScriptType::P2wsh => 2,
+        }
+    }
+}
+
+/// The account key of a multisig participant, in the form usually exchanged between
+/// coordinators and hardware signers.
+#[derive(Debug, Clone, uniffi::Record)]
+pub struct Cosigner {
+    /// The fingerprint of the master key of the cosigner.
+    pub master_fingerprint: String,
+    /// The derivation path from the master key to the account key.
+    pub derivation_path: Arc<DerivationPath>,
+    /// The extended public key of the account.
+    pub xpub: String,
+}
+
+/// The script types implied by the SLIP-132 versions of extended keys.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, uniffi::Enum)]
+pub enum Slip132ScriptType {
+    /// Legacy keys (`xpub`/`tpub`).
+    P2pkh,
+    /// Nested segwit single-sig keys (`ypub`/`upub`).
+    P2shP2wpkh,
+    /// Native segwit single-sig keys (`zpub`/`vpub`).
+    P2wpkh,
+    /// Nested segwit multisig keys (`Ypub`/`Upub`).
+    P2shP2wsh,
+    /// Native segwit multisig keys (`Zpub`/`Vpub`).
+    P2wsh,
+}
@@ -3,28 +3,64 @@ use crate::bitcoin::{
 };
 use crate::descriptor::Descriptor;
 use crate::error::{
-    CalculateFeeError, CannotConnectError, CreateWithPersistError, DescriptorError,
-    LoadWithPersistError, PersistenceError, SignerError, TxidParseError,
+    CalculateFeeError, CannotConnectError, ConsolidationError, CreateWithPersistError,
+    DescriptorError, LoadWithPersistError, PersistenceError, SignerError, TxidParseError,
 };
+use crate::privacy::{self, PrivacyReport};
 use crate::signer::SignersContainer;
+use crate::spending_path::{self, SpendingPath};
 use crate::store::{PersistenceType, Persister};
+use crate::tx_builder::TxBuilder;
 use crate::types::{
-    AddressInfo, Balance, BlockId, CanonicalTx, ChangeSet, EvictedTx, FullScanRequestBuilder,
-    KeychainAndIndex, KeychainKind, LocalOutput, Policy, SentAndReceivedValues, SignOptions,
-    SyncRequestBuilder, UnconfirmedTx, Update, WalletEvent, WalletKeychain,
+    AddressInfo, Balance, BlockId, CanonicalTx, ChangeSet, ConsolidationProposal, EvictedTx,
+    FullScanRequestBuilder, KeychainAndIndex, KeychainKind, LocalOutput, Policy,
+    SentAndReceivedValues, SignOptions, SyncRequestBuilder, UnconfirmedTx, Update, WalletEvent,
+    WalletKeychain,
 };
 
-use bdk_wallet::bitcoin::Network;
+use bdk_wallet::bitcoin::{
+    Amount as BdkAmount, Network, Transaction as BdkTransaction, TxIn as BdkTxIn,
+    TxOut as BdkTxOut, VarInt, Weight as BdkWeight,
+};
 use bdk_wallet::keys::KeyMap;
 #[allow(deprecated)]
 use bdk_wallet::signer::SignOptions as BdkSignOptions;
 use bdk_wallet::{
     CreateParams as BdkCreateParams, LoadParams as BdkLoadParams, PersistedWallet,
-    Wallet as BdkWallet,
+    Update as BdkUpdate, Wallet as BdkWallet,
 };
 
+use std::collections::{BTreeMap, HashMap};
 use std::ops::DerefMut;
-use std::sync::{Arc, Mutex, MutexGuard};
+use std::sync::{Arc, Mutex, RwLock, RwLockReadGuard, RwLockWriteGuard};
+
+/// The eviction timestamp recorded for cancelled transactions. No `last_seen` value can be greater
+/// than this, so a cancelled transaction can only become canonical again by confirming.
+pub(crate) const CANCELLED_AT: u64 = u64::MAX;
+
+/// The lowest revealed index of `keychain` whose outputs all belong to transactions cancelled with
+/// [`Wallet::cancel_tx`], if it comes before the first unused index.
+///
+/// Such an index cannot be marked as unused again, since the outputs of the cancelled transactions
+/// stay indexed, so it is looked up from the transaction graph instead.
+pub(crate) fn released_index(wallet: &BdkWallet, keychain: KeychainKind) -> Option<u32> {
+    let index = wallet.spk_index();
+    let first_unused = index.unused_keychain_spks(keychain).next().map(|(i, _)| i);
+    let graph = wallet.tx_graph();
+    let mut released: BTreeMap<u32, bool> = BTreeMap::new();
+    for (i, outpoint) in index.keychain_outpoints(keychain) {
+        if first_unused.is_some_and(|first_unused| i >= first_unused) {
+            break;
+        }
+        let cancelled = graph.get_last_evicted(outpoint.txid) == Some(CANCELLED_AT)
+            && wallet.get_tx(outpoint.txid).is_none();
+        *released.entry(i).or_insert(true) &= cancelled;
+    }
+    released
+        .into_iter()
+        .find(|(_, released)| *released)
+        .map(|(i, _)| i)
+}
 
 /// A Bitcoin wallet.
 ///
@@ -38,9 +74,13 @@ use std::sync::{Arc, Mutex, MutexGuard};
 ///
 /// The Wallet descriptor (external) and change descriptor (internal) must not derive the same
 /// script pubkeys. See KeychainTxOutIndex::insert_descriptor() for more details.
+///
+/// The Wallet can be shared between threads. Methods that only read wallet state, such as
+/// `balance`, `list_unspent` or `sign`, can run concurrently with each other, while methods that
+/// modify it, such as `apply_update` or `reveal_next_address`, wait for exclusive access.
 #[derive(uniffi::Object)]
 pub struct Wallet {
-    inner_mutex: Mutex<PersistedWallet<PersistenceType>>,
+    inner: RwLock<PersistedWallet<PersistenceType>>,
 }
 
 /// Parameters for `Wallet` creation.
@@ -162,7 +202,7 @@ impl Wallet {
             .map_err(CreateWithPersistError::from)?;
 
         Ok(Wallet {
-            inner_mutex: Mutex::new(wallet),
+            inner: RwLock::new(wallet),
         })
     }
 
@@ -221,7 +261,7 @@ impl Wallet {
             .map_err(CreateWithPersistError::from)?;
 
         Ok(Wallet {
-            inner_mutex: Mutex::new(wallet),
+            inner: RwLock::new(wallet),
         })
     }
 
@@ -276,7 +316,7 @@ impl Wallet {
             .map_err(CreateWithPersistError::from)?;
 
         Ok(Wallet {
-            inner_mutex: Mutex::new(wallet),
+            inner: RwLock::new(wallet),
         })
     }
 
@@ -325,7 +365,7 @@ impl Wallet {
             .ok_or(LoadWithPersistError::CouldNotLoad)?;
 
         Ok(Wallet {
-            inner_mutex: Mutex::new(wallet),
+            inner: RwLock::new(wallet),
         })
     }
 
@@ -374,7 +414,7 @@ impl Wallet {
             .ok_or(LoadWithPersistError::CouldNotLoad)?;
 
         Ok(Wallet {
-            inner_mutex: Mutex::new(wallet),
+            inner: RwLock::new(wallet),
         })
     }
 
@@ -414,7 +454,7 @@ impl Wallet {
             .ok_or(LoadWithPersistError::CouldNotLoad)?;
 
         Ok(Wallet {
-            inner_mutex: Mutex::new(wallet),
+            inner: RwLock::new(wallet),
         })
     }
 
@@ -445,7 +485,7 @@ impl Wallet {
     /// index defined in [BIP32](https://github.com/bitcoin/bips/blob/master/bip-0032.mediawiki),
     /// then the last revealed address will be returned.
     pub fn reveal_next_address(&self, keychain: KeychainKind) -> AddressInfo {
-        self.get_wallet().reveal_next_address(keychain).into()
+        self.get_wallet_mut().reveal_next_address(keychain).into()
     }
 
     /// Peek an address of the given `keychain` at `index` without revealing it.
@@ -467,7 +507,8 @@ impl Wallet {
     }
 
     /// Get the next unused address for the given `keychain`, i.e. the address with the lowest
-    /// derivation index that hasn't been used in a transaction.
+    /// derivation index that hasn't been used in a transaction. Addresses only used by
+    /// transactions abandoned with [`Wallet::cancel_tx`] count as unused.
     ///
     /// This will attempt to reveal a new address if all previously revealed addresses have
     /// been used, in which case the returned address will be the same as calling [`Wallet::reveal_next_address`].
@@ -475,14 +516,18 @@ impl Wallet {
     /// **WARNING**: To avoid address reuse you must persist the changes resulting from one or more
     /// calls to this method before closing the wallet. See [`Wallet::reveal_next_address`].
     pub fn next_unused_address(&self, keychain: KeychainKind) -> AddressInfo {
-        self.get_wallet().next_unused_address(keychain).into()
+        let mut wallet = self.get_wallet_mut();
+        match released_index(&wallet, keychain) {
+            Some(index) => wallet.peek_address(keychain, index).into(),
+            None => wallet.next_unused_address(keychain).into(),
+        }
     }
 
     /// Marks an address used of the given `keychain` at `index`.
     ///
     /// Returns whether the given index was present and then removed from the unused set.
     pub fn mark_used(&self, keychain: KeychainKind, index: u32) -> bool {
-        self.get_wallet().mark_used(keychain, index)
+        self.get_wallet_mut().mark_used(keychain, index)
     }
 
     /// Undoes the effect of [`mark_used`] and returns whether the `index` was inserted
@@ -494,7 +539,7 @@ impl Wallet {
     ///
     /// [`mark_used`]: Self::mark_used
     pub fn unmark_used(&self, keychain: KeychainKind, index: u32) -> bool {
-        self.get_wallet().unmark_used(keychain, index)
+        self.get_wallet_mut().unmark_used(keychain, index)
     }
 
     /// Reveal addresses up to and including the target `index` and return an iterator
@@ -507,7 +552,7 @@ impl Wallet {
     /// **WARNING**: To avoid address reuse you must persist the changes resulting from one or more
     /// calls to this method before closing the wallet. See [`Wallet::reveal_next_address`].
     pub fn reveal_addresses_to(&self, keychain: KeychainKind, index: u32) -> Vec<AddressInfo> {
-        self.get_wallet()
+        self.get_wallet_mut()
             .reveal_addresses_to(keychain, index)
             .map(|address_info| address_info.into())
             .collect()
@@ -533,7 +578,7 @@ impl Wallet {
     /// After applying updates you should persist the staged wallet changes. For an example of how
     /// to persist staged wallet changes see [`Wallet::reveal_next_address`].
     pub fn apply_update(&self, update: Arc<Update>) -> Result<(), CannotConnectError> {
-        self.get_wallet()
+        self.get_wallet_mut()
             .apply_update(update.0.clone())
             .map_err(CannotConnectError::from)
     }
@@ -550,7 +595,7 @@ impl Wallet {
         &self,
         update: Arc<Update>,
     ) -> Result<Vec<WalletEvent>, CannotConnectError> {
-        match self.get_wallet().apply_update_events(update.0.clone()) {
+        match self.get_wallet_mut().apply_update_events(update.0.clone()) {
             Ok(events) => Ok(events.into_iter().map(|e| e.into()).collect()),
             Err(e) => Err(CannotConnectError::from(e)),
         }
@@ -559,7 +604,7 @@ impl Wallet {
     /// Apply relevant unconfirmed transactions to the wallet.
     /// Transactions that are not relevant are filtered out.
     pub fn apply_unconfirmed_txs(&self, unconfirmed_txs: Vec<UnconfirmedTx>) {
-        self.get_wallet().apply_unconfirmed_txs(
+        self.get_wallet_mut().apply_unconfirmed_txs(
             unconfirmed_txs
                 .into_iter()
                 .map(|utx| (Arc::new(utx.tx.as_ref().into()), utx.last_seen)),
@@ -578,7 +623,7 @@ impl Wallet {
         &self,
         unconfirmed_txs: Vec<UnconfirmedTx>,
     ) -> Vec<WalletEvent> {
-        self.get_wallet()
+        self.get_wallet_mut()
             .apply_unconfirmed_txs_events(
                 unconfirmed_txs
                     .into_iter()
@@ -595,7 +640,7 @@ impl Wallet {
     ///
     /// For more information: https://docs.rs/bdk_wallet/latest/bdk_wallet/struct.Wallet.html#method.apply_evicted_txs
     pub fn apply_evicted_txs(&self, evicted_txs: Vec<EvictedTx>) {
-        self.get_wallet().apply_evicted_txs(
+        self.get_wallet_mut().apply_evicted_txs(
             evicted_txs
                 .into_iter()
                 .map(|etx| (etx.txid.0, etx.evicted_at)),
@@ -612,7 +657,7 @@ impl Wallet {
     /// [`apply_evicted_txs`]: Self::apply_evicted_txs
     /// [`apply_update_events`]: Self::apply_update_events
     pub fn apply_evicted_txs_events(&self, evicted_txs: Vec<EvictedTx>) -> Vec<WalletEvent> {
-        self.get_wallet()
+        self.get_wallet_mut()
             .apply_evicted_txs_events(
                 evicted_txs
                     .into_iter()
@@ -644,6 +689,28 @@ impl Wallet {
             .map(|e| e.map(|p| Arc::new(p.into())))
     }
 
+    /// Enumerate the ways the outputs of a keychain can be spent, as described by
+    /// `Wallet::policies`, each with the keys that need to sign, the preimages to reveal and the
+    /// timelocks to satisfy.
+    ///
+    /// Timelocks are checked against the wallet's chain tip, time-based ones against
+    /// `current_time` (a unix timestamp in seconds). Pass the chosen path to
+    /// `TxBuilder::spending_path` to build a transaction spending through it.
+    ///
+    /// Fails with `DescriptorError::TooManySpendingPaths` for policies with too many paths to list,
+    /// such as large thresholds of keys.
+    pub fn spending_paths(
+        &self,
+        keychain: KeychainKind,
+        current_time: u64,
+    ) -> Result<Vec<SpendingPath>, DescriptorError> {
+        let wallet = self.get_wallet();
+        match wallet.policies(keychain).map_err(DescriptorError::from)? {
+            Some(policy) => spending_path::spending_paths(&wallet, keychain, &policy, current_time),
+            None => Ok(Vec::new()),
+        }
+    }
+
     /// Get the Bitcoin network the wallet is using.
     pub fn network(&self) -> Network {
         self.get_wallet().network()
@@ -658,7 +725,7 @@ impl Wallet {
                 keychain,
                 public_descriptor: Arc::new(Descriptor {
                     extended_descriptor: descriptor.clone(),
-                    key_map: KeyMap::default(),
+                    key_map: KeyMap::default().into(),
                 }),
             })
             .collect()
@@ -815,10 +882,59 @@ impl Wallet {
     /// [`list_unspent`]: Self::list_unspent
     /// [`list_output`]: Self::list_output
     pub fn insert_txout(&self, outpoint: OutPoint, txout: TxOut) {
-        self.get_wallet()
+        self.get_wallet_mut()
             .insert_txout(outpoint.into(), txout.into());
     }
 
+    /// Inserts a transaction into the wallet's transaction graph without any timestamp or anchor.
+    ///
+    /// This is meant for transactions built by this wallet that have not been broadcast yet. The
+    /// transaction is not considered canonical, so its inputs remain spendable and it does not
+    /// affect the balance until it is seen in the mempool (see [`apply_unconfirmed_txs`]) or
+    /// confirmed.
+    ///
+    /// Returns whether the transaction was not already in the wallet.
+    ///
+    /// [`apply_unconfirmed_txs`]: Self::apply_unconfirmed_txs
+    pub fn insert_tx(&self, tx: &Transaction) -> bool {
+        let tx: BdkTransaction = tx.into();
+        let mut wallet = self.get_wallet_mut();
+        if wallet.tx_graph().get_tx(tx.compute_txid()).is_some() {
+            return false;
+        }
+        let mut update = BdkUpdate::default();
+        update.tx_update.txs.push(Arc::new(tx));
+        wallet
+            .apply_update(update)
+            .expect("update without a chain update always connects");
+        true
+    }
+
+    /// Abandons a transaction built by this wallet, typically because broadcasting it failed.
+    ///
+    /// If the transaction is in the wallet's transaction graph it is marked as evicted so that it
+    /// is no longer canonical, releasing the outputs it spends, unless it is later confirmed. The
+    /// addresses of its outputs that were reserved by the wallet (such as the change address) are
+    /// released as well: [`Wallet::next_unused_address`] and [`TxBuilder`] hand them out again as
+    /// long as no other transaction pays to them.
+    pub fn cancel_tx(&self, tx: &Transaction) {
+        let tx: BdkTransaction = tx.into();
+        let txid = tx.compute_txid();
+        let mut wallet = self.get_wallet_mut();
+        if wallet.tx_graph().get_tx(txid).is_some() {
+            let mut update = BdkUpdate::default();
+            update.tx_update.evicted_ats.insert((txid, CANCELLED_AT));
+            wallet
+                .apply_update(update)
+                .expect("update without a chain update always connects");
+        }
+        for txout in &tx.output {
+            if let Some((keychain, index)) = wallet.derivation_of_spk(txout.script_pubkey.clone()) {
+                wallet.unmark_used(keychain, index);
+            }
+        }
+    }
+
     /// Calculates the fee of a given transaction. Returns [`Amount::ZERO`] if `tx` is a coinbase transaction.
     ///
     /// To calculate the fee for a [`Transaction`] with inputs not owned by this wallet you must
@@ -851,6 +967,132 @@ impl Wallet {
         self.get_wallet().list_unspent().map(|o| o.into()).collect()
     }
 
+    /// Propose transactions consolidating the wallet's confirmed unspent outputs, to spend them
+    /// now at `current_fee_rate` rather than later at the expected `future_fee_rate`.
+    ///
+    /// Outputs are grouped from the smallest value upwards, with at most `max_inputs` outputs per
+    /// transaction. Locked outputs, outputs worth more than `max_utxo_value` and outputs worth less
+    /// than the fee needed to spend them at the current fee rate are left alone. Only
+    /// transactions that save fees, accounting for spending the consolidated output later, are
+    /// proposed, so nothing is proposed when fee rates are not expected to rise.
+    ///
+    /// Each proposed transaction pays to a different unused change address of the wallet, revealing
+    /// new ones as needed. `max_inputs` must be at least 2.
+    #[uniffi::method(default(max_utxo_value = None, max_inputs = 500))]
+    pub fn plan_consolidation(
+        &self,
+        current_fee_rate: Arc<FeeRate>,
+        future_fee_rate: Arc<FeeRate>,
+        max_utxo_value: Option<Arc<Amount>>,
+        max_inputs: u32,
+    ) -> Result<Vec<ConsolidationProposal>, ConsolidationError> {
+        if max_inputs < 2 {
+            return Err(ConsolidationError::InvalidMaxInputs { max_inputs });
+        }
+        let mut wallet = self.get_wallet_mut();
+
+        let mut input_weights = HashMap::new();
+        let mut is_segwit = false;
+        for (keychain, descriptor) in wallet.keychains() {
+            let satisfaction_weight =
+                descriptor
+                    .max_weight_to_satisfy()
+                    .map_err(|e| ConsolidationError::Descriptor {
+                        error_message: e.to_string(),
+                    })?;
+            let mut weight = BdkTxIn::default().legacy_weight() + satisfaction_weight;
+            if descriptor.desc_type().segwit_version().is_some() {
+                // The count of witness elements.
+                weight += BdkWeight::from_wu(1);
+                is_segwit = true;
+            }
+            input_weights.insert(keychain, weight);
+        }
+        let fee = |fee_rate: &FeeRate, weight: BdkWeight| {
+            fee_rate.0.fee_wu(weight).unwrap_or(BdkAmount::MAX)
+        };
+
+        let mut candidates = wallet
+            .list_unspent()
+            .filter(|utxo| {
+                utxo.chain_position.is_confirmed()
+                    && !wallet.is_outpoint_locked(utxo.outpoint)
+                    && max_utxo_value
+                        .as_ref()
+                        .is_none_or(|max| utxo.txout.value <= max.0)
+                    && utxo.txout.value > fee(&current_fee_rate, input_weights[&utxo.keychain])
+            })
+            .collect::<Vec<_>>();
+        candidates.sort_by_key(|utxo| utxo.txout.value);
+
+        // Change addresses all have the same script type, so any of them gives the output weight.
+        let output = BdkTxOut {
+            value: BdkAmount::ZERO,
+            script_pubkey: wallet
+                .peek_address(KeychainKind::Internal, 0)
+                .script_pubkey(),
+        };
+        let consolidated_input_weight = *input_weights
+            .get(&KeychainKind::Internal)
+            .unwrap_or(&input_weights[&KeychainKind::External]);
+
+        let mut planned = Vec::new();
+        for utxos in candidates.chunks(max_inputs as usize) {
+            if utxos.len() < 2 {
+                continue;
+            }
+            let header_bytes = 4 + 4 + VarInt(utxos.len() as u64).size() + VarInt(1).size();
+            let mut weight = BdkWeight::from_vb_unchecked(header_bytes as u64) + output.weight();
+            if is_segwit {
+                // The segwit marker and flag.
+                weight += BdkWeight::from_wu(2);
+            }
+            let mut spend_later_fee = BdkAmount::ZERO;
+            for utxo in utxos {
+                let input_weight = input_weights[&utxo.keychain];
+                weight += input_weight;
+                spend_later_fee += fee(&future_fee_rate, input_weight);
+            }
+            let consolidation_fee = fee(&current_fee_rate, weight);
+            let savings = spend_later_fee
+                .checked_sub(consolidation_fee)
+                .and_then(|savings| {
+                    savings.checked_sub(fee(&future_fee_rate, consolidated_input_weight))
+                });
+            let savings = match savings {
+                Some(savings) if savings > BdkAmount::ZERO => savings,
+                _ => continue,
+            };
+
+            planned.push((utxos, weight, consolidation_fee, savings));
+        }
+
+        let mut unused_addresses = wallet
+            .list_unused_addresses(KeychainKind::Internal)
+            .collect::<Vec<_>>()
+            .into_iter();
+        let mut proposals = Vec::new();
+        for (utxos, weight, consolidation_fee, savings) in planned {
+            let drain_address = unused_addresses
+                .next()
+                .unwrap_or_else(|| wallet.reveal_next_address(KeychainKind::Internal));
+            let tx_builder = TxBuilder::new()
+                .add_utxos(utxos.iter().map(|utxo| utxo.outpoint.into()).collect())
+                .manually_selected_only()
+                .drain_to(&Script(drain_address.address.script_pubkey()))
+                .fee_rate(&current_fee_rate);
+            proposals.push(ConsolidationProposal {
+                utxos: utxos.iter().cloned().map(LocalOutput::from).collect(),
+                weight: weight.to_wu(),
+                fee: Arc::new(consolidation_fee.into()),
+                savings: Arc::new(savings.into()),
+                tx_builder,
+            });
+        }
+
+        Ok(proposals)
+    }
+
     /// List the locked outpoints.
     pub fn list_locked_outpoints(&self) -> Vec<OutPoint> {
         self.get_wallet()
@@ -880,14 +1122,14 @@ impl Wallet {
     /// **You must persist the staged change for the lock status to be persistent**. To unlock a
     /// previously locked outpoint, see `Wallet::unlock_outpoint`.
     pub fn lock_outpoint(&self, outpoint: OutPoint) {
-        self.get_wallet().lock_outpoint(outpoint.into());
+        self.get_wallet_mut().lock_outpoint(outpoint.into());
     }
 
     /// Unlock the wallet output of the specified `outpoint`.
     ///
     /// **You must persist the staged change for the lock status to be persistent**.
     pub fn unlock_outpoint(&self, outpoint: OutPoint) {
-        self.get_wallet().unlock_outpoint(outpoint.into());
+        self.get_wallet_mut().unlock_outpoint(outpoint.into());
     }
 
     /// List all relevant outputs (includes both spent and unspent, confirmed and unconfirmed).
@@ -897,6 +1139,16 @@ impl Wallet {
         self.get_wallet().list_output().map(|o| o.into()).collect()
     }
 
+    /// Analyze the wallet's on-chain history for privacy leaks: reused addresses, addresses and
+    /// unspent outputs linked by being spent together, change outputs that can be told apart from
+    /// the payment by a round payment amount or by a different script type, and unspent outputs
+    /// of several script types.
+    ///
+    /// Only transactions in the best chain and the mempool are considered.
+    pub fn privacy_report(&self) -> PrivacyReport {
+        privacy::privacy_report(&self.get_wallet())
+    }
+
     /// Create a [`FullScanRequest] for this wallet.
     ///
     /// This is the first step when performing a spk-based wallet full scan, the returned
@@ -945,7 +1197,7 @@ impl Wallet {
     pub fn persist(&self, persister: Arc<Persister>) -> Result<bool, PersistenceError> {
         let mut persist_lock = persister.inner.lock().unwrap();
         let deref = persist_lock.deref_mut();
-        self.get_wallet()
+        self.get_wallet_mut()
             .persist(deref)
             .map_err(|e| PersistenceError::Reason {
                 error_message: e.to_string(),
@@ -961,7 +1213,7 @@ impl Wallet {
 
     /// Take the staged [`ChangeSet`] to be persisted now (if any).
     pub fn take_staged(&self) -> Option<Arc<ChangeSet>> {
-        self.get_wallet()
+        self.get_wallet_mut()
             .take_staged()
             .map(|changeset| Arc::new(changeset.into()))
     }
@@ -997,7 +1249,13 @@ impl Wallet {
 }
 
 impl Wallet {
-    pub(crate) fn get_wallet(&self) -> MutexGuard<'_, PersistedWallet<PersistenceType>> {
-        self.inner_mutex.lock().expect("wallet")
+    /// Acquire shared access to the wallet. Any number of readers may hold this at the same time.
+    pub(crate) fn get_wallet(&self) -> RwLockReadGuard<'_, PersistedWallet<PersistenceType>> {
+        self.inner.read().expect("wallet")
+    }
+
+    /// Acquire exclusive access to the wallet, waiting for all readers to release it.
+    pub(crate) fn get_wallet_mut(&self) -> RwLockWriteGuard<'_, PersistedWallet<PersistenceType>> {
+        self.inner.write().expect("wallet")
     }
 }